@@ -4,7 +4,7 @@
 import { ref, computed, nextTick } from "vue";
 import { invoke } from "@tauri-apps/api/core";
 import type { SpiderParams, SpiderConfig } from "../types";
-import { convertProjectType, formatDateTime } from "../utils/helpers";
+import { projectTypeLabel, formatDateTime } from "../utils/helpers";
 import { sendNotificationWithPermission } from "./useNotification";
 import { useSmtp } from "./useSmtp";
 
@@ -83,7 +83,7 @@ export function useSpider() {
     return {
       page: page.value,
       title: title.value,
-      project_type: convertProjectType(projectType.value)
+      project_type: projectType.value
     };
   }
 
@@ -357,16 +357,11 @@ export function useSpider() {
       if (config.params) {
         page.value = config.params.page || 1;
         title.value = config.params.title || "";
-        const projectTypeMap: Record<string, string> = {
-          "": "全部",
-          "0001": "物资",
-          "0002": "工程"
-        };
-        projectType.value = projectTypeMap[config.params.project_type] || config.params.project_type || "全部";
+        projectType.value = projectTypeLabel(config.params.project_type);
       } else {
         page.value = config.page || 1;
         title.value = config.title || "";
-        projectType.value = config.project_type || "全部";
+        projectType.value = projectTypeLabel(config.project_type);
       }
 
       email.value = config.email || "";
@@ -1,13 +1,16 @@
 /**
  * 工具函数集合
  */
-import { PROJECT_TYPE_MAP } from "./constants";
+import { LEGACY_PROJECT_TYPE_CODES } from "./constants";
 
 /**
- * 项目类型转换
+ * 读取配置中的项目类型，兼容旧配置保存的编码
  */
-export function convertProjectType(type: string): string {
-  return PROJECT_TYPE_MAP[type] || "";
+export function projectTypeLabel(value: string | undefined): string {
+  if (value === undefined) {
+    return "全部";
+  }
+  return LEGACY_PROJECT_TYPE_CODES[value] || value;
 }
 
 /**
@@ -1,3 +1,6 @@
 fn main() {
-    tauri_build::build()
+    // 只编译命令行工具（--no-default-features）时不需要 Tauri 的构建步骤
+    if std::env::var_os("CARGO_FEATURE_DESKTOP").is_some() {
+        tauri_build::build()
+    }
 }
@@ -0,0 +1,369 @@
+// ============================================================================
+// 命令行工具：在没有图形界面的服务器上执行爬虫和定时任务
+// ============================================================================
+//
+// 与桌面应用共用 AppCore，默认读写桌面应用的应用数据目录（可用 --data-dir 指定其他目录），
+// 因此在桌面应用中保存的爬虫设置、SMTP 配置、网络和限速设置在这里同样生效。
+// 编译：cargo build --bin rpa-cli --no-default-features（不需要 Tauri 和 WebView）
+//
+// 子命令：
+//   run              立即执行一次爬虫，结果以 JSON 输出到 stdout
+//   schedule         按已保存的定时任务设置等待、执行并发送邮件
+//   daemon           常驻运行，配置文件变化后重新安排定时任务
+//   send-test-email  发送测试邮件，检查 SMTP 配置
+//   diagnose         诊断运行环境，有失败项时退出码为 1
+//   export           导出本地公告数据
+//   serve            启动本机 HTTP API（http_api.rs），直到收到退出信号
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{Datelike, Local, NaiveDateTime, Timelike};
+use clap::{Parser, Subcommand};
+use serde::de::DeserializeOwned;
+
+use rpa_core::app_core::{AppCore, AppPaths, RunOptions};
+use rpa_core::backend::{BackendChoice, FallbackPolicy};
+use rpa_core::diagnostics::CheckStatus;
+use rpa_core::export::{self, ExportFormat};
+use rpa_core::http_api::{self, ApiServer, ApiSettings};
+use rpa_core::settings::{self, default_spider_id, AppSettings};
+use rpa_core::store::{self, SearchFilters};
+use rpa_core::{log_info, log_warn, logging, scheduler};
+use tauri_lesson_1_lib::IDENTITY;
+
+/// --at 参数的时间格式
+const AT_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Parser)]
+#[command(name = "rpa-cli", version, about = "招标公告爬虫命令行工具")]
+struct Cli {
+    /// 应用数据目录，默认与桌面应用相同
+    #[arg(long, global = true)]
+    data_dir: Option<PathBuf>,
+    /// 资源目录（spiders.py、spider_bin、内置插件所在目录）
+    #[arg(long, global = true)]
+    resource_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// 立即执行一次爬虫，未指定的参数使用已保存的设置
+    Run {
+        /// 爬虫 ID
+        #[arg(long)]
+        spider: Option<String>,
+        /// 爬虫参数（JSON 对象）
+        #[arg(long)]
+        params: Option<String>,
+        /// 抓取新公告的详情页和附件
+        #[arg(long)]
+        fetch_details: bool,
+        /// 执行后端：auto / python / binary
+        #[arg(long, value_parser = parse_enum::<BackendChoice>)]
+        backend: Option<BackendChoice>,
+        /// 首选后端失败时不回退
+        #[arg(long)]
+        no_fallback: bool,
+    },
+    /// 按已保存的定时任务设置执行一次：等到执行时间后执行爬虫并发送邮件
+    Schedule {
+        /// 覆盖执行时间，格式为 "YYYY-MM-DD HH:MM:SS"
+        #[arg(long)]
+        at: Option<String>,
+        /// 覆盖结果发送到的邮箱
+        #[arg(long)]
+        email: Option<String>,
+    },
+    /// 常驻运行：定期读取设置，启用的定时任务变化后重新安排
+    Daemon {
+        /// 检查设置文件的间隔（秒）
+        #[arg(long, default_value_t = 30)]
+        interval: u64,
+    },
+    /// 发送测试邮件
+    SendTestEmail {
+        /// 收件人
+        to: String,
+    },
+    /// 诊断运行环境，结果以 JSON 输出
+    Diagnose,
+    /// 导出本地公告数据
+    Export {
+        /// 导出格式：csv / xlsx / jsonl / markdown
+        #[arg(long, value_parser = parse_enum::<ExportFormat>)]
+        format: ExportFormat,
+        /// 导出文件路径
+        #[arg(long)]
+        output: PathBuf,
+        /// 全文检索关键字
+        #[arg(long)]
+        keyword: Option<String>,
+    },
+    /// 启动本机 HTTP API（只监听 127.0.0.1），令牌为空时自动生成并保存
+    Serve {
+        /// 覆盖已保存的端口
+        #[arg(long)]
+        port: Option<u16>,
+    },
+}
+
+/// 按 serde 的名称解析枚举参数，与设置文件中的写法一致
+fn parse_enum<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_string())).map_err(|e| e.to_string())
+}
+
+fn paths(cli: &Cli) -> Result<AppPaths, String> {
+    let mut paths = AppPaths::for_identifier(IDENTITY.identifier)?;
+    if let Some(data_dir) = &cli.data_dir {
+        paths.log_dir = data_dir.join("logs");
+        paths.app_data_dir = data_dir.clone();
+    }
+    paths.resource_dir = cli.resource_dir.clone();
+    Ok(paths)
+}
+
+/// 读取已保存的设置，没有保存过时返回错误
+fn saved_settings(core: &AppCore) -> Result<AppSettings, String> {
+    settings::load(core.app_data_dir())
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("{:?} 中没有已保存的设置，请先在桌面应用中保存设置", core.app_data_dir()))
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| format!("序列化结果失败: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+async fn run_once(
+    core: Arc<AppCore>,
+    spider: Option<String>,
+    params: Option<String>,
+    fetch_details: bool,
+    backend: Option<BackendChoice>,
+    no_fallback: bool,
+) -> Result<(), String> {
+    // 只有命令行参数不完整时才需要已保存的设置
+    let saved = match (&spider, &params) {
+        (Some(_), Some(_)) => None,
+        _ => settings::load(core.app_data_dir()).map_err(|e| e.to_string())?.map(|s| s.spider),
+    };
+    let spider_id = spider
+        .or_else(|| saved.as_ref().map(|config| config.spider_id.clone()))
+        .unwrap_or_else(default_spider_id);
+    let params = match params {
+        Some(params) => serde_json::from_str(&params).map_err(|e| format!("--params 不是有效的 JSON: {}", e))?,
+        None => saved
+            .as_ref()
+            .map(|config| config.params.clone())
+            .ok_or("未指定 --params，也没有已保存的设置")?,
+    };
+    let mut options = RunOptions {
+        job: store::MANUAL_JOB.to_string(),
+        fetch_details: fetch_details || saved.as_ref().is_some_and(|config| config.fetch_details),
+        backend: saved.as_ref().map(|config| config.backend).unwrap_or_default(),
+    };
+    if let Some(backend) = backend {
+        options.backend.backend = backend;
+    }
+    if no_fallback {
+        options.backend.fallback = Some(FallbackPolicy::Never);
+    }
+
+    let output = tokio::task::spawn_blocking(move || core.run_spider(&spider_id, &options, params))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    print_json(&output)
+}
+
+async fn schedule_once(core: Arc<AppCore>, at: Option<String>, email: Option<String>) -> Result<(), String> {
+    let mut config = saved_settings(&core)?.spider;
+    if let Some(at) = at {
+        let time = NaiveDateTime::parse_from_str(&at, AT_FORMAT)
+            .map_err(|e| format!("--at 的格式应为 \"YYYY-MM-DD HH:MM:SS\": {}", e))?;
+        config.year = time.year() as u32;
+        config.month = time.month();
+        config.day = time.day();
+        config.hour = time.hour();
+        config.minute = time.minute();
+        config.second = time.second();
+    }
+    if let Some(email) = email {
+        config.email = email;
+    }
+    let job = scheduler::plan(&core, &config, core.now()).map_err(|e| e.to_string())?;
+    eprintln!("定时任务将在 {} 执行", job.time_label());
+    scheduler::run(core, job).await
+}
+
+/// 常驻运行：设置文件变化后取消旧任务，按新设置重新安排；任务执行完后等待下一次设置变化
+async fn daemon(core: Arc<AppCore>, interval: u64) -> Result<(), String> {
+    tokio::spawn(core.clone().worker_health_loop());
+    let mut current: Option<serde_json::Value> = None;
+    let mut task: Option<tokio::task::JoinHandle<()>> = None;
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval.max(1)));
+    log_info!("[守护进程] 已启动，每 {} 秒检查一次设置", interval);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = tokio::signal::ctrl_c() => {
+                log_info!("[守护进程] 收到退出信号");
+                return Ok(());
+            }
+        }
+
+        let settings = match settings::load(core.app_data_dir()) {
+            Ok(settings) => settings,
+            Err(e) => {
+                log_warn!("[守护进程] 读取设置失败: {}", e);
+                continue;
+            }
+        };
+        let snapshot = settings.as_ref().and_then(|settings| serde_json::to_value(settings).ok());
+        if snapshot == current {
+            continue;
+        }
+        current = snapshot;
+
+        if let Some(task) = task.take() {
+            if !task.is_finished() {
+                log_info!("[守护进程] 设置已变化，取消之前安排的任务");
+            }
+            task.abort();
+        }
+        let Some(config) = settings.map(|settings| settings.spider).filter(|config| config.enabled) else {
+            log_info!("[守护进程] 定时任务未启用");
+            continue;
+        };
+        match scheduler::plan(&core, &config, core.now()) {
+            Ok(job) => {
+                log_info!("[守护进程] 已安排定时任务: {}", job.time_label());
+                let core = core.clone();
+                task = Some(tokio::spawn(async move {
+                    if let Err(e) = scheduler::run(core, job).await {
+                        log_warn!("[守护进程] 定时任务失败: {}", e);
+                    }
+                }));
+            }
+            Err(e) => log_warn!("[守护进程] 未安排定时任务: {}", e),
+        }
+    }
+}
+
+async fn send_test_email(core: Arc<AppCore>, to: String) -> Result<(), String> {
+    let subject = "测试邮件 - RPA 应用";
+    let body = format!(
+        "这是一封测试邮件，用于验证邮件发送功能是否正常工作。\n\n发送时间: {}\n\n如果您收到这封邮件，说明邮件发送功能正常工作。",
+        Local::now().format(AT_FORMAT)
+    );
+    let message = tokio::task::spawn_blocking(move || core.send_email(&to, subject, &body))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    println!("{}", message);
+    Ok(())
+}
+
+async fn diagnose(core: Arc<AppCore>) -> Result<(), String> {
+    let report = tokio::task::spawn_blocking(move || core.diagnose())
+        .await
+        .map_err(|e| e.to_string())?;
+    print_json(&report)?;
+    if report.status == CheckStatus::Fail {
+        return Err("诊断发现失败项".to_string());
+    }
+    Ok(())
+}
+
+fn export_results(core: &AppCore, format: ExportFormat, output: PathBuf, keyword: Option<String>) -> Result<(), String> {
+    let summary = export::export_announcements(
+        &core.store,
+        &output,
+        format,
+        keyword.as_deref().unwrap_or_default(),
+        &SearchFilters::default(),
+    )?;
+    print_json(&summary)
+}
+
+/// 启动 HTTP API 并保持运行；设置文件中的 enabled 只控制桌面应用是否自动启动，这里总是启动
+async fn serve(core: Arc<AppCore>, port: Option<u16>) -> Result<(), String> {
+    let mut settings = ApiSettings::load(core.app_data_dir())?;
+    if settings.ensure_token() {
+        settings.save(core.app_data_dir())?;
+        log_info!(
+            "[HTTP API] 已生成访问令牌，保存在 {:?}",
+            core.app_data_dir().join(http_api::SETTINGS_FILE)
+        );
+    }
+    settings.enabled = true;
+    if let Some(port) = port {
+        settings.port = port;
+    }
+    let server = ApiServer::default();
+    server.apply(core.clone(), &settings).await?;
+    print_json(&serde_json::json!({
+        "url": format!("http://127.0.0.1:{}/api/v1", settings.port),
+        "openapi": format!("http://127.0.0.1:{}/api/v1/openapi.json", settings.port),
+    }))?;
+
+    tokio::spawn(core.worker_health_loop());
+    let _ = tokio::signal::ctrl_c().await;
+    log_info!("[HTTP API] 收到退出信号");
+    server.stop().await;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    // stdout 只输出结果（JSON），日志输出到 stderr
+    logging::set_console_stderr(true);
+    let paths = match paths(&cli) {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = logging::init(&paths.log_dir) {
+        eprintln!("警告: {}，日志只输出到控制台", e);
+    }
+    // 命令行工具没有界面，事件只写入日志
+    let core = match AppCore::open(IDENTITY, paths, |event, payload| log_info!("[事件] {}: {}", event, payload)) {
+        Ok(core) => Arc::new(core),
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = match cli.command {
+        Commands::Run { spider, params, fetch_details, backend, no_fallback } => {
+            run_once(core.clone(), spider, params, fetch_details, backend, no_fallback).await
+        }
+        Commands::Schedule { at, email } => schedule_once(core.clone(), at, email).await,
+        Commands::Daemon { interval } => daemon(core.clone(), interval).await,
+        Commands::SendTestEmail { to } => send_test_email(core.clone(), to).await,
+        Commands::Diagnose => diagnose(core.clone()).await,
+        Commands::Export { format, output, keyword } => export_results(&core, format, output, keyword),
+        Commands::Serve { port } => serve(core.clone(), port).await,
+    };
+    // 退出前关闭常驻 worker，避免遗留 Python 子进程
+    core.worker.shutdown();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
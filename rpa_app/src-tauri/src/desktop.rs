@@ -0,0 +1,881 @@
+// ============================================================================
+// 桌面应用（Tauri）
+// ============================================================================
+//
+// Tauri 命令只做参数转换和线程切换，爬虫执行、邮件发送、定时任务和配置读取都在 AppCore
+// （app_core.rs）中，命令行工具使用同一套逻辑。AppCore 在 setup 中创建，以 Arc<AppCore>
+// 放进 managed state
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::Local;
+use serde::Serialize;
+use tauri::menu::{Menu, MenuItem, Submenu};
+use tauri::{Emitter, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+use rpa_core::app_core::{AppCore, AppPaths, RunOptions};
+use rpa_core::backend::BackendOptions;
+use rpa_core::candidate_extract;
+use rpa_core::config_file::{self, BackupInfo};
+use rpa_core::crawl_policy::{self, CrawlPolicy, CrawlStatus};
+use rpa_core::diagnostics::DiagnosticReport;
+use rpa_core::error::{AppError, AppResult, ErrorKind};
+use rpa_core::export::{self, ExportFormat, ExportSummary};
+use rpa_core::http_api::{ApiServer, ApiSettings};
+use rpa_core::i18n::{self, LocaleSettings};
+use rpa_core::logging::{self, LogQuery, LogRecord};
+use rpa_core::network::{self, NetworkSettings, ProbeResult};
+use rpa_core::profile::{self, ImportReport, ProfileExportSummary};
+use rpa_core::redact::{self, RedactionSettings};
+use rpa_core::runtime::{self, RuntimeReport, RuntimeSettings};
+use rpa_core::scheduler::{self, ScheduleInfo};
+use rpa_core::settings::{self, default_spider_id, SmtpConfig, SpiderConfig};
+use rpa_core::spider_params::FieldError;
+use rpa_core::spider_registry::{SpiderPlugin, SpiderRunOutput};
+use rpa_core::spider_worker::WorkerStatus;
+use rpa_core::store::{self, Announcement, RunRecord, SearchFilters, SearchPage};
+use rpa_core::venv::{self, EnvStatus, SetupInput};
+use rpa_core::{log_info, log_warn};
+
+use crate::IDENTITY;
+
+/// 桌面应用共用的核心状态
+fn core(app_handle: &tauri::AppHandle) -> Arc<AppCore> {
+    app_handle.state::<Arc<AppCore>>().inner().clone()
+}
+
+fn app_data_dir(app_handle: &tauri::AppHandle) -> PathBuf {
+    core(app_handle).paths.app_data_dir.clone()
+}
+
+/// Tauri 命令：保存设置。前端传入 JSON 字符串，按当前版本的设置文档解析和校验后写入
+#[tauri::command]
+fn save_note(app_handle: tauri::AppHandle, content: String) -> AppResult<String> {
+    let app_data_dir = app_data_dir(&app_handle);
+    
+    let document: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| AppError::invalid_input("settings.invalid_json", "配置不是有效的 JSON").detail(e.to_string()))?;
+    // 前端可能传入未带版本号的旧格式，统一升级到当前版本
+    let settings = settings::migrate(document)
+        .map_err(|e| AppError::invalid_input("settings.invalid", e))?
+        .settings;
+    
+    let mut errors = settings.validate();
+    match core(&app_handle).registry.get(&settings.spider.spider_id) {
+        Ok(plugin) => errors.extend(AppCore::check_spider_params(&plugin, &settings.spider.params)),
+        Err(e) => errors.push(FieldError::new("spider_id", "enum", e)),
+    }
+    if !errors.is_empty() {
+        return Err(AppError::validation(errors));
+    }
+    
+    settings::write(&app_data_dir, &settings).map_err(AppError::config)?;
+    Ok("配置保存成功".to_string())
+}
+
+/// Tauri 命令：加载设置，返回当前版本的设置 JSON；没有保存过设置时返回空字符串
+#[tauri::command]
+fn load_note(app_handle: tauri::AppHandle) -> AppResult<String> {
+    let app_data_dir = app_data_dir(&app_handle);
+    match settings::load(&app_data_dir)? {
+        Some(settings) => serde_json::to_string_pretty(&settings)
+            .map_err(|e| AppError::from(format!("序列化设置失败: {}", e))),
+        None => Ok(String::new()),
+    }
+}
+
+#[tauri::command]
+fn save_smtp_config(app_handle: tauri::AppHandle, config: SmtpConfig) -> AppResult<String> {
+    let app_data_dir = app_data_dir(&app_handle);
+    config.save(&app_data_dir).map_err(AppError::config)?;
+    redact::register_secret(&config.password);
+    Ok("SMTP 配置保存成功".to_string())
+}
+
+/// Tauri 命令：加载 SMTP 配置。没有保存过时返回默认的 QQ 邮箱配置，文件损坏时返回错误
+#[tauri::command]
+fn load_smtp_config(app_handle: tauri::AppHandle) -> AppResult<SmtpConfig> {
+    SmtpConfig::load(&app_data_dir(&app_handle))
+}
+
+/// 可以列出和恢复历史版本的配置文件
+const BACKED_UP_FILES: [&str; 5] = [
+    settings::SETTINGS_FILE,
+    settings::SMTP_FILE,
+    network::SETTINGS_FILE,
+    crawl_policy::SETTINGS_FILE,
+    runtime::SETTINGS_FILE,
+];
+
+fn check_backed_up_file(file: &str) -> AppResult<()> {
+    if BACKED_UP_FILES.contains(&file) {
+        Ok(())
+    } else {
+        Err(AppError::invalid_input("config.unsupported_file", format!("不支持的配置文件: {}", file)))
+    }
+}
+
+/// Tauri 命令：列出配置文件的历史版本（最新的在前）
+#[tauri::command]
+fn list_config_backups(app_handle: tauri::AppHandle, file: String) -> AppResult<Vec<BackupInfo>> {
+    check_backed_up_file(&file)?;
+    let app_data_dir = app_data_dir(&app_handle);
+    config_file::list_backups(&app_data_dir, &file).map_err(AppError::config)
+}
+
+/// Tauri 命令：把配置文件恢复为某个历史版本，恢复前的文件（如果有效）会成为新的历史版本
+#[tauri::command]
+fn restore_config_backup(app_handle: tauri::AppHandle, file: String, backup: String) -> AppResult<String> {
+    check_backed_up_file(&file)?;
+    let app_data_dir = app_data_dir(&app_handle);
+    config_file::restore_backup(&app_data_dir, &file, &backup).map_err(AppError::config)?;
+    if file == crawl_policy::SETTINGS_FILE {
+        core(&app_handle).limiter.set_policy(CrawlPolicy::load(&app_data_dir)?);
+    } else if file == runtime::SETTINGS_FILE {
+        core(&app_handle).resolver.set_settings(RuntimeSettings::load(&app_data_dir)?);
+    }
+    log_info!("[配置] 已将 {} 恢复为 {}", file, backup);
+    Ok(format!("{} 已恢复", file))
+}
+
+#[tauri::command]
+fn save_redaction_settings(app_handle: tauri::AppHandle, settings: RedactionSettings) -> AppResult<String> {
+    let app_data_dir = app_data_dir(&app_handle);
+    settings.save(&app_data_dir).map_err(AppError::config)?;
+    redact::configure(settings);
+    Ok("脱敏设置保存成功".to_string())
+}
+
+#[tauri::command]
+fn load_redaction_settings(app_handle: tauri::AppHandle) -> AppResult<RedactionSettings> {
+    let app_data_dir = app_data_dir(&app_handle);
+    RedactionSettings::load(&app_data_dir)
+}
+
+#[tauri::command]
+fn save_locale_settings(app_handle: tauri::AppHandle, settings: LocaleSettings) -> AppResult<String> {
+    let app_data_dir = app_data_dir(&app_handle);
+    settings.save(&app_data_dir).map_err(AppError::config)?;
+    Ok("语言设置保存成功".to_string())
+}
+
+#[tauri::command]
+fn load_locale_settings(app_handle: tauri::AppHandle) -> AppResult<LocaleSettings> {
+    let app_data_dir = app_data_dir(&app_handle);
+    LocaleSettings::load(&app_data_dir)
+}
+
+/// Tauri 命令：前端通知使用的消息目录（当前语言）
+#[tauri::command]
+fn message_catalog(app_handle: tauri::AppHandle) -> std::collections::BTreeMap<&'static str, &'static str> {
+    i18n::frontend_catalog(core(&app_handle).locale())
+}
+
+
+/// Tauri 命令：最近的日志，lines 默认 200 条
+#[tauri::command]
+async fn tail_logs(app_handle: tauri::AppHandle, lines: Option<usize>) -> AppResult<Vec<LogRecord>> {
+    let log_dir = core(&app_handle).paths.log_dir.clone();
+    tokio::task::spawn_blocking(move || logging::tail(&log_dir, lines.unwrap_or(200)))
+        .await
+        .map_err(AppError::task)
+}
+
+/// Tauri 命令：按最低级别、run_id 和起始时间查询日志
+#[tauri::command]
+async fn query_logs(app_handle: tauri::AppHandle, query: LogQuery) -> AppResult<Vec<LogRecord>> {
+    let log_dir = core(&app_handle).paths.log_dir.clone();
+    tokio::task::spawn_blocking(move || logging::query(&log_dir, &query))
+        .await
+        .map_err(AppError::task)?
+        .map_err(|e| AppError::invalid_input("logs.invalid_query", e))
+}
+
+#[tauri::command]
+fn save_network_settings(app_handle: tauri::AppHandle, mut settings: NetworkSettings) -> AppResult<String> {
+    settings.validate().map_err(|e| AppError::invalid_input("network.invalid", e))?;
+    let app_data_dir = app_data_dir(&app_handle);
+    settings.keep_stored_password(&app_data_dir)?;
+    settings.save(&app_data_dir).map_err(AppError::config)?;
+    redact::register_secret(&settings.proxy_password);
+    Ok("网络设置保存成功".to_string())
+}
+
+#[tauri::command]
+fn load_network_settings(app_handle: tauri::AppHandle) -> AppResult<NetworkSettings> {
+    let app_data_dir = app_data_dir(&app_handle);
+    NetworkSettings::load(&app_data_dir).map(NetworkSettings::without_password)
+}
+
+#[tauri::command]
+fn save_crawl_policy(app_handle: tauri::AppHandle, policy: CrawlPolicy) -> AppResult<String> {
+    policy.validate().map_err(|e| AppError::invalid_input("crawl_policy.invalid", e))?;
+    let app_data_dir = app_data_dir(&app_handle);
+    policy.save(&app_data_dir).map_err(AppError::config)?;
+    core(&app_handle).limiter.set_policy(policy);
+    Ok("限速设置保存成功".to_string())
+}
+
+#[tauri::command]
+fn load_crawl_policy(app_handle: tauri::AppHandle) -> AppResult<CrawlPolicy> {
+    let app_data_dir = app_data_dir(&app_handle);
+    CrawlPolicy::load(&app_data_dir)
+}
+
+#[tauri::command]
+fn load_api_settings(app_handle: tauri::AppHandle) -> AppResult<ApiSettings> {
+    ApiSettings::load(&app_data_dir(&app_handle))
+}
+
+/// Tauri 命令：保存本机 HTTP API 设置并按新设置重启服务；令牌为空时自动生成，返回保存后的设置
+#[tauri::command]
+async fn save_api_settings(app_handle: tauri::AppHandle, mut settings: ApiSettings) -> AppResult<ApiSettings> {
+    settings.validate().map_err(|e| AppError::invalid_input("api.invalid_settings", e))?;
+    settings.ensure_token();
+    let core = core(&app_handle);
+    settings.save(core.app_data_dir()).map_err(AppError::config)?;
+    app_handle.state::<ApiServer>().apply(core, &settings).await?;
+    Ok(settings)
+}
+
+/// Tauri 命令：查询限速器状态（运行中/排队数量、退避剩余时间）
+#[tauri::command]
+fn crawl_status(app_handle: tauri::AppHandle) -> CrawlStatus {
+    core(&app_handle).limiter.status()
+}
+
+#[derive(Serialize, Debug)]
+pub struct NetworkTestReport {
+    pub proxy: Option<String>,
+    pub http: ProbeResult,
+    pub smtp: ProbeResult,
+}
+
+/// Tauri 命令：按当前（或传入的未保存）网络设置检测目标网站和 SMTP 服务器的连通性
+#[tauri::command]
+async fn test_network_connectivity(
+    app_handle: tauri::AppHandle,
+    settings: Option<NetworkSettings>,
+    url: Option<String>,
+) -> AppResult<NetworkTestReport> {
+    let settings = match settings {
+        Some(mut settings) => {
+            settings.keep_stored_password(core(&app_handle).app_data_dir())?;
+            settings
+        }
+        None => core(&app_handle).network_settings(),
+    };
+    settings.validate().map_err(|e| AppError::invalid_input("network.invalid", e))?;
+    let smtp_config = core(&app_handle).smtp_config();
+    let url = url.unwrap_or_else(|| "https://www.cnpcbidding.com/".to_string());
+    
+    let result = tokio::task::spawn_blocking(move || {
+        let mut proxy = settings.clone();
+        proxy.proxy_password = "******".to_string();
+        NetworkTestReport {
+            proxy: proxy.proxy_url(),
+            http: network::probe_http(&settings, &url),
+            smtp: network::probe_smtp(&settings, &smtp_config.server, smtp_config.port),
+        }
+    }).await;
+    
+    result.map_err(AppError::task)
+}
+
+/// Tauri 命令：查看 Python、爬虫脚本和打包可执行文件的解析结果，包括每个尝试过的路径及被拒绝的原因
+#[tauri::command]
+async fn runtime_status(app_handle: tauri::AppHandle) -> AppResult<RuntimeReport> {
+    tokio::task::spawn_blocking(move || core(&app_handle).resolver.report())
+        .await
+        .map_err(AppError::task)
+}
+
+/// Tauri 命令：重新查找运行环境（安装 Python 或移动文件后使用）
+#[tauri::command]
+async fn resolve_runtime(app_handle: tauri::AppHandle) -> AppResult<RuntimeReport> {
+    tokio::task::spawn_blocking(move || core(&app_handle).resolver.resolve())
+        .await
+        .map_err(AppError::task)
+}
+
+#[tauri::command]
+fn load_runtime_settings(app_handle: tauri::AppHandle) -> RuntimeSettings {
+    core(&app_handle).resolver.settings()
+}
+
+/// Tauri 命令：保存用户指定的 Python / 脚本 / 可执行文件路径（留空为自动查找），并重新解析
+#[tauri::command]
+async fn save_runtime_settings(app_handle: tauri::AppHandle, settings: RuntimeSettings) -> AppResult<RuntimeReport> {
+    let app_data_dir = app_data_dir(&app_handle);
+    settings.save(&app_data_dir).map_err(AppError::config)?;
+    tokio::task::spawn_blocking(move || core(&app_handle).resolver.set_settings(settings))
+        .await
+        .map_err(AppError::task)
+}
+
+/// Tauri 命令：诊断整条工具链（Python 及依赖包、Node.js、spider_bin、资源文件、应用数据目录、SMTP），
+/// 每一项返回 pass / warn / fail 和处理建议
+#[tauri::command]
+async fn diagnose_environment(app_handle: tauri::AppHandle) -> AppResult<DiagnosticReport> {
+    let core = core(&app_handle);
+    tokio::task::spawn_blocking(move || core.diagnose())
+        .await
+        .map_err(AppError::task)
+}
+
+/// Tauri 命令：查看私有 Python 环境的状态（是否安装完成、依赖清单是否有变化、可用的 wheel 数量）
+#[tauri::command]
+fn python_env_status(app_handle: tauri::AppHandle) -> AppResult<EnvStatus> {
+    let app_data_dir = app_data_dir(&app_handle);
+    let script = core(&app_handle).resolver.script().ok();
+    Ok(venv::status(&app_data_dir, script.as_deref().and_then(|p| p.parent())))
+}
+
+/// Tauri 命令：创建或更新爬虫专用的私有 Python 环境，安装过程通过 python-env-progress 事件报告进度。
+/// force 为 true 时删除现有环境后重建
+#[tauri::command]
+async fn setup_python_env(app_handle: tauri::AppHandle, force: Option<bool>) -> AppResult<EnvStatus> {
+    let core = core(&app_handle);
+    tokio::task::spawn_blocking(move || {
+        let _guard = core.setup_lock.try_begin()?;
+        let resolver = &core.resolver;
+        let system_python = resolver.system_python();
+        let base_python = system_python
+            .path
+            .clone()
+            .ok_or_else(|| system_python.describe_failure("用于创建私有环境的 Python 3"))?;
+        let script_path = resolver.script()?;
+        let script_dir = script_path.parent().ok_or("无法获取脚本目录")?;
+
+        let status = venv::setup(
+            &SetupInput {
+                app_data_dir: core.app_data_dir(),
+                base_python: &base_python,
+                script_dir,
+                network: &core.network_settings(),
+                force: force.unwrap_or(false),
+            },
+            |progress| {
+                core.emit("python-env-progress", &progress);
+            },
+        )?;
+        // 重新解析后爬虫改用私有环境；关闭旧的 worker，下次调用时用新解释器启动
+        resolver.resolve();
+        core.worker.shutdown();
+        Ok::<_, String>(status)
+    })
+    .await
+    .map_err(AppError::task)?
+    .map_err(|e| AppError::new(ErrorKind::Environment, "environment.setup_failed", "安装私有 Python 环境失败").detail(e))
+}
+
+/// Tauri 命令：列出所有可用的爬虫插件
+#[tauri::command]
+fn list_spiders(app_handle: tauri::AppHandle) -> Vec<SpiderPlugin> {
+    core(&app_handle).registry.list()
+}
+
+/// Tauri 命令：校验爬虫参数，返回字段级错误（为空表示校验通过）
+#[tauri::command]
+fn validate_spider_params(app_handle: tauri::AppHandle, spider_id: Option<String>, params: serde_json::Value) -> AppResult<Vec<FieldError>> {
+    let spider_id = spider_id.unwrap_or_else(default_spider_id);
+    let plugin = core(&app_handle)
+        .registry
+        .get(&spider_id)
+        .map_err(|e| AppError::invalid_input("spider.unknown", e))?;
+    Ok(AppCore::check_spider_params(&plugin, &params))
+}
+
+/// Tauri 命令：重新扫描插件目录
+#[tauri::command]
+fn reload_spiders(app_handle: tauri::AppHandle) -> Vec<SpiderPlugin> {
+    let core = core(&app_handle);
+    let count = core.reload_plugins();
+    log_info!("[插件] 已加载 {} 个爬虫插件", count);
+    core.registry.list()
+}
+
+#[tauri::command]
+async fn send_email(app_handle: tauri::AppHandle, to: String, subject: String, body: String) -> AppResult<String> {
+    // 在后台线程执行邮件发送，避免阻塞主线程
+    let core = core(&app_handle);
+    let result = tokio::task::spawn_blocking(move || {
+        core.send_email(&to, &subject, &body)
+    }).await;
+    
+    match result {
+        Ok(res) => res,
+        Err(e) => Err(AppError::task(e)),
+    }
+}
+
+#[tauri::command]
+async fn start_scheduled_spider(app_handle: tauri::AppHandle, config: SpiderConfig) -> AppResult<String> {
+    let core = core(&app_handle);
+    let now = core.now();
+    let job = scheduler::plan(&core, &config, now)?;
+    
+    log_info!("[定时任务] ========== 准备启动定时任务 ==========");
+    log_info!("[定时任务] 当前时间: {}", now.format("%Y-%m-%d %H:%M:%S"));
+    log_info!("[定时任务] 执行时间: {}", job.time_label());
+    log_info!("[定时任务] 爬虫: {}，参数: {}", job.spider_id, job.params);
+    log_info!("[定时任务] 邮箱: {}", job.email);
+    
+    let started = i18n::format(core.locale(), "schedule.started", &[
+        ("time", &job.time_label()),
+        ("email", &config.email),
+    ]);
+    scheduler::start(&core, job);
+    Ok(started)
+}
+
+/// Tauri 命令：列出已登记、尚未执行完的定时任务
+#[tauri::command]
+fn list_scheduled_tasks(app_handle: tauri::AppHandle) -> Vec<ScheduleInfo> {
+    core(&app_handle).schedules.list()
+}
+
+/// Tauri 命令：取消定时任务
+#[tauri::command]
+fn cancel_scheduled_task(app_handle: tauri::AppHandle, id: String) -> AppResult<String> {
+    if !core(&app_handle).schedules.cancel(&id) {
+        return Err(AppError::not_found("schedule", format!("未找到定时任务: {}", id)));
+    }
+    Ok("定时任务已取消".to_string())
+}
+
+/// Tauri 命令：最近的爬虫执行历史，limit 默认 50
+#[tauri::command]
+async fn list_runs(app_handle: tauri::AppHandle, limit: Option<u32>) -> AppResult<Vec<RunRecord>> {
+    let result = tokio::task::spawn_blocking(move || {
+        core(&app_handle).store.list_runs(limit.unwrap_or(50))
+    }).await;
+
+    match result {
+        Ok(res) => Ok(res?),
+        Err(e) => Err(AppError::task(e)),
+    }
+}
+
+/// Tauri 命令：执行爬虫，spider_id 为空时使用内置的中国石油招标投标网爬虫；
+/// fetch_details 为 true 时抓取新公告的详情页和附件；backend 指定内置爬虫的执行后端和回退策略（默认 auto）
+#[tauri::command]
+async fn run_spider(
+    app_handle: tauri::AppHandle,
+    spider_id: Option<String>,
+    params: serde_json::Value,
+    fetch_details: Option<bool>,
+    backend: Option<BackendOptions>,
+) -> AppResult<SpiderRunOutput> {
+    let spider_id = spider_id.unwrap_or_else(default_spider_id);
+    // 在后台线程执行，避免阻塞主线程导致 UI 卡死
+    let core = core(&app_handle);
+    let result = tokio::task::spawn_blocking(move || {
+        let options = RunOptions {
+            job: store::MANUAL_JOB.to_string(),
+            fetch_details: fetch_details.unwrap_or(false),
+            backend: backend.unwrap_or_default(),
+        };
+        core.run_spider(&spider_id, &options, params)
+    }).await;
+    
+    match result {
+        Ok(res) => res,
+        Err(e) => Err(AppError::task(e)),
+    }
+}
+
+/// Tauri 命令：检索本地公告数据库，query 为空时按发布日期倒序列出
+#[tauri::command]
+async fn search_announcements(
+    app_handle: tauri::AppHandle,
+    query: Option<String>,
+    filters: Option<SearchFilters>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> AppResult<SearchPage> {
+    let result = tokio::task::spawn_blocking(move || {
+        core(&app_handle).store.search(
+            query.as_deref().unwrap_or_default(),
+            &filters.unwrap_or_default(),
+            page.unwrap_or(1),
+            page_size.unwrap_or(20),
+        )
+    }).await;
+    
+    match result {
+        Ok(res) => Ok(res?),
+        Err(e) => Err(AppError::task(e)),
+    }
+}
+
+/// Tauri 命令：读取一条公告的完整内容（含原始数据）
+#[tauri::command]
+fn get_announcement(app_handle: tauri::AppHandle, id: i64) -> AppResult<Announcement> {
+    core(&app_handle)
+        .store
+        .get_announcement(id)?
+        .ok_or_else(|| AppError::not_found("announcement", format!("未找到公告: {}", id)))
+}
+
+/// Tauri 命令：（重新）抓取一条公告的详情页和附件
+#[tauri::command]
+async fn fetch_announcement_detail(app_handle: tauri::AppHandle, id: i64) -> AppResult<Announcement> {
+    let core = core(&app_handle);
+    let result = tokio::task::spawn_blocking(move || {
+        let store = &core.store;
+        let announcement = store.get_announcement(id)?.ok_or_else(|| format!("未找到公告: {}", id))?;
+        let plugin = core.registry.get(&announcement.spider_id)?;
+        if announcement.detail_url.is_none() {
+            return Err("该公告没有详情页地址".to_string());
+        }
+        if core.fetch_details(&plugin, &[id]) == 0 {
+            let announcement = store.get_announcement(id)?;
+            return Err(announcement
+                .and_then(|a| a.detail_error)
+                .unwrap_or_else(|| "详情抓取失败".to_string()));
+        }
+        store.get_announcement(id)?.ok_or_else(|| format!("未找到公告: {}", id))
+    }).await;
+    
+    match result {
+        Ok(res) => Ok(res?),
+        Err(e) => Err(AppError::task(e)),
+    }
+}
+
+/// Tauri 命令：对已保存的公告正文重新提取中标候选人信息（例如提取规则更新后）
+#[tauri::command]
+fn extract_candidate_notice(app_handle: tauri::AppHandle, id: i64) -> AppResult<Option<candidate_extract::CandidateNotice>> {
+    let core = core(&app_handle);
+    let store = &core.store;
+    let announcement = store.get_announcement(id)?.ok_or_else(|| format!("未找到公告: {}", id))?;
+    let notice = candidate_extract::extract_candidate_notice(&announcement.content);
+    store.save_candidate_notice(id, notice.as_ref())?;
+    Ok(notice)
+}
+
+/// Tauri 命令：导出本地公告数据；未传入 path 时弹出保存对话框，用户取消时返回 None
+#[tauri::command]
+async fn export_results(
+    app_handle: tauri::AppHandle,
+    format: ExportFormat,
+    keyword: Option<String>,
+    filters: Option<SearchFilters>,
+    path: Option<String>,
+) -> AppResult<Option<ExportSummary>> {
+    let result = tokio::task::spawn_blocking(move || {
+        let path = match path {
+            Some(path) => std::path::PathBuf::from(path),
+            None => {
+                let file_name = format!("公告导出_{}.{}", Local::now().format("%Y%m%d_%H%M%S"), format.extension());
+                let selected = app_handle
+                    .dialog()
+                    .file()
+                    .add_filter(format.filter_name(), &[format.extension()])
+                    .set_file_name(file_name)
+                    .blocking_save_file();
+                match selected {
+                    Some(path) => path.into_path().map_err(|e| format!("无效的保存路径: {}", e))?,
+                    None => return Ok(None),
+                }
+            }
+        };
+        
+        log_info!("[导出] 导出 {} 到 {:?}", format.extension(), path);
+        let summary = export::export_announcements(
+            &core(&app_handle).store,
+            &path,
+            format,
+            keyword.as_deref().unwrap_or_default(),
+            &filters.unwrap_or_default(),
+        )?;
+        log_info!("[导出] ✅ 已导出 {} 条公告", summary.rows);
+        Ok(Some(summary))
+    }).await;
+    
+    match result {
+        Ok(res) => Ok(res?),
+        Err(e) => Err(AppError::task(e)),
+    }
+}
+
+/// Tauri 命令：把爬虫配置、定时任务、限速规则、网络和 SMTP 设置以及用户插件导出为一个档案文件。
+/// 提供 passphrase 时 SMTP 密码和代理密码加密后一并导出，否则不导出密码。
+/// 未指定 path 时弹出保存对话框，用户取消时返回 None
+#[tauri::command]
+async fn export_profile(
+    app_handle: tauri::AppHandle,
+    passphrase: Option<String>,
+    path: Option<String>,
+) -> AppResult<Option<ProfileExportSummary>> {
+    let result = tokio::task::spawn_blocking(move || {
+        let app_data_dir = app_data_dir(&app_handle);
+        let path = match path {
+            Some(path) => std::path::PathBuf::from(path),
+            None => {
+                let file_name = format!("配置档案_{}.{}", Local::now().format("%Y%m%d_%H%M%S"), profile::PROFILE_EXTENSION);
+                let selected = app_handle
+                    .dialog()
+                    .file()
+                    .add_filter("配置档案", &[profile::PROFILE_EXTENSION])
+                    .set_file_name(file_name)
+                    .blocking_save_file();
+                match selected {
+                    Some(path) => path.into_path().map_err(|e| format!("无效的保存路径: {}", e))?,
+                    None => return Ok(None),
+                }
+            }
+        };
+        
+        let summary = profile::export_profile(&app_data_dir, &path, passphrase.as_deref())?;
+        log_info!("[档案] ✅ 已导出 {} 个文件到 {:?}", summary.entries.len(), summary.path);
+        Ok(Some(summary))
+    }).await;
+    
+    match result {
+        Ok(res) => Ok(res?),
+        Err(e) => Err(AppError::task(e)),
+    }
+}
+
+/// Tauri 命令：导入配置档案。dry_run 为 true 时只预览哪些文件会被新建或覆盖；
+/// 确认后用预览结果中的 path 再调用一次（dry_run = false）完成导入。
+/// 未指定 path 时弹出打开文件对话框，用户取消时返回 None
+#[tauri::command]
+async fn import_profile(
+    app_handle: tauri::AppHandle,
+    path: Option<String>,
+    passphrase: Option<String>,
+    dry_run: bool,
+) -> AppResult<Option<ImportReport>> {
+    let result = tokio::task::spawn_blocking(move || {
+        let app_data_dir = app_data_dir(&app_handle);
+        let path = match path {
+            Some(path) => std::path::PathBuf::from(path),
+            None => {
+                let selected = app_handle
+                    .dialog()
+                    .file()
+                    .add_filter("配置档案", &[profile::PROFILE_EXTENSION])
+                    .blocking_pick_file();
+                match selected {
+                    Some(path) => path.into_path().map_err(|e| format!("无效的文件路径: {}", e))?,
+                    None => return Ok(None),
+                }
+            }
+        };
+        
+        let report = profile::import_profile(&app_data_dir, &path, passphrase.as_deref(), dry_run)?;
+        if !dry_run {
+            // 让导入的插件和限速规则立即生效
+            let core = core(&app_handle);
+            core.reload_plugins();
+            core.limiter.set_policy(CrawlPolicy::load(&app_data_dir)?);
+            log_info!("[档案] ✅ 已从 {:?} 导入配置", path);
+        }
+        Ok(Some(report))
+    }).await;
+    
+    match result {
+        Ok(res) => Ok(res?),
+        Err(e) => Err(AppError::task(e)),
+    }
+}
+
+/// Tauri 命令：查询常驻爬虫 worker 状态
+#[tauri::command]
+fn spider_worker_status(app_handle: tauri::AppHandle) -> WorkerStatus {
+    core(&app_handle).worker.status()
+}
+
+/// Tauri 命令：重启常驻爬虫 worker
+#[tauri::command]
+async fn restart_spider_worker(app_handle: tauri::AppHandle) -> AppResult<WorkerStatus> {
+    let core = core(&app_handle);
+    let result = tokio::task::spawn_blocking(move || {
+        let command = core.worker_command()?;
+        core.worker.restart(&command)?;
+        Ok(core.worker.status())
+    }).await;
+    
+    match result {
+        Ok(res) => Ok(res?),
+        Err(e) => Err(AppError::task(e)),
+    }
+}
+
+/// Tauri 命令：运行自动化任务（demo 版本）
+/// 通过 stdin 发送 JSON 数据给 Python 脚本，从 stdout 接收结果
+#[tauri::command]
+async fn run_automation(
+    app_handle: tauri::AppHandle,
+    url: String,
+    action: String,
+    output: Option<String>,
+) -> AppResult<String> {
+    // 在后台线程执行，避免阻塞主线程
+    let core = core(&app_handle);
+    let result = tokio::task::spawn_blocking(move || {
+        core.run_automation(&url, &action, output.as_deref())
+    }).await;
+    
+    match result {
+        Ok(res) => res,
+        Err(e) => Err(AppError::task(e)),
+    }
+}
+
+/// Tauri 命令：检测 Python 环境（demo 版本）
+#[tauri::command]
+fn check_python_env(app_handle: tauri::AppHandle) -> AppResult<serde_json::Value> {
+    core(&app_handle).check_python_env()
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        .menu(|app| {
+            let about = MenuItem::with_id(app, "about", "关于本应用", true, None::<&str>)?;
+            let quit = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
+            let app_submenu = Submenu::with_items(app, "应用", true, &[&about, &quit])?;
+            Menu::with_items(app, &[&app_submenu])
+        })
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
+            // 先启动日志，后面的初始化日志都写入文件
+            let app_data_dir = app.path().app_data_dir().or_else(|e| {
+                log_warn!("警告：无法获取应用数据目录: {}，使用当前目录", e);
+                std::env::current_dir().map_err(|e| format!("无法获取当前目录: {}", e))
+            })?;
+            let log_dir = match app.path().app_log_dir() {
+                Ok(dir) => dir,
+                Err(e) => {
+                    log_warn!("[日志] 获取日志目录失败: {}，使用应用数据目录", e);
+                    app_data_dir.join("logs")
+                }
+            };
+            match logging::init(&log_dir) {
+                Ok(()) => log_info!("[日志] 日志目录: {:?}", log_dir),
+                Err(e) => log_warn!("[日志] {}，日志只输出到控制台", e),
+            }
+            // 日志消息在 logging::write 中已脱敏
+            let log_handle = app.handle().clone();
+            logging::set_listener(move |record| {
+                let _ = log_handle.emit("log-line", record);
+            });
+            let paths = AppPaths {
+                app_data_dir,
+                log_dir,
+                resource_dir: app.path().resource_dir().ok(),
+            };
+            let event_handle = app.handle().clone();
+            let core = Arc::new(AppCore::open(IDENTITY, paths, move |event, payload| {
+                let _ = event_handle.emit(event, payload);
+            })?);
+            app.manage(core.clone());
+            tauri::async_runtime::spawn(core.clone().worker_health_loop());
+
+            // 本机 HTTP API 默认关闭，开启后随应用启动
+            app.manage(ApiServer::default());
+            match ApiSettings::load(core.app_data_dir()) {
+                Ok(settings) if settings.enabled => {
+                    let app_handle = app.handle().clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = app_handle.state::<ApiServer>().apply(core, &settings).await {
+                            log_warn!("[HTTP API] 启动失败: {}", e);
+                        }
+                    });
+                }
+                Ok(_) => {}
+                Err(e) => log_warn!("[HTTP API] {}，不启动 HTTP API", e),
+            }
+            Ok(())
+        })
+        .on_menu_event(|app, event| {
+            let id = event.id();
+            match id.as_ref() {
+                "quit" => {
+                    if let Some(window) = app.get_webview_window("main") {
+                        window.close().unwrap();
+                    }
+                }
+                "about" => {
+                    if let Some(window) = app.get_webview_window("main") {
+                        let dialog = window.dialog().clone();
+                        tauri_plugin_dialog::MessageDialogBuilder::new(
+                            dialog,
+                            "关于",
+                            "这是我的 Tauri 学习项目 demo：\n- Vue + TypeScript 前端\n- Rust 后端\n- 支持文件读写 / 系统菜单 / 通知"
+                        )
+                        .show(|_| {});
+                    }
+                }
+                _ => {}
+            }
+        })
+        .invoke_handler(tauri::generate_handler![
+            save_note,
+            load_note,
+            run_spider,
+            send_email,
+            start_scheduled_spider,
+            list_scheduled_tasks,
+            cancel_scheduled_task,
+            list_runs,
+            save_smtp_config,
+            load_smtp_config,
+            run_automation,
+            check_python_env,
+            spider_worker_status,
+            restart_spider_worker,
+            list_spiders,
+            reload_spiders,
+            validate_spider_params,
+            save_locale_settings,
+            load_locale_settings,
+            message_catalog,
+            save_redaction_settings,
+            load_redaction_settings,
+            tail_logs,
+            query_logs,
+            save_network_settings,
+            load_network_settings,
+            test_network_connectivity,
+            save_crawl_policy,
+            load_crawl_policy,
+            crawl_status,
+            load_api_settings,
+            save_api_settings,
+            search_announcements,
+            get_announcement,
+            export_results,
+            export_profile,
+            import_profile,
+            list_config_backups,
+            restore_config_backup,
+            runtime_status,
+            resolve_runtime,
+            load_runtime_settings,
+            save_runtime_settings,
+            diagnose_environment,
+            python_env_status,
+            setup_python_env,
+            fetch_announcement_detail,
+            extract_candidate_notice
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                // 退出前通知 worker 关闭，避免遗留 Python/Node 子进程
+                if let Some(core) = app_handle.try_state::<Arc<AppCore>>() {
+                    core.worker.shutdown();
+                }
+            }
+        });
+}
@@ -0,0 +1,57 @@
+/**
+ * 界面语言 Composable（单例模式）
+ * 通知文字来自后端的消息目录，与邮件、错误信息使用同一份翻译
+ */
+import { ref } from "vue";
+import { invoke } from "@tauri-apps/api/core";
+import type { Locale, LocaleSettings } from "../types";
+
+// 单例状态（模块级别）
+const locale = ref<Locale>("zh");
+const catalog = ref<Record<string, string>>({});
+
+/**
+ * 加载语言设置和当前语言的消息目录
+ */
+async function loadLocale(): Promise<void> {
+  try {
+    const settings = await invoke<LocaleSettings>("load_locale_settings");
+    locale.value = settings.locale;
+  } catch (e) {
+    console.error("[语言] 加载语言设置失败，使用中文:", e);
+  }
+  try {
+    catalog.value = await invoke<Record<string, string>>("message_catalog");
+  } catch (e) {
+    console.error("[语言] 加载消息目录失败:", e);
+  }
+}
+
+/**
+ * 切换语言并保存
+ */
+async function setLocale(value: Locale): Promise<void> {
+  const settings: LocaleSettings = { locale: value };
+  await invoke("save_locale_settings", { settings });
+  await loadLocale();
+}
+
+/**
+ * 取消息目录中的文字，{name} 由 args 替换；目录中没有时返回 key
+ */
+function t(key: string, args: Record<string, string | number> = {}): string {
+  const text = catalog.value[key] ?? key;
+  return Object.entries(args).reduce(
+    (result, [name, value]) => result.split(`{${name}}`).join(String(value)),
+    text
+  );
+}
+
+export function useI18n() {
+  return {
+    locale,
+    loadLocale,
+    setLocale,
+    t
+  };
+}
@@ -6,6 +6,8 @@ import { invoke } from "@tauri-apps/api/core";
 import type { SmtpConfig } from "../types";
 import { sendNotificationWithPermission } from "./useNotification";
 import { DEFAULT_SMTP } from "../utils/constants";
+import { errorMessage } from "../utils/helpers";
+import { useI18n } from "./useI18n";
 
 /**
  * 邮箱格式验证正则
@@ -18,18 +20,19 @@ const port = ref(DEFAULT_SMTP.port);
 const username = ref("");
 const password = ref("");
 const visible = ref(false);
+const { t } = useI18n();
 
 /**
  * 保存 SMTP 配置
  */
 async function saveConfig(): Promise<boolean> {
   if (!server.value?.trim()) {
-    await sendNotificationWithPermission("保存失败", "SMTP 服务器地址不能为空");
+    await sendNotificationWithPermission(t("notify.save_failed"), t("notify.smtp_server_required"));
     return false;
   }
 
   if (!port.value || port.value < 1 || port.value > 65535) {
-    await sendNotificationWithPermission("保存失败", "SMTP 端口必须是 1-65535 之间的数字");
+    await sendNotificationWithPermission(t("notify.save_failed"), t("notify.smtp_port_invalid"));
     return false;
   }
 
@@ -44,15 +47,15 @@ async function saveConfig(): Promise<boolean> {
     await invoke("save_smtp_config", { config });
 
     if (!config.username || !config.password) {
-      await sendNotificationWithPermission("配置已保存", "SMTP 配置已保存，但用户名或密码为空");
+      await sendNotificationWithPermission(t("notify.config_saved"), t("notify.smtp_saved_incomplete"));
     } else {
-      await sendNotificationWithPermission("配置已保存", "SMTP 配置已成功保存");
+      await sendNotificationWithPermission(t("notify.config_saved"), t("notify.smtp_saved_body"));
     }
     return true;
   } catch (e: unknown) {
-    const errorMsg = e instanceof Error ? e.message : String(e);
+    const errorMsg = errorMessage(e);
     console.error("[SMTP] 保存失败:", errorMsg);
-    await sendNotificationWithPermission("保存失败", `SMTP 配置保存失败: ${errorMsg}`);
+    await sendNotificationWithPermission(t("notify.save_failed"), t("notify.smtp_save_failed", { error: errorMsg }));
     return false;
   }
 }
@@ -75,7 +78,9 @@ async function loadConfig(): Promise<void> {
     password.value = smtpConfig.password?.trim() || "";
     console.log("[SMTP] 配置已加载");
   } catch (e: unknown) {
-    console.log("[SMTP] 加载配置失败，使用默认值");
+    const errorMsg = errorMessage(e);
+    console.log("[SMTP] 加载配置失败，使用默认值:", errorMsg);
+    await sendNotificationWithPermission(t("notify.load_failed"), t("notify.smtp_load_failed", { error: errorMsg }));
     resetToDefaults();
   }
 }
@@ -158,7 +163,7 @@ async function testEmail(
 如果您收到这封邮件，说明邮件发送功能正常工作。`;
 
   const result = await invoke<string>("send_email", { to, subject, body });
-  await sendNotificationWithPermission("测试邮件已发送", `邮件已发送到 ${to}`);
+  await sendNotificationWithPermission(t("notify.test_email_sent"), t("notify.test_email_sent_to", { email: to }));
   return result;
 }
 
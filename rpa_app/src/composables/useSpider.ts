@@ -3,10 +3,11 @@
  */
 import { ref, computed, nextTick } from "vue";
 import { invoke } from "@tauri-apps/api/core";
-import type { SpiderParams, SpiderConfig } from "../types";
-import { convertProjectType, formatDateTime } from "../utils/helpers";
+import type { SpiderParams, SpiderConfig, SpiderRunOutput } from "../types";
+import { projectTypeLabel, errorMessage, fieldErrorMap, formatDateTime, isAppError } from "../utils/helpers";
 import { sendNotificationWithPermission } from "./useNotification";
 import { useSmtp } from "./useSmtp";
+import { useI18n } from "./useI18n";
 
 /**
  * 获取当前日期时间的默认值
@@ -29,6 +30,7 @@ function getDefaultDateTime() {
 export function useSpider() {
   // 获取 SMTP 单例
   const smtp = useSmtp();
+  const { t } = useI18n();
 
   // 请求参数
   const page = ref(1);
@@ -75,6 +77,16 @@ export function useSpider() {
   const error = ref("");
   const status = ref("");
   const log = ref("");
+  // 后端校验失败时各字段的错误说明（字段名同 SpiderParams / SpiderConfig，如 page、email、hour）
+  const fieldErrors = ref<Record<string, string>>({});
+  // 执行时间由多个输入框组成，显示其中第一条错误
+  const scheduleTimeError = computed(() => {
+    const errors = fieldErrors.value;
+    return errors.year ?? errors.month ?? errors.day ?? errors.hour ?? errors.minute ?? errors.second ?? "";
+  });
+  // 最近一次执行新入库的公告 ID，执行过后才显示结果表格
+  const newIds = ref<number[]>([]);
+  const hasRun = ref(false);
 
   /**
    * 获取爬虫参数
@@ -83,7 +95,7 @@ export function useSpider() {
     return {
       page: page.value,
       title: title.value,
-      project_type: convertProjectType(projectType.value)
+      project_type: projectType.value
     };
   }
 
@@ -114,6 +126,9 @@ export function useSpider() {
     result.value = "";
     status.value = "";
     log.value = "";
+    newIds.value = [];
+    hasRun.value = false;
+    fieldErrors.value = {};
   }
 
   /**
@@ -169,7 +184,7 @@ export function useSpider() {
       const msg = "❌ SMTP 配置不完整，无法发送邮件。请填写 SMTP 用户名和密码（QQ邮箱授权码），然后点击'保存 SMTP 配置'按钮";
       appendLog(msg);
       error.value = "SMTP 配置不完整";
-      await sendNotificationWithPermission("邮件发送失败", "请先完成 SMTP 配置");
+      await sendNotificationWithPermission(t("notify.email_failed"), t("notify.smtp_incomplete"));
       return;
     }
 
@@ -198,12 +213,12 @@ export function useSpider() {
       }
 
       appendLog(`✅ 邮件已发送到: ${email.value}`);
-      await sendNotificationWithPermission("邮件已发送", `结果已发送到 ${email.value}`);
+      await sendNotificationWithPermission(t("notify.email_sent"), t("notify.email_sent_to", { email: email.value }));
     } catch (e: unknown) {
-      const errMsg = e instanceof Error ? e.message : String(e);
+      const errMsg = errorMessage(e);
       appendLog(`❌ 邮件发送失败: ${errMsg}`);
       error.value = errMsg;
-      await sendNotificationWithPermission("邮件发送失败", errMsg);
+      await sendNotificationWithPermission(t("notify.email_failed"), errMsg);
     }
   }
 
@@ -221,11 +236,14 @@ export function useSpider() {
       const params = getSpiderParams();
       log.value = "正在执行爬虫任务...";
 
-      const response = await invoke("run_spider", { params });
+      const response = await invoke<SpiderRunOutput>("run_spider", { params });
       const duration = ((Date.now() - startTime) / 1000).toFixed(2);
-      const responseStr = typeof response === "string" ? response : JSON.stringify(response, null, 2);
+      const raw = response.raw;
+      const responseStr = typeof raw === "string" ? raw : JSON.stringify(raw, null, 2);
 
       result.value = responseStr;
+      newIds.value = response.new_ids;
+      hasRun.value = true;
       status.value = "✅ 爬虫执行成功！";
       appendLog(`✅ 爬虫执行成功！\n执行耗时: ${duration} 秒`);
 
@@ -235,8 +253,9 @@ export function useSpider() {
       }
     } catch (e: unknown) {
       const duration = ((Date.now() - startTime) / 1000).toFixed(2);
-      const errMsg = e instanceof Error ? e.message : String(e);
+      const errMsg = errorMessage(e);
       error.value = errMsg;
+      fieldErrors.value = fieldErrorMap(e);
       status.value = "❌ 爬虫执行失败";
       appendLog(`❌ 爬虫执行失败\n错误: ${errMsg}\n执行耗时: ${duration} 秒`);
 
@@ -289,6 +308,7 @@ export function useSpider() {
 
     // 重置定时任务状态
     scheduledTaskStarted.value = false;
+    fieldErrors.value = {};
     loading.value = true;
 
     try {
@@ -309,14 +329,15 @@ export function useSpider() {
       appendLog(String(response));
       scheduledTaskStarted.value = true;
 
-      await sendNotificationWithPermission("定时任务已启动", `任务将在 ${timeValidation.datetimeStr} 执行`);
+      await sendNotificationWithPermission(t("notify.schedule_started"), t("notify.schedule_runs_at", { time: timeValidation.datetimeStr }));
     } catch (e: unknown) {
-      const errMsg = e instanceof Error ? e.message : String(e);
-      const msg = errMsg.includes("已过")
-        ? `❌ ${errMsg}\n\n💡 提示：请设置未来的时间`
+      const errMsg = errorMessage(e);
+      const msg = isAppError(e) && e.code === "schedule.time_passed"
+        ? `❌ ${errMsg}`
         : `❌ 启动定时任务失败: ${errMsg}`;
 
       error.value = errMsg;
+      fieldErrors.value = fieldErrorMap(e);
       status.value = msg;
       appendLog(msg);
     } finally {
@@ -332,11 +353,11 @@ export function useSpider() {
       const config = buildConfig();
       await invoke("save_note", { content: JSON.stringify(config, null, 2) });
       appendLog("✅ 配置信息已保存");
-      await sendNotificationWithPermission("配置已保存", "配置信息已成功保存");
+      await sendNotificationWithPermission(t("notify.config_saved"), t("notify.config_saved_body"));
     } catch (e: unknown) {
-      const errMsg = e instanceof Error ? e.message : String(e);
+      const errMsg = errorMessage(e);
       appendLog(`❌ 保存配置失败: ${errMsg}`);
-      await sendNotificationWithPermission("保存失败", errMsg);
+      await sendNotificationWithPermission(t("notify.save_failed"), errMsg);
     }
   }
 
@@ -357,16 +378,11 @@ export function useSpider() {
       if (config.params) {
         page.value = config.params.page || 1;
         title.value = config.params.title || "";
-        const projectTypeMap: Record<string, string> = {
-          "": "全部",
-          "0001": "物资",
-          "0002": "工程"
-        };
-        projectType.value = projectTypeMap[config.params.project_type] || config.params.project_type || "全部";
+        projectType.value = projectTypeLabel(config.params.project_type);
       } else {
         page.value = config.page || 1;
         title.value = config.title || "";
-        projectType.value = config.project_type || "全部";
+        projectType.value = projectTypeLabel(config.project_type);
       }
 
       email.value = config.email || "";
@@ -390,7 +406,10 @@ export function useSpider() {
 
       console.log("[Spider] 配置已加载");
     } catch (e: unknown) {
-      console.log("[Spider] 加载配置失败:", e);
+      // 配置文件损坏时不要当作没有配置，提示用户从历史版本恢复
+      const errMsg = errorMessage(e);
+      console.log("[Spider] 加载配置失败:", errMsg);
+      appendLog(`⚠️ 加载配置失败: ${errMsg}`);
     }
   }
 
@@ -403,10 +422,10 @@ export function useSpider() {
     try {
       await navigator.clipboard.writeText(text);
       appendLog(`✅ ${successMessage}`);
-      await sendNotificationWithPermission("复制成功", successMessage);
+      await sendNotificationWithPermission(t("notify.copied"), successMessage);
       return true;
     } catch (e: unknown) {
-      const errMsg = e instanceof Error ? e.message : String(e);
+      const errMsg = errorMessage(e);
       appendLog(`❌ 复制失败: ${errMsg}`);
       return false;
     }
@@ -473,6 +492,10 @@ export function useSpider() {
     error,
     status,
     log,
+    fieldErrors,
+    scheduleTimeError,
+    newIds,
+    hasRun,
     // 方法
     appendLog,
     runSpider,
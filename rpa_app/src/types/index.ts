@@ -14,7 +14,21 @@ export interface SpiderParams {
   project_type: string;
 }
 
+export type BackendChoice = 'auto' | 'python' | 'binary';
+
+export type FallbackPolicy = 'on_environment_error' | 'never';
+
+export type Backend = 'python' | 'binary' | 'plugin';
+
+export interface BackendOptions {
+  backend: BackendChoice;
+  /** 不填时 auto 回退，python / binary 只用所选后端 */
+  fallback?: FallbackPolicy;
+}
+
 export interface SpiderConfig {
+  version?: number;
+  spider_id?: string;
   params: SpiderParams;
   email: string;
   push_content: string;
@@ -26,6 +40,152 @@ export interface SpiderConfig {
   second: number;
   enabled: boolean;
   push_content_enabled: boolean;
+  fetch_details?: boolean;
+  backend?: BackendChoice;
+  fallback?: FallbackPolicy;
+}
+
+export interface FieldError {
+  field: string;
+  code: string;
+  message: string;
+}
+
+export type ErrorKind =
+  | "validation"
+  | "not_found"
+  | "config"
+  | "environment"
+  | "network"
+  | "smtp"
+  | "execution"
+  | "internal";
+
+/** 命令返回的结构化错误 */
+export interface AppError {
+  kind: ErrorKind;
+  /** 稳定的错误码，如 "smtp.auth_failed" */
+  code: string;
+  message: string;
+  detail: string | null;
+  hints: string[];
+  retryable: boolean;
+  fields: FieldError[];
+}
+
+export interface SpiderRunOutput {
+  spider_id: string;
+  run_id: string;
+  raw: unknown;
+  items: Record<string, unknown>[];
+  new_count: number;
+  new_ids: number[];
+  detail_count: number;
+  backend: Backend | null;
+  fallback_reason: string | null;
+}
+
+export interface SearchFilters {
+  spider_id?: string;
+  job?: string;
+  project_type?: string;
+  date_from?: string;
+  date_to?: string;
+}
+
+export interface AnnouncementSummary {
+  id: number;
+  spider_id: string;
+  title: string;
+  snippet: string;
+  project_type: string | null;
+  publish_date: string | null;
+  url: string | null;
+  detail_url: string | null;
+  attachment_count: number;
+  job: string;
+  first_seen_at: string;
+}
+
+export interface AttachmentRecord {
+  url: string;
+  file_name: string;
+  sha256: string;
+  size: number;
+  path: string;
+}
+
+export interface SearchPage {
+  total: number;
+  page: number;
+  page_size: number;
+  items: AnnouncementSummary[];
+}
+
+export interface Announcement extends Omit<AnnouncementSummary, "snippet" | "attachment_count"> {
+  source_id: string;
+  content: string;
+  raw: unknown;
+  run_id: string;
+  last_seen_at: string;
+  detail_path: string | null;
+  detail_fetched_at: string | null;
+  detail_error: string | null;
+  attachments: AttachmentRecord[];
+  candidate_notice: CandidateNotice | null;
+}
+
+export interface Extracted<T> {
+  value: T;
+  confidence: number;
+  snippet: string;
+}
+
+export interface BidPrice {
+  amount_yuan: number;
+  text: string;
+}
+
+export interface Candidate {
+  rank: Extracted<number>;
+  bidder: Extracted<string>;
+  bid_price: Extracted<BidPrice> | null;
+}
+
+export interface CandidateNotice {
+  project_number: Extracted<string> | null;
+  tender_agency: Extracted<string> | null;
+  candidates: Candidate[];
+}
+
+export type LogLevel = "debug" | "info" | "warn" | "error";
+
+/** 日志文件中的一条记录 */
+export interface LogRecord {
+  ts: string;
+  level: LogLevel;
+  target: string;
+  message: string;
+  run_id?: string;
+}
+
+export interface LogQuery {
+  /** 最低级别 */
+  level: LogLevel | null;
+  run_id: string | null;
+  /** RFC 3339 时间 */
+  since: string | null;
+  limit: number | null;
+}
+
+export type Locale = "zh" | "en";
+
+export interface LocaleSettings {
+  locale: Locale;
+}
+
+export interface RedactionSettings {
+  mask_email_local_part: boolean;
 }
 
 export interface SmtpConfig {
@@ -35,3 +195,143 @@ export interface SmtpConfig {
   password: string;
 }
 
+export type ExportFormat = "csv" | "xlsx" | "jsonl" | "markdown";
+
+export interface ExportSummary {
+  path: string;
+  format: ExportFormat;
+  rows: number;
+}
+
+export type IntegrityMode = 'enforce' | 'warn';
+
+export interface RuntimeSettings {
+  python_path: string;
+  script_path: string;
+  binary_path: string;
+  integrity_mode?: IntegrityMode;
+}
+
+export interface RuntimeCandidate {
+  path: string;
+  source: string;
+  rejected: string | null;
+}
+
+export interface RuntimeResolution {
+  path: string | null;
+  version: string | null;
+  candidates: RuntimeCandidate[];
+}
+
+export interface RuntimeReport {
+  python: RuntimeResolution;
+  script: RuntimeResolution;
+  binary: RuntimeResolution;
+  resolved_at: string;
+}
+
+export type CheckStatus = "pass" | "warn" | "fail";
+
+export interface CheckItem {
+  id: string;
+  name: string;
+  status: CheckStatus;
+  detail: string;
+  hint: string | null;
+}
+
+export interface DiagnosticReport {
+  status: CheckStatus;
+  checks: CheckItem[];
+  generated_at: string;
+}
+
+export type SetupStage = 'prepare' | 'create' | 'install' | 'verify' | 'done';
+
+export interface SetupProgress {
+  stage: SetupStage;
+  message: string;
+  percent: number;
+}
+
+export interface EnvMarker {
+  requirements_sha256: string;
+  python_version: string;
+  base_python: string;
+  offline: boolean;
+  created_at: string;
+}
+
+export interface EnvStatus {
+  path: string;
+  python: string | null;
+  ready: boolean;
+  marker: EnvMarker | null;
+  requirements: string | null;
+  outdated: boolean;
+  wheelhouse: string | null;
+  wheel_count: number;
+}
+
+export interface BackupInfo {
+  id: string;
+  file: string;
+  created_at: string;
+  size: number;
+}
+
+export interface ProfileExportSummary {
+  path: string;
+  entries: string[];
+  includes_credentials: boolean;
+}
+
+export type ImportAction = "create" | "overwrite" | "unchanged";
+
+export interface ImportEntry {
+  path: string;
+  label: string;
+  action: ImportAction;
+}
+
+export interface ImportReport {
+  path: string;
+  created_at: string;
+  entries: ImportEntry[];
+  has_credentials: boolean;
+  credentials_imported: boolean;
+  dry_run: boolean;
+}
+
+/** 已登记、尚未执行完的定时任务 */
+export interface ScheduleInfo {
+  id: string;
+  spider_id: string;
+  params: Record<string, unknown>;
+  email: string;
+  next_run: string;
+  fetch_details: boolean;
+  push_content_enabled: boolean;
+}
+
+/** 一次爬虫执行的记录 */
+export interface RunRecord {
+  run_id: string;
+  spider_id: string;
+  job: string;
+  params: Record<string, unknown>;
+  started_at: string;
+  finished_at: string | null;
+  status: string;
+  item_count: number;
+  new_count: number;
+  error: string | null;
+}
+
+/** 本机 HTTP API 设置 */
+export interface ApiSettings {
+  enabled: boolean;
+  port: number;
+  token: string;
+}
@@ -3,13 +3,12 @@
  */
 
 /**
- * 项目类型映射
+ * 旧配置中的项目类型编码 -> 名称（现在参数和配置都直接使用名称，编码由后端在内部转换）
  */
-export const PROJECT_TYPE_MAP: Record<string, string> = {
-  "全部": "",
-  "物资": "0001",
-  "工程": "0002",
-  "服务": "0001"
+export const LEGACY_PROJECT_TYPE_CODES: Record<string, string> = {
+  "": "全部",
+  "0001": "物资",
+  "0002": "工程"
 } as const;
 
 /**
@@ -1,13 +1,17 @@
 /**
  * 工具函数集合
  */
-import { PROJECT_TYPE_MAP } from "./constants";
+import { LEGACY_PROJECT_TYPE_CODES } from "./constants";
+import type { AppError } from "../types";
 
 /**
- * 项目类型转换
+ * 读取配置中的项目类型，兼容旧配置保存的编码
  */
-export function convertProjectType(type: string): string {
-  return PROJECT_TYPE_MAP[type] || "";
+export function projectTypeLabel(value: string | undefined): string {
+  if (value === undefined) {
+    return "全部";
+  }
+  return LEGACY_PROJECT_TYPE_CODES[value] || value;
 }
 
 /**
@@ -46,3 +50,39 @@ export function parseDate(dateStr: string): { year: number; month: number; day:
   return null;
 }
 
+
+/**
+ * 判断命令抛出的错误是否为结构化的 AppError
+ */
+export function isAppError(e: unknown): e is AppError {
+  return typeof e === "object" && e !== null && "code" in e && "message" in e && "kind" in e;
+}
+
+/**
+ * 校验错误按字段归类（字段名 -> 第一条错误说明），供表单在对应输入框旁显示；不是校验错误时返回空对象
+ */
+export function fieldErrorMap(e: unknown): Record<string, string> {
+  const map: Record<string, string> = {};
+  if (isAppError(e) && e.kind === "validation") {
+    for (const field of e.fields) {
+      if (!(field.field in map)) {
+        map[field.field] = field.message;
+      }
+    }
+  }
+  return map;
+}
+
+/**
+ * 错误的文字描述（AppError 包含技术细节和处理建议）
+ */
+export function errorMessage(e: unknown): string {
+  if (isAppError(e)) {
+    const lines = [e.detail ? `${e.message}: ${e.detail}` : e.message];
+    if (e.hints.length > 0) {
+      lines.push("", "处理建议:", ...e.hints.map((hint, i) => `${i + 1}. ${hint}`));
+    }
+    return lines.join("\n");
+  }
+  return e instanceof Error ? e.message : String(e);
+}
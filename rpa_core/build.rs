@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+/// 爬虫项目目录（相对 rpa_core）
+const SPIDER_PROJECT_DIR: &str = "../中国石油招标投标网";
+/// 打包进应用、执行前需要校验的文件
+const BUNDLED_FILES: [&str; 4] = ["spiders.py", "decryption.js", "jsencrypt.js", "js_executor.js"];
+
+fn main() {
+    write_integrity_manifest();
+}
+
+/// 生成完整性清单 integrity.json：随应用发布的爬虫脚本和 spider_bin 的 SHA-256，
+/// 由 src/integrity.rs 通过 include_str! 编译进程序
+fn write_integrity_manifest() {
+    let project = Path::new(SPIDER_PROJECT_DIR);
+    let mut files = BTreeMap::new();
+    for name in BUNDLED_FILES {
+        let path = project.join(name);
+        println!("cargo:rerun-if-changed={}", path.display());
+        if let Ok(content) = fs::read(&path) {
+            files.insert(name.to_string(), hex::encode(Sha256::digest(&content)));
+        }
+    }
+
+    // spider_bin 由 build_bin.sh 生成，没有构建时清单中不包含它，运行时会拒绝自动找到的 spider_bin；
+    // 布局与运行时查找一致：onedir 模式 dist/spider_bin/spider_bin，旧版本单个文件 dist/spider_bin
+    let exe = if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("windows") { ".exe" } else { "" };
+    let name = format!("spider_bin{}", exe);
+    let dist = project.join("dist");
+    println!("cargo:rerun-if-changed={}", dist.display());
+    let binary: Option<PathBuf> = [dist.join("spider_bin").join(&name), dist.join(&name)]
+        .into_iter()
+        .find(|path| path.is_file());
+    match binary.and_then(|path| fs::read(path).ok()) {
+        Some(content) => {
+            files.insert("spider_bin".to_string(), hex::encode(Sha256::digest(&content)));
+        }
+        None if std::env::var("PROFILE").as_deref() == Ok("release") => {
+            println!(
+                "cargo:warning=没有找到 {}，完整性清单中不包含 spider_bin，发布版本将拒绝执行自动找到的 spider_bin；\
+                 请先在爬虫项目目录执行 build_bin.sh（Windows 为 build_bin.bat）再构建",
+                dist.join("spider_bin").join(&name).display()
+            );
+        }
+        None => {}
+    }
+
+    let manifest = serde_json::json!({ "files": files });
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR 未设置"));
+    fs::write(out_dir.join("integrity.json"), manifest.to_string()).expect("写入完整性清单失败");
+}
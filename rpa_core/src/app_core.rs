@@ -0,0 +1,621 @@
+// ============================================================================
+// 与界面无关的应用核心
+// ============================================================================
+//
+// AppCore 持有爬虫执行需要的全部状态（worker、插件注册表、限速器、运行环境、公告数据库），
+// 并提供读取配置、执行爬虫、抓取详情和发送邮件的方法。桌面应用把它放进 Tauri 的 managed state，
+// 命令行工具直接创建，两者读写同一个应用数据目录下的配置文件。
+// 共用 rpa_core 的应用通过 AppIdentity 区分：应用标识决定数据目录，显示名称用作发件人名称，
+// AppFeatures 控制可选功能。
+// 需要通知界面的事件（定时任务结果、邮件发送结果、安装进度）通过 emit 交给创建者提供的监听器，
+// payload 中的字符串先经过脱敏。
+// 当前时间、外部进程和邮件发送通过 Services 中的 Clock / ProcessRunner / MailTransport 完成，
+// 应用使用 open（真实实现），测试使用 open_with_services 换成假的实现
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use crate::automation;
+use crate::backend::{self, Backend, BackendError, BackendOptions, BackendRun};
+use crate::candidate_extract;
+use crate::clock::{Clock, SystemClock};
+use crate::crawl_policy::{CrawlLimiter, CrawlOutcome, CrawlPolicy, PageDelay};
+use crate::detail_fetch::DetailFetcher;
+use crate::diagnostics::{self, DiagnoseInput, DiagnosticReport};
+use crate::error::{AppError, AppResult, ErrorKind};
+use crate::i18n::{self, Locale, LocaleSettings};
+use crate::mailer::{self, Email, MailTransport, SmtpMailTransport};
+use crate::network::NetworkSettings;
+use crate::process::{ProcessRunner, ProcessSpec, SystemProcessRunner};
+use crate::redact::{self, RedactionSettings};
+use crate::runtime::{RuntimeResolver, RuntimeSettings, SearchRoots};
+use crate::scheduler::Schedules;
+use crate::settings::SmtpConfig;
+use crate::spider_params::{self, FieldError, SpiderParams};
+use crate::spider_registry::{self, SpiderPlugin, SpiderRegistry, SpiderRunOutput};
+use crate::spider_worker::{self, SpiderWorker, WorkerCommand};
+use crate::store::{AnnouncementStore, NewAnnouncement};
+use crate::venv::SetupLock;
+
+/// 常驻 worker 健康检查的间隔
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 应用标识，由各桌面应用定义
+#[derive(Debug, Clone, Copy)]
+pub struct AppIdentity {
+    /// tauri.conf.json 的 identifier，决定应用数据目录和日志目录的位置
+    pub identifier: &'static str,
+    /// 显示名称，用作邮件的发件人名称
+    pub display_name: &'static str,
+    pub features: AppFeatures,
+}
+
+/// 各应用启用的可选功能
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AppFeatures {
+    /// Python 自动化脚本（run_automation / check_python_env）
+    pub automation: bool,
+}
+
+/// 应用使用的目录
+#[derive(Debug, Clone)]
+pub struct AppPaths {
+    /// 配置文件、公告数据库、私有 Python 环境和用户插件所在的目录
+    pub app_data_dir: PathBuf,
+    pub log_dir: PathBuf,
+    /// 随应用打包的资源（spiders.py、spider_bin、内置插件），命令行工具可以没有
+    pub resource_dir: Option<PathBuf>,
+}
+
+impl AppPaths {
+    /// 与 Tauri 的 app_data_dir / app_log_dir 相同的位置，命令行工具用它读取桌面应用保存的配置
+    pub fn for_identifier(identifier: &str) -> Result<Self, String> {
+        let data_dir = dirs::data_dir().ok_or("无法获取用户数据目录")?;
+        let log_dir = if cfg!(target_os = "macos") {
+            dirs::home_dir().map(|home| home.join("Library").join("Logs").join(identifier))
+        } else {
+            dirs::data_local_dir().map(|dir| dir.join(identifier).join("logs"))
+        };
+        Ok(AppPaths {
+            app_data_dir: data_dir.join(identifier),
+            log_dir: log_dir.ok_or("无法获取日志目录")?,
+            resource_dir: None,
+        })
+    }
+}
+
+/// 单次执行的选项
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    /// 触发来源：手动执行为 "manual"，定时任务为 "scheduled:<执行时间>"
+    pub job: String,
+    /// 是否抓取新公告的详情页和附件
+    pub fetch_details: bool,
+    /// 内置爬虫的执行后端和回退策略
+    pub backend: BackendOptions,
+}
+
+/// 可替换的外部依赖：当前时间、外部进程和邮件发送
+#[derive(Clone)]
+pub struct Services {
+    pub clock: Arc<dyn Clock>,
+    pub processes: Arc<dyn ProcessRunner>,
+    pub mail: Arc<dyn MailTransport>,
+}
+
+impl Default for Services {
+    /// 系统时钟、真实子进程和 SMTP 服务器
+    fn default() -> Self {
+        Services {
+            clock: Arc::new(SystemClock),
+            processes: Arc::new(SystemProcessRunner),
+            mail: Arc::new(SmtpMailTransport),
+        }
+    }
+}
+
+type EventListener = Box<dyn Fn(&str, serde_json::Value) + Send + Sync>;
+
+pub struct AppCore {
+    pub identity: AppIdentity,
+    pub paths: AppPaths,
+    pub worker: SpiderWorker,
+    pub registry: SpiderRegistry,
+    pub limiter: CrawlLimiter,
+    pub resolver: RuntimeResolver,
+    pub store: AnnouncementStore,
+    pub setup_lock: SetupLock,
+    /// 已登记的定时任务
+    pub schedules: Schedules,
+    pub services: Services,
+    events: EventListener,
+}
+
+impl AppCore {
+    /// 读取应用数据目录下的设置并初始化各组件；设置文件损坏时使用默认值，数据库无法打开时使用内存数据库
+    pub fn open(identity: AppIdentity, paths: AppPaths, events: impl Fn(&str, serde_json::Value) + Send + Sync + 'static) -> Result<Self, String> {
+        Self::open_with_services(identity, paths, Services::default(), events)
+    }
+
+    /// 与 open 相同，但使用指定的时钟、进程启动和邮件发送实现
+    pub fn open_with_services(
+        identity: AppIdentity,
+        paths: AppPaths,
+        services: Services,
+        events: impl Fn(&str, serde_json::Value) + Send + Sync + 'static,
+    ) -> Result<Self, String> {
+        let app_data_dir = &paths.app_data_dir;
+        // 脱敏设置和已保存的密码要在后续日志写出前生效
+        match RedactionSettings::load(app_data_dir) {
+            Ok(settings) => redact::configure(settings),
+            Err(e) => log_warn!("[脱敏] {}，使用默认脱敏设置", e),
+        }
+
+        let runtime_settings = RuntimeSettings::load(app_data_dir).unwrap_or_else(|e| {
+            log_warn!("[运行环境] {}，使用自动查找", e);
+            RuntimeSettings::default()
+        });
+        let roots = SearchRoots::from_process(paths.resource_dir.clone(), Some(app_data_dir.clone()));
+        let limiter = CrawlLimiter::default();
+        match CrawlPolicy::load(app_data_dir) {
+            Ok(policy) => limiter.set_policy(policy),
+            Err(e) => log_warn!("[限速] {}，使用默认限速设置", e),
+        }
+        let store = AnnouncementStore::open(app_data_dir).or_else(|e| {
+            log_warn!("[数据库] {}，本次运行使用内存数据库", e);
+            AnnouncementStore::open_in_memory()
+        })?;
+
+        let core = AppCore {
+            worker: SpiderWorker::new(),
+            registry: SpiderRegistry::new(),
+            limiter,
+            resolver: RuntimeResolver::new(roots, runtime_settings),
+            store,
+            setup_lock: SetupLock::default(),
+            schedules: Schedules::default(),
+            services,
+            events: Box::new(events),
+            identity,
+            paths,
+        };
+        core.smtp_config();
+        core.network_settings();
+        let count = core.reload_plugins();
+        log_info!("[插件] 已加载 {} 个爬虫插件", count);
+        Ok(core)
+    }
+
+    /// 发送事件，payload 中的字符串先经过脱敏
+    pub fn emit<S: Serialize>(&self, event: &str, payload: S) {
+        match serde_json::to_value(payload) {
+            Ok(value) => (self.events)(event, redact::redact_json(value)),
+            Err(e) => log_warn!("[事件] 序列化 {} 失败: {}", event, e),
+        }
+    }
+
+    /// 当前时间（来自 Services 中的时钟）
+    pub fn now(&self) -> DateTime<Local> {
+        self.services.clock.now()
+    }
+
+    pub fn app_data_dir(&self) -> &Path {
+        &self.paths.app_data_dir
+    }
+
+    /// 读取网络设置，读取失败时不使用代理
+    pub fn network_settings(&self) -> NetworkSettings {
+        let settings = NetworkSettings::load(self.app_data_dir()).unwrap_or_else(|e| {
+            log_warn!("[网络] {}，不使用代理", e);
+            NetworkSettings::default()
+        });
+        redact::register_secret(&settings.proxy_password);
+        settings
+    }
+
+    /// 读取界面语言，读取失败时使用中文
+    pub fn locale(&self) -> Locale {
+        LocaleSettings::load(self.app_data_dir())
+            .map(|settings| settings.locale)
+            .unwrap_or_else(|e| {
+                log_warn!("[语言] {}，使用中文", e);
+                Locale::default()
+            })
+    }
+
+    /// 读取 SMTP 配置，密码登记为需要脱敏的值
+    pub fn smtp_config(&self) -> SmtpConfig {
+        let config = self.read_smtp_config();
+        redact::register_secret(&config.password);
+        config
+    }
+
+    fn read_smtp_config(&self) -> SmtpConfig {
+        // 首先尝试从配置文件读取
+        match SmtpConfig::load(self.app_data_dir()) {
+            Ok(config) => {
+                // 如果配置完整，直接返回
+                if !config.username.is_empty() && !config.password.is_empty() {
+                    return config;
+                }
+                // 即使不完整，也使用配置文件中的服务器和端口，只从环境变量获取用户名和密码
+                let smtp_username = std::env::var("SMTP_USERNAME")
+                    .unwrap_or_else(|_| config.username.clone());
+                let smtp_password = std::env::var("SMTP_PASSWORD")
+                    .unwrap_or_else(|_| config.password.clone());
+
+                if !smtp_username.is_empty() && !smtp_password.is_empty() {
+                    return SmtpConfig {
+                        server: config.server,
+                        port: config.port,
+                        username: smtp_username,
+                        password: smtp_password,
+                    };
+                }
+                return config;
+            }
+            Err(e) => log_warn!("[SMTP] {}，改用环境变量", e),
+        }
+
+        // 如果配置文件不存在或加载失败，尝试从环境变量读取
+        let smtp_server = std::env::var("SMTP_SERVER")
+            .unwrap_or_else(|_| "smtp.qq.com".to_string());
+        let smtp_port = std::env::var("SMTP_PORT")
+            .unwrap_or_else(|_| "587".to_string())
+            .parse::<u16>()
+            .unwrap_or(587);
+        let smtp_username = std::env::var("SMTP_USERNAME")
+            .unwrap_or_else(|_| "".to_string());
+        let smtp_password = std::env::var("SMTP_PASSWORD")
+            .unwrap_or_else(|_| "".to_string());
+
+        SmtpConfig {
+            server: smtp_server,
+            port: smtp_port,
+            username: smtp_username,
+            password: smtp_password,
+        }
+    }
+
+    /// 按当前的 SMTP 配置、网络设置和界面语言发送邮件
+    pub fn send_email(&self, to: &str, subject: &str, body: &str) -> AppResult<String> {
+        mailer::send(
+            self.services.mail.as_ref(),
+            &self.smtp_config(),
+            &self.network_settings(),
+            self.locale(),
+            &Email { from_name: self.identity.display_name, to, subject, body },
+        )
+    }
+
+    /// 未开启 AppFeatures::automation 的应用调用自动化功能时的错误
+    fn check_automation_enabled(&self) -> AppResult<()> {
+        if self.identity.features.automation {
+            Ok(())
+        } else {
+            Err(AppError::new(
+                ErrorKind::Config,
+                "automation.disabled",
+                format!("{} 未提供自动化脚本功能", self.identity.display_name),
+            ))
+        }
+    }
+
+    /// 运行 Python 自动化脚本（阻塞），脚本使用当前的网络设置
+    pub fn run_automation(&self, url: &str, action: &str, output: Option<&str>) -> AppResult<String> {
+        self.check_automation_enabled()?;
+        automation::run(
+            self.services.processes.as_ref(),
+            self.paths.resource_dir.as_deref(),
+            self.network_settings().env_vars(),
+            url,
+            action,
+            output,
+        )
+    }
+
+    /// 检测自动化脚本使用的 Python 环境
+    pub fn check_python_env(&self) -> AppResult<serde_json::Value> {
+        self.check_automation_enabled()?;
+        automation::check_python()
+    }
+
+    /// 诊断整条工具链，先重新查找一次运行环境，反映刚安装或移动的文件
+    pub fn diagnose(&self) -> DiagnosticReport {
+        let runtime = self.resolver.resolve();
+        let report = diagnostics::diagnose(&DiagnoseInput {
+            runtime: &runtime,
+            app_data_dir: Some(self.app_data_dir()),
+            network: &self.network_settings(),
+            smtp: &self.smtp_config(),
+        });
+        log_info!("[诊断] 完成，{} 项检查，结果: {:?}", report.checks.len(), report.status);
+        report
+    }
+
+    /// 重新扫描资源目录和应用数据目录下的 plugins 目录
+    pub fn reload_plugins(&self) -> usize {
+        let bundled_dir = self.paths.resource_dir.as_ref().map(|dir| dir.join("plugins"));
+        let user_dir = self.app_data_dir().join("plugins");
+        self.registry.reload(bundled_dir.as_deref(), Some(&user_dir))
+    }
+
+    /// 构造常驻 worker 的启动命令（需要 Python 和 spiders.py 都可用）
+    pub fn worker_command(&self) -> Result<WorkerCommand, String> {
+        let python = self.resolver.python().ok_or("未找到 Python 环境")?;
+        let script_path = self.resolver.script()?;
+        let script_dir = script_path.parent().ok_or("无法获取脚本目录")?.to_path_buf();
+        Ok(WorkerCommand {
+            program: python,
+            script: script_path,
+            cwd: script_dir,
+            env: self.network_settings().env_vars(),
+        })
+    }
+
+    /// 定期对常驻 worker 做健康检查，崩溃或卡死时自动重启
+    pub async fn worker_health_loop(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let core = self.clone();
+            let _ = tokio::task::spawn_blocking(move || {
+                if let Ok(command) = core.worker_command() {
+                    if let Err(e) = core.worker.health_check_if_running(&command) {
+                        log_warn!("[爬虫 worker] 重启失败: {}", e);
+                    }
+                }
+            }).await;
+        }
+    }
+
+    /// 内置爬虫的请求参数，附带当前的页面请求间隔（一次执行内的多次页面请求由爬虫自行等待）
+    fn builtin_params(&self, params: &SpiderParams) -> Result<serde_json::Value, BackendError> {
+        let mut value = serde_json::to_value(params)
+            .map_err(|e| BackendError::Execution(format!("序列化参数失败: {}", e)))?;
+        if let Some(object) = value.as_object_mut() {
+            object.insert(PageDelay::PARAM.to_string(), serde_json::json!(self.limiter.page_delay()));
+        }
+        Ok(value)
+    }
+
+    /// 用常驻的 Python worker 执行内置爬虫（模型只加载一次，后续请求无需重新启动解释器）
+    fn run_with_python(&self, params: &SpiderParams) -> Result<String, BackendError> {
+        let command = self.worker_command().map_err(BackendError::Environment)?;
+        let params_value = self.builtin_params(params)?;
+        self.worker
+            .call(&command, "run", params_value, spider_worker::RUN_TIMEOUT)
+            .map(|result| result.to_string())
+            .map_err(BackendError::from_worker)
+    }
+
+    /// 用打包的 spider_bin 执行内置爬虫
+    fn run_with_binary(&self, params: &SpiderParams) -> Result<String, BackendError> {
+        let bin_path = self.resolver.binary().map_err(BackendError::Environment)?;
+        let params_json = self.builtin_params(params)?.to_string();
+        let command = ProcessSpec::new(&bin_path)
+            .arg(params_json)
+            .envs(self.network_settings().env_vars());
+        let output = self
+            .services
+            .processes
+            .run(&command)
+            .map_err(|e| BackendError::Environment(format!("执行爬虫程序失败: {}", e)))?;
+
+        if output.success {
+            String::from_utf8(output.stdout)
+                .map_err(|e| BackendError::Execution(format!("解析输出失败: {}", e)))
+        } else {
+            let error = String::from_utf8(output.stderr)
+                .unwrap_or_else(|_| "未知错误".to_string());
+            Err(BackendError::from_binary_stderr(error))
+        }
+    }
+
+    /// 按任务的后端设置执行内置爬虫，只在环境错误时回退到另一个后端
+    fn run_builtin(&self, params: SpiderParams, options: &BackendOptions) -> AppResult<BackendRun> {
+        backend::run_with_fallback(options, self.locale(), |backend| match backend {
+            Backend::Python => self.run_with_python(&params),
+            Backend::Binary => self.run_with_binary(&params),
+            Backend::Plugin => Err(BackendError::Environment("内置爬虫不能作为插件执行".to_string())),
+        })
+    }
+
+    /// 校验指定爬虫的参数：内置爬虫使用类型化校验，插件按其 params_schema 校验
+    pub fn check_spider_params(plugin: &SpiderPlugin, params: &serde_json::Value) -> Vec<FieldError> {
+        match &plugin.entrypoint {
+            spider_registry::Entrypoint::Builtin { name } if name == spider_registry::DEFAULT_SPIDER_ID => {
+                spider_params::validate_spider_params(params).err().unwrap_or_default()
+            }
+            _ => spider_params::validate_against_schema(&plugin.params_schema, params),
+        }
+    }
+
+    /// 取出爬虫插件并校验参数（在启动任何进程之前）
+    pub fn checked_spider(&self, spider_id: &str, params: &serde_json::Value) -> AppResult<SpiderPlugin> {
+        let plugin = self
+            .registry
+            .get(spider_id)
+            .map_err(|e| AppError::invalid_input("spider.unknown", e))?;
+        let errors = Self::check_spider_params(&plugin, params);
+        if !errors.is_empty() {
+            return Err(AppError::validation(errors));
+        }
+        Ok(plugin)
+    }
+
+    /// 通过注册表执行指定爬虫插件，返回原始输出和映射后的结果；结果会写入本地公告数据库
+    pub fn run_spider(&self, spider_id: &str, options: &RunOptions, params: serde_json::Value) -> AppResult<SpiderRunOutput> {
+        let job = options.job.as_str();
+        let plugin = self.checked_spider(spider_id, &params)?;
+
+        let store = &self.store;
+        let run_id = uuid::Uuid::new_v4().to_string();
+        // 本次执行期间当前线程写出的日志都带上 run_id
+        crate::logging::with_run_id(&run_id, || {
+            log_info!("[爬虫] 开始执行 {}（{}）", spider_id, job);
+            if let Err(e) = store.begin_run(&run_id, spider_id, job, &params) {
+                log_warn!("[数据库] {}", e);
+            }
+            let result = self.execute_plugin(&plugin, &options.backend, params);
+
+            match result {
+                Ok(run) => {
+                    let mut output = plugin.to_output(&run.stdout);
+                    output.run_id = run_id.clone();
+                    output.backend = Some(run.backend);
+                    output.fallback_reason = run.fallback_reason;
+                    let rows = plugin.rows(&output.raw);
+                    let mapped: Vec<_> = rows.iter().map(|row| plugin.map_row(row)).collect();
+                    let items: Vec<NewAnnouncement> = rows
+                        .iter()
+                        .zip(&mapped)
+                        .map(|(row, mapped)| NewAnnouncement {
+                            row,
+                            mapped,
+                            detail_url: plugin.detail_url(mapped),
+                        })
+                        .collect();
+                    match store.upsert_announcements(spider_id, job, &run_id, &items) {
+                        Ok(new_ids) => {
+                            output.new_count = new_ids.len();
+                            output.new_ids = new_ids;
+                        }
+                        Err(e) => log_warn!("[数据库] {}", e),
+                    }
+                    log_info!("[数据库] 本次获取 {} 条公告，新增 {} 条（执行后端: {}）", output.items.len(), output.new_count, run.backend.label());
+                    if options.fetch_details && !output.new_ids.is_empty() {
+                        output.detail_count = self.fetch_details(&plugin, &output.new_ids);
+                    }
+                    if let Err(e) = store.finish_run(&run_id, Ok((output.items.len(), output.new_count))) {
+                        log_warn!("[数据库] {}", e);
+                    }
+                    Ok(output)
+                }
+                Err(e) => {
+                    if let Err(db_error) = store.finish_run(&run_id, Err(&e.to_string())) {
+                        log_warn!("[数据库] {}", db_error);
+                    }
+                    Err(e)
+                }
+            }
+        })
+    }
+
+    /// 在限速器许可下执行插件，返回原始输出和实际使用的后端
+    fn execute_plugin(&self, plugin: &SpiderPlugin, options: &BackendOptions, params: serde_json::Value) -> AppResult<BackendRun> {
+        // 手动和定时执行共用同一个请求预算：并发上限、请求间隔和退避
+        let _permit = self.limiter.acquire();
+
+        let result = match &plugin.entrypoint {
+            spider_registry::Entrypoint::Builtin { name } if name == spider_registry::DEFAULT_SPIDER_ID => {
+                let params = spider_params::validate_spider_params(&params)
+                    .map_err(AppError::validation)?;
+                self.run_builtin(params, options)
+            }
+            spider_registry::Entrypoint::Builtin { name } => {
+                return Err(AppError::invalid_input("spider.unknown", format!("未知的内置爬虫: {}", name)));
+            }
+            _ => {
+                let mut env = self.network_settings().env_vars();
+                env.extend(self.limiter.page_delay().env_vars());
+                plugin
+                    .run_external(self.services.processes.as_ref(), self.resolver.python().as_deref(), &env, &params)
+                    .map(|stdout| BackendRun { stdout, backend: Backend::Plugin, fallback_reason: None })
+                    .map_err(|e| AppError::new(ErrorKind::Execution, "spider.execution_failed", format!("插件 {} 执行失败", plugin.id)).detail(e))
+            }
+        };
+        self.limiter.record(match &result {
+            Ok(run) => CrawlOutcome::classify(&Ok(run.stdout.clone())),
+            Err(e) => CrawlOutcome::classify(&Err(e.to_string())),
+        });
+        result
+    }
+
+    /// 抓取指定公告的详情页和附件，返回成功的数量；每个详情页都经过限速器
+    pub fn fetch_details(&self, plugin: &SpiderPlugin, ids: &[i64]) -> usize {
+        let source = match &plugin.detail {
+            Some(source) => source,
+            None => {
+                log_warn!("[详情] 爬虫 {} 没有配置详情来源（detail），跳过详情抓取", plugin.id);
+                return 0;
+            }
+        };
+        let store = &self.store;
+        let targets = match store.detail_targets(ids) {
+            Ok(targets) => targets,
+            Err(e) => {
+                log_warn!("[详情] {}", e);
+                return 0;
+            }
+        };
+        let client = match self.network_settings().build_http_client() {
+            Ok(client) => client,
+            Err(e) => {
+                log_warn!("[详情] {}", e);
+                return 0;
+            }
+        };
+        let fetcher = DetailFetcher::new(&client, self.app_data_dir());
+
+        log_info!("[详情] 开始抓取 {} 条公告的详情页", targets.len());
+        let mut fetched = 0;
+        for (id, url) in targets {
+            let result = {
+                let _permit = self.limiter.acquire();
+                fetcher.fetch(source, &url)
+            };
+            self.limiter.record(match &result {
+                Ok(_) => CrawlOutcome::Success,
+                Err(e) => CrawlOutcome::classify(&Err(e.clone())),
+            });
+            let saved = result.and_then(|detail| {
+                log_info!("[详情] ✅ {}（{} 个附件）", url, detail.attachments.len());
+                store.save_detail(id, &detail)?;
+                // 详情页正文中提取中标候选人、报价、项目编号和代理机构
+                let notice = candidate_extract::extract_candidate_notice(&detail.text);
+                if let Some(notice) = &notice {
+                    log_info!("[提取] 项目编号: {:?}，候选人 {} 个", notice.project_number.as_ref().map(|f| &f.value), notice.candidates.len());
+                }
+                store.save_candidate_notice(id, notice.as_ref())
+            });
+            match saved {
+                Ok(()) => fetched += 1,
+                Err(e) => {
+                    log_error!("[详情] ❌ {}: {}", url, e);
+                    if let Err(db_error) = store.save_detail_error(id, &e) {
+                        log_warn!("[数据库] {}", db_error);
+                    }
+                }
+            }
+        }
+        fetched
+    }
+
+    /// 邮件正文中的新公告列表：标题、详情页链接和归档附件
+    pub fn new_announcements_text(&self, ids: &[i64]) -> String {
+        let locale = self.locale();
+        let label = |key: &str| i18n::text(locale, key);
+        let mut lines = vec![i18n::format(locale, "email.new_announcements", &[("count", &ids.len())])];
+        for announcement in ids.iter().filter_map(|id| self.store.get_announcement(*id).ok().flatten()) {
+            lines.push(format!("- {}", announcement.title));
+            if let Some(url) = announcement.detail_url.as_ref().or(announcement.url.as_ref()) {
+                lines.push(format!("  {}: {}", label("email.announcement_detail"), url));
+            }
+            if let Some(path) = &announcement.detail_path {
+                lines.push(format!("  {}: {}", label("email.announcement_archive"), path));
+            }
+            for attachment in &announcement.attachments {
+                lines.push(format!("  {}: {} ({})", label("email.announcement_attachment"), attachment.file_name, attachment.url));
+            }
+            if let Some(notice) = announcement.candidate_notice.as_ref().filter(|n| !n.candidates.is_empty()) {
+                lines.push(format!("  {}: {}", label("email.announcement_candidates"), notice.candidates_summary()));
+            }
+        }
+        lines.join("\n")
+    }
+}
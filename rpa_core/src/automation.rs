@@ -0,0 +1,141 @@
+// ============================================================================
+// Python 自动化脚本（demo）
+// ============================================================================
+//
+// 通过 stdin 把 JSON 任务发给 tauri-python-demo/scripts/main.py，从 stdout 读取结果。
+// 属于可选功能，只有 AppFeatures::automation 开启的应用提供（AppCore::run_automation）
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::json;
+
+use crate::error::{AppError, AppResult, ErrorKind};
+use crate::process::{ProcessRunner, ProcessSpec};
+
+/// 查找 Python 可执行文件
+/// 优先尝试 python3，然后尝试 python
+pub fn find_python() -> Result<String, String> {
+    let python_candidates = vec!["python3", "python"];
+
+    for python in python_candidates {
+        // 检查 Python 是否可用
+        if Command::new(python)
+            .arg("--version")
+            .output()
+            .is_ok()
+        {
+            return Ok(python.to_string());
+        }
+    }
+
+    Err("未找到 Python 可执行文件。请确保已安装 Python 3 并添加到 PATH".to_string())
+}
+
+/// 查找 Python 脚本路径
+/// 优先从资源目录查找（打包后的应用），然后从当前目录查找（开发环境）
+pub fn find_script(resource_dir: Option<&Path>) -> Result<PathBuf, String> {
+    // 1. 优先从应用资源目录查找（打包后的应用使用此路径）
+    if let Some(resource_dir) = resource_dir {
+        let script_path = resource_dir.join("scripts").join("main.py");
+        if script_path.exists() {
+            return Ok(script_path);
+        }
+    }
+
+    // 2. 从当前工作目录查找（开发环境使用）
+    if let Ok(mut search_dir) = std::env::current_dir() {
+        for _ in 0..5 {
+            let script_path = search_dir.join("tauri-python-demo").join("scripts").join("main.py");
+            if script_path.exists() {
+                return Ok(script_path);
+            }
+            if let Some(parent) = search_dir.parent() {
+                search_dir = parent.to_path_buf();
+            } else {
+                break;
+            }
+        }
+    }
+
+    Err("未找到 Python 脚本 main.py".to_string())
+}
+
+/// 运行自动化任务（阻塞）：启动脚本，写入任务 JSON，等待结果
+pub fn run(
+    runner: &dyn ProcessRunner,
+    resource_dir: Option<&Path>,
+    env: Vec<(String, String)>,
+    url: &str,
+    action: &str,
+    output: Option<&str>,
+) -> AppResult<String> {
+    // 查找 Python
+    let python = find_python().map_err(|e| {
+        AppError::new(ErrorKind::Environment, "environment.python_missing", e)
+            .hint("安装 Python 3 并添加到 PATH")
+    })?;
+
+    // 查找 Python 脚本
+    let script_path = find_script(resource_dir).map_err(|e| {
+        AppError::new(ErrorKind::Environment, "automation.script_missing", e)
+            .hint("确认 scripts/main.py 已随应用打包")
+    })?;
+
+    // 构造输入数据
+    let input_data = json!({
+        "url": url,
+        "action": action,
+        "output": output.unwrap_or("output.txt")
+    }).to_string();
+
+    // 启动 Python 进程，任务 JSON 通过 stdin 传入，等待进程完成并获取输出
+    let command = ProcessSpec::new(&python)
+        .arg(script_path.to_string_lossy())
+        .envs(env)
+        .stdin(input_data);
+    let output = runner.run(&command).map_err(|e| {
+        AppError::new(ErrorKind::Environment, "automation.spawn_failed", "启动 Python 进程失败")
+            .detail(e.to_string())
+    })?;
+
+    if output.success {
+        // 解析 stdout 中的 JSON 结果
+        let result = String::from_utf8(output.stdout)
+            .map_err(|e| AppError::from(format!("解析输出失败: {}", e)))?;
+        Ok(result)
+    } else {
+        // 读取 stderr 中的错误信息
+        let error = String::from_utf8(output.stderr)
+            .unwrap_or_else(|_| "未知错误".to_string());
+        Err(AppError::new(ErrorKind::Execution, "automation.failed", "Python 脚本执行失败").detail(error.trim()))
+    }
+}
+
+/// 检测 Python 环境
+pub fn check_python() -> AppResult<serde_json::Value> {
+    match find_python() {
+        Ok(python) => {
+            // 获取 Python 版本信息
+            let output = Command::new(&python)
+                .arg("--version")
+                .output()
+                .map_err(|e| format!("获取 Python 版本失败: {}", e))?;
+
+            let version = String::from_utf8(output.stdout)
+                .unwrap_or_else(|_| "未知版本".to_string())
+                .trim()
+                .to_string();
+
+            Ok(json!({
+                "available": true,
+                "python_path": python,
+                "version": version
+            }))
+        }
+        Err(e) => Ok(json!({
+            "available": false,
+            "error": e
+        }))
+    }
+}
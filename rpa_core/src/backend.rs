@@ -0,0 +1,293 @@
+// ============================================================================
+// 内置爬虫的执行后端选择与回退策略
+// ============================================================================
+//
+// 内置爬虫有两种执行方式：Python worker（spiders.py）和打包的 spider_bin。
+// 每个任务可以指定后端：
+//   auto:   先 Python，再打包版本
+//   python: 只用 Python worker（fallback 设为 on_environment_error 时先用 Python）
+//   binary: 只用打包的 spider_bin（fallback 设为 on_environment_error 时先用打包版本）
+// 回退只在环境错误时发生（找不到解释器或可执行文件、依赖包无法导入、进程无法启动），
+// 网站返回错误、超时等执行错误直接返回，避免同一次任务执行两遍。
+// 实际使用的后端和回退原因随执行结果一起返回
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::crawl_policy::CrawlOutcome;
+use crate::error::{AppError, ErrorKind};
+use crate::i18n::{self, Locale};
+use crate::spider_worker::{WorkerError, ENVIRONMENT_ERROR_CODE};
+
+/// 任务设置中的后端选择
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendChoice {
+    #[default]
+    Auto,
+    Python,
+    Binary,
+}
+
+/// 回退策略；未指定时 auto 回退，python / binary 不回退
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackPolicy {
+    /// 首选后端出现环境错误时改用另一个后端
+    #[default]
+    OnEnvironmentError,
+    /// 不回退，首选后端失败即返回错误
+    Never,
+}
+
+/// 实际执行的后端
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    /// 常驻的 Python worker
+    Python,
+    /// 打包的 spider_bin
+    Binary,
+    /// 外部插件自己的脚本或可执行文件
+    Plugin,
+}
+
+impl Backend {
+    /// 日志中使用的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            Backend::Python => "Python",
+            Backend::Binary => "打包版本",
+            Backend::Plugin => "插件",
+        }
+    }
+
+    /// 给用户看的名称
+    pub fn name(self, locale: Locale) -> String {
+        let key = match self {
+            Backend::Python => "backend.python",
+            Backend::Binary => "backend.binary",
+            Backend::Plugin => "backend.plugin",
+        };
+        i18n::text(locale, key)
+    }
+}
+
+/// 单次执行的后端设置
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BackendOptions {
+    #[serde(default)]
+    pub backend: BackendChoice,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<FallbackPolicy>,
+}
+
+impl BackendOptions {
+    /// 实际使用的回退策略：明确选择了后端时默认只用该后端
+    pub fn fallback_policy(&self) -> FallbackPolicy {
+        self.fallback.unwrap_or(match self.backend {
+            BackendChoice::Auto => FallbackPolicy::OnEnvironmentError,
+            BackendChoice::Python | BackendChoice::Binary => FallbackPolicy::Never,
+        })
+    }
+
+    /// 依次尝试的后端
+    pub fn order(&self) -> Vec<Backend> {
+        let order = match self.backend {
+            BackendChoice::Auto | BackendChoice::Python => [Backend::Python, Backend::Binary],
+            BackendChoice::Binary => [Backend::Binary, Backend::Python],
+        };
+        match self.fallback_policy() {
+            FallbackPolicy::OnEnvironmentError => order.to_vec(),
+            FallbackPolicy::Never => order[..1].to_vec(),
+        }
+    }
+}
+
+/// 后端执行失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackendError {
+    /// 环境问题，可以改用另一个后端
+    Environment(String),
+    /// 后端已经开始执行后失败（网站错误、超时等），不再回退
+    Execution(String),
+}
+
+impl BackendError {
+    /// Python worker 的失败，worker 已经区分了环境错误和执行错误
+    pub fn from_worker(error: WorkerError) -> Self {
+        match error {
+            WorkerError::Environment(message) => BackendError::Environment(message),
+            WorkerError::Execution(message) => BackendError::Execution(message),
+        }
+    }
+
+    /// spider_bin 失败时在 stderr 最后一行输出 {"error", "type", "code"}，错误码与 worker 协议相同；
+    /// 环境错误（依赖包无法导入、找不到 Node.js）可以回退，其余按原始输出作为执行错误
+    pub fn from_binary_stderr(stderr: String) -> Self {
+        let report = stderr
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .and_then(|line| serde_json::from_str::<Value>(line).ok());
+        match report {
+            Some(report) if report.get("code").and_then(Value::as_i64) == Some(ENVIRONMENT_ERROR_CODE) => {
+                let message = report.get("error").and_then(Value::as_str).unwrap_or("未知错误");
+                BackendError::Environment(message.to_string())
+            }
+            _ => BackendError::Execution(stderr),
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            BackendError::Environment(message) | BackendError::Execution(message) => message,
+        }
+    }
+
+    /// 开始执行后的失败，按错误内容区分验证码、网络错误和其他错误
+    fn into_execution_error(message: String, backend: Backend, locale: Locale) -> AppError {
+        let name = backend.name(locale);
+        let text = |key: &str| i18n::format(locale, key, &[("backend", &name)]);
+        match CrawlOutcome::classify(&Err(message.clone())) {
+            CrawlOutcome::Captcha => AppError::new(ErrorKind::Execution, "spider.captcha", text("spider.captcha"))
+                .detail(message)
+                .hint(text("spider.captcha.hint"))
+                .retryable(),
+            CrawlOutcome::HttpError => AppError::new(ErrorKind::Network, "network.request_failed", text("network.request_failed"))
+                .detail(message)
+                .hint(text("network.request_failed.hint"))
+                .retryable(),
+            _ => AppError::new(ErrorKind::Execution, "spider.execution_failed", text("spider.execution_failed")).detail(message),
+        }
+    }
+}
+
+/// 一次执行的结果
+#[derive(Debug, Clone)]
+pub struct BackendRun {
+    pub stdout: String,
+    pub backend: Backend,
+    /// 发生回退时首选后端的错误
+    pub fallback_reason: Option<String>,
+}
+
+/// 按 options 的顺序执行，只有环境错误才尝试下一个后端；locale 为错误说明和回退原因使用的语言
+pub fn run_with_fallback<F>(options: &BackendOptions, locale: Locale, mut run: F) -> Result<BackendRun, AppError>
+where
+    F: FnMut(Backend) -> Result<String, BackendError>,
+{
+    let mut environment_errors: Vec<(Backend, String)> = Vec::new();
+    for backend in options.order() {
+        match run(backend) {
+            Ok(stdout) => {
+                if let Some((failed, e)) = environment_errors.first() {
+                    log_info!("[爬虫] 已改用{}执行（{} 不可用: {}）", backend.label(), failed.label(), e);
+                }
+                let fallback_reason = environment_errors.first().map(|(failed, e)| {
+                    i18n::format(locale, "spider.unavailable", &[("backend", &failed.name(locale)), ("error", e)])
+                });
+                return Ok(BackendRun { stdout, backend, fallback_reason });
+            }
+            Err(BackendError::Execution(e)) => {
+                return Err(BackendError::into_execution_error(e, backend, locale));
+            }
+            Err(error) => {
+                log_warn!("[爬虫] {} 不可用: {}", backend.label(), error.message());
+                environment_errors.push((backend, error.message().to_string()));
+            }
+        }
+    }
+
+    let details: Vec<String> = environment_errors
+        .iter()
+        .map(|(backend, e)| format!("- {}: {}", backend.label(), e))
+        .collect();
+    Err(AppError::new(ErrorKind::Environment, "spider.no_backend", i18n::text(locale, "spider.no_backend"))
+        .detail(details.join("\n"))
+        .hint(i18n::text(locale, "spider.no_backend.hint_python"))
+        .hint(i18n::text(locale, "spider.no_backend.hint_binary"))
+        .hint(i18n::text(locale, "spider.no_backend.hint_diagnose")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(backend: BackendChoice, fallback: Option<FallbackPolicy>) -> Vec<Backend> {
+        BackendOptions { backend, fallback }.order()
+    }
+
+    #[test]
+    fn explicit_backend_is_used_alone_by_default() {
+        assert_eq!(order(BackendChoice::Auto, None), [Backend::Python, Backend::Binary]);
+        assert_eq!(order(BackendChoice::Python, None), [Backend::Python]);
+        assert_eq!(order(BackendChoice::Binary, None), [Backend::Binary]);
+        assert_eq!(BackendOptions::default().order(), [Backend::Python, Backend::Binary]);
+    }
+
+    #[test]
+    fn fallback_policy_overrides_default() {
+        let on_error = Some(FallbackPolicy::OnEnvironmentError);
+        let never = Some(FallbackPolicy::Never);
+        assert_eq!(order(BackendChoice::Auto, on_error), [Backend::Python, Backend::Binary]);
+        assert_eq!(order(BackendChoice::Python, on_error), [Backend::Python, Backend::Binary]);
+        assert_eq!(order(BackendChoice::Binary, on_error), [Backend::Binary, Backend::Python]);
+        assert_eq!(order(BackendChoice::Auto, never), [Backend::Python]);
+        assert_eq!(order(BackendChoice::Python, never), [Backend::Python]);
+        assert_eq!(order(BackendChoice::Binary, never), [Backend::Binary]);
+    }
+
+    #[test]
+    fn only_environment_errors_fall_back() {
+        let options = BackendOptions { backend: BackendChoice::Auto, fallback: None };
+        let mut tried = Vec::new();
+        let run = run_with_fallback(&options, Locale::default(), |backend| {
+            tried.push(backend);
+            match backend {
+                Backend::Python => Err(BackendError::from_worker(WorkerError::Environment("No module named 'ddddocr'".to_string()))),
+                _ => Ok("{}".to_string()),
+            }
+        })
+        .unwrap();
+        assert_eq!(run.backend, Backend::Binary);
+        assert!(run.fallback_reason.is_some());
+        assert_eq!(tried, [Backend::Python, Backend::Binary]);
+
+        // 错误信息中带有 ImportError 字样，但 worker 报告的是执行错误，不回退
+        tried.clear();
+        let error = run_with_fallback(&options, Locale::default(), |backend| {
+            tried.push(backend);
+            Err(BackendError::from_worker(WorkerError::Execution("ImportError: 页面返回异常".to_string())))
+        })
+        .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Execution);
+        assert_eq!(tried, [Backend::Python]);
+    }
+
+    #[test]
+    fn binary_stderr_code_decides_error_kind() {
+        let environment = r#"{"error": "未找到 Node.js。", "type": "RuntimeEnvironmentError", "code": -32001}"#;
+        assert_eq!(
+            BackendError::from_binary_stderr(format!("警告信息\n{}\n", environment)),
+            BackendError::Environment("未找到 Node.js。".to_string())
+        );
+
+        // 执行错误保留原始输出，供验证码和网络错误分类使用
+        let execution = r#"{"error": "Max retries exceeded", "type": "ConnectionError", "code": -32000}"#;
+        assert_eq!(
+            BackendError::from_binary_stderr(execution.to_string()),
+            BackendError::Execution(execution.to_string())
+        );
+        // 旧版 spider_bin 没有错误码，按执行错误处理
+        let legacy = r#"{"error": "No module named 'ddddocr'", "type": "ModuleNotFoundError"}"#;
+        assert_eq!(
+            BackendError::from_binary_stderr(legacy.to_string()),
+            BackendError::Execution(legacy.to_string())
+        );
+        assert_eq!(
+            BackendError::from_binary_stderr("Traceback (most recent call last):".to_string()),
+            BackendError::Execution("Traceback (most recent call last):".to_string())
+        );
+    }
+}
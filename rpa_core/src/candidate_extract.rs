@@ -0,0 +1,393 @@
+// ============================================================================
+// 中标候选人公示结构化提取
+// ============================================================================
+//
+// 从详情页纯文本（detail_fetch::html_to_text 的输出，表格单元格以制表符分隔）中提取：
+// 中标候选人名称、排名、投标报价、项目编号、招标代理机构。
+// 每个字段都带置信度和原文片段，便于人工核对：
+//   - 表格中按表头识别的列：0.9
+//   - "项目编号：xxx" 这类带标签的文本：0.85-0.9
+//   - 没有标签、只靠格式猜测：0.4-0.6
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 片段最大长度（字符）
+const SNIPPET_CHARS: usize = 120;
+
+/// 提取出的字段值，附带置信度（0-1）和原文片段
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Extracted<T> {
+    pub value: T,
+    pub confidence: f32,
+    pub snippet: String,
+}
+
+impl<T> Extracted<T> {
+    fn new(value: T, confidence: f32, snippet: &str) -> Self {
+        Extracted {
+            value,
+            confidence,
+            snippet: truncate(snippet.trim(), SNIPPET_CHARS),
+        }
+    }
+}
+
+/// 投标报价，统一换算为元
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BidPrice {
+    pub amount_yuan: f64,
+    /// 原文中的写法，如 "123.45万元"
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub rank: Extracted<u32>,
+    pub bidder: Extracted<String>,
+    pub bid_price: Option<Extracted<BidPrice>>,
+}
+
+/// 中标候选人公示的结构化结果
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct CandidateNotice {
+    pub project_number: Option<Extracted<String>>,
+    pub tender_agency: Option<Extracted<String>>,
+    pub candidates: Vec<Candidate>,
+}
+
+impl CandidateNotice {
+    pub fn is_empty(&self) -> bool {
+        self.project_number.is_none() && self.tender_agency.is_none() && self.candidates.is_empty()
+    }
+
+    /// 导出和邮件中使用的一行摘要，如 "1. 甲公司 ¥1234.00; 2. 乙公司"
+    pub fn candidates_summary(&self) -> String {
+        self.candidates
+            .iter()
+            .map(|c| match &c.bid_price {
+                Some(price) => format!("{}. {} ¥{:.2}", c.rank.value, c.bidder.value, price.value.amount_yuan),
+                None => format!("{}. {}", c.rank.value, c.bidder.value),
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}…", &text[..index]),
+        None => text.to_string(),
+    }
+}
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("内置正则表达式无效"))
+}
+
+fn project_number_label() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(&RE, r"(?:招标项目编号|项目编号|招标编号|项目编码|标段编号)\s*[:：]?\s*([A-Za-z0-9][A-Za-z0-9\-_/.()（）\[\]]{3,60})")
+}
+
+fn project_number_loose() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(&RE, r"\b([A-Z]{2,}[A-Z0-9]*-[A-Z0-9\-]{4,})\b")
+}
+
+fn agency_label() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(&RE, r"(?:招标代理机构|招标代理单位|代理机构)(?:名称)?\s*[:：]\s*([^\t\n，,。；;：:]{2,60})")
+}
+
+fn agency_loose() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(&RE, r"([\p{Han}（）()]{2,40}?招标[\p{Han}（）()]{0,20}?(?:有限责任公司|有限公司|中心|公司))")
+}
+
+fn rank_text() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(
+        &RE,
+        r"第\s*([一二三四五六七八九十\d]+)\s*(?:名|中标候选人|候选人)\s*[:：]?\s*(?:中标候选人)?\s*[:：]?\s*([^\t\n，,；;。:：]{2,60}?(?:有限责任公司|有限公司|股份公司|集团|公司|研究院|研究所|设计院|中心|厂|局|院|所))",
+    )
+}
+
+fn price_text() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(
+        &RE,
+        r"(?:投标报价|投标价格|报价|投标总价|评标价|金额)[^\d\n]{0,12}?([0-9][0-9,，]*(?:\.[0-9]+)?)\s*(万元|元)?",
+    )
+}
+
+fn amount() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(&RE, r"([0-9][0-9,，]*(?:\.[0-9]+)?)\s*(万元|元)?")
+}
+
+/// 解析金额，unit_hint 为表头中的单位（如 "投标报价（万元）"）
+fn parse_price(text: &str, unit_hint: Option<&str>) -> Option<BidPrice> {
+    let captures = amount().captures(text)?;
+    let number: f64 = captures[1].replace([',', '，'], "").parse().ok()?;
+    let unit = captures.get(2).map(|m| m.as_str()).or(unit_hint);
+    let amount_yuan = if unit == Some("万元") { number * 10_000.0 } else { number };
+    Some(BidPrice {
+        amount_yuan,
+        text: captures[0].trim().to_string(),
+    })
+}
+
+/// 中文或阿拉伯数字排名
+fn parse_rank(text: &str) -> Option<u32> {
+    let text = text.trim().trim_start_matches('第').trim_end_matches(['名', '位']).trim();
+    if let Ok(rank) = text.parse::<u32>() {
+        return Some(rank);
+    }
+    let digit = |c: char| "零一二三四五六七八九".chars().position(|d| d == c).map(|p| p as u32);
+    match text.chars().collect::<Vec<_>>().as_slice() {
+        ['十'] => Some(10),
+        [c] => digit(*c),
+        ['十', c] => digit(*c).map(|d| 10 + d),
+        _ => None,
+    }
+}
+
+/// 表头列定位
+struct TableColumns {
+    rank: Option<usize>,
+    bidder: usize,
+    price: Option<usize>,
+    price_unit: Option<&'static str>,
+}
+
+fn detect_header(cells: &[&str]) -> Option<TableColumns> {
+    let find = |keys: &[&str]| cells.iter().position(|cell| keys.iter().any(|k| cell.contains(k)));
+    let bidder = find(&["候选人名称", "投标人名称", "单位名称", "投标人", "中标候选人", "供应商名称", "企业名称"])?;
+    let rank = find(&["排名", "名次", "排序", "推荐顺序", "序号"]).filter(|i| *i != bidder);
+    let price = find(&["投标报价", "报价", "投标价格", "投标总价", "评标价", "金额"]).filter(|i| *i != bidder);
+    let price_unit = price.and_then(|i| if cells[i].contains("万元") { Some("万元") } else { None });
+    Some(TableColumns {
+        rank,
+        bidder,
+        price,
+        price_unit,
+    })
+}
+
+/// 从表格（制表符分隔的行）中提取候选人
+fn extract_from_table(text: &str) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+    let mut columns: Option<TableColumns> = None;
+
+    for line in text.lines() {
+        let cells: Vec<&str> = line.split('\t').map(str::trim).collect();
+        if cells.len() < 2 {
+            columns = None;
+            continue;
+        }
+        if let Some(header) = detect_header(&cells) {
+            // 表头行本身不含公司名；否则可能是"第一名 | 某公司"这种无表头的行
+            if !cells.iter().any(|c| c.contains("公司")) {
+                columns = Some(header);
+                continue;
+            }
+        }
+        let Some(cols) = &columns else { continue };
+        let Some(bidder) = cells.get(cols.bidder).filter(|c| c.chars().count() >= 2) else { continue };
+
+        let rank = cols
+            .rank
+            .and_then(|i| cells.get(i))
+            .and_then(|c| parse_rank(c))
+            .map(|r| Extracted::new(r, 0.9, line))
+            .unwrap_or_else(|| Extracted::new(candidates.len() as u32 + 1, 0.5, line));
+        let bid_price = cols
+            .price
+            .and_then(|i| cells.get(i))
+            .and_then(|c| parse_price(c, cols.price_unit))
+            .map(|p| Extracted::new(p, 0.9, line));
+
+        candidates.push(Candidate {
+            rank,
+            bidder: Extracted::new(bidder.to_string(), 0.9, line),
+            bid_price,
+        });
+    }
+    candidates
+}
+
+/// 从"第一名：某某公司，投标报价：123 万元"这类正文中提取候选人
+fn extract_from_text(text: &str) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for line in text.lines() {
+        let matches: Vec<_> = rank_text().captures_iter(line).collect();
+        for (index, captures) in matches.iter().enumerate() {
+            let Some(rank) = parse_rank(&captures[1]) else { continue };
+            if candidates.iter().any(|c| c.rank.value == rank) {
+                continue;
+            }
+            // 报价只在本候选人到下一个候选人之间查找
+            let whole = captures.get(0).unwrap();
+            let segment_end = matches.get(index + 1).map(|m| m.get(0).unwrap().start()).unwrap_or(line.len());
+            let segment = &line[whole.start()..segment_end];
+            let bid_price = price_text().captures(segment).and_then(|price| {
+                let unit = price.get(2).map(|m| m.as_str());
+                parse_price(&price[1], unit).map(|mut p| {
+                    p.text = price[0].trim().to_string();
+                    Extracted::new(p, 0.7, segment)
+                })
+            });
+            candidates.push(Candidate {
+                rank: Extracted::new(rank, 0.85, segment),
+                bidder: Extracted::new(captures[2].trim().to_string(), 0.7, segment),
+                bid_price,
+            });
+        }
+    }
+    candidates.sort_by_key(|c| c.rank.value);
+    candidates
+}
+
+/// 取匹配位置所在的整行作为原文片段
+fn line_of(text: &str, start: usize) -> &str {
+    let line_start = text[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = text[start..].find('\n').map(|i| start + i).unwrap_or(text.len());
+    &text[line_start..line_end]
+}
+
+fn extract_project_number(text: &str) -> Option<Extracted<String>> {
+    if let Some(captures) = project_number_label().captures(text) {
+        let value = captures[1].trim_end_matches(['.', '）', ')']).to_string();
+        return Some(Extracted::new(value, 0.9, line_of(text, captures.get(0).unwrap().start())));
+    }
+    project_number_loose()
+        .captures(text)
+        .map(|captures| Extracted::new(captures[1].to_string(), 0.4, line_of(text, captures.get(0).unwrap().start())))
+}
+
+fn extract_tender_agency(text: &str) -> Option<Extracted<String>> {
+    if let Some(captures) = agency_label().captures(text) {
+        return Some(Extracted::new(
+            captures[1].trim().to_string(),
+            0.85,
+            line_of(text, captures.get(0).unwrap().start()),
+        ));
+    }
+    agency_loose()
+        .captures(text)
+        .map(|captures| Extracted::new(captures[1].to_string(), 0.5, line_of(text, captures.get(0).unwrap().start())))
+}
+
+/// 从详情页纯文本中提取中标候选人公示；什么都没提取到时返回 None
+pub fn extract_candidate_notice(text: &str) -> Option<CandidateNotice> {
+    let mut candidates = extract_from_table(text);
+    if candidates.is_empty() {
+        candidates = extract_from_text(text);
+    }
+    let notice = CandidateNotice {
+        project_number: extract_project_number(text),
+        tender_agency: extract_tender_agency(text),
+        candidates,
+    };
+    if notice.is_empty() {
+        None
+    } else {
+        Some(notice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(notice: &CandidateNotice) -> Vec<(u32, &str, Option<f64>)> {
+        notice
+            .candidates
+            .iter()
+            .map(|c| (c.rank.value, c.bidder.value.as_str(), c.bid_price.as_ref().map(|p| p.value.amount_yuan)))
+            .collect()
+    }
+
+    #[test]
+    fn table_with_header_is_extracted() {
+        let text = "中标候选人公示\n\
+                    排名\t投标人名称\t投标报价（万元）\n\
+                    第一名\t甲石油工程有限公司\t1,234.5\n\
+                    2\t乙建设集团有限公司\t1300\n\
+                    项目编号：CNPC-2024-0001\n\
+                    招标代理机构：中油物采信息技术有限公司";
+        let notice = extract_candidate_notice(text).unwrap();
+        assert_eq!(
+            names(&notice),
+            vec![(1, "甲石油工程有限公司", Some(12_345_000.0)), (2, "乙建设集团有限公司", Some(13_000_000.0))]
+        );
+        assert_eq!(notice.candidates[0].rank.confidence, 0.9);
+        assert_eq!(notice.project_number.as_ref().unwrap().value, "CNPC-2024-0001");
+        assert_eq!(notice.tender_agency.as_ref().unwrap().value, "中油物采信息技术有限公司");
+        assert_eq!(notice.candidates_summary(), "1. 甲石油工程有限公司 ¥12345000.00; 2. 乙建设集团有限公司 ¥13000000.00");
+    }
+
+    #[test]
+    fn table_without_rank_column_numbers_rows() {
+        let text = "投标人\t报价\n甲公司\t100元\n乙公司\t95.5万元";
+        let notice = extract_candidate_notice(text).unwrap();
+        assert_eq!(names(&notice), vec![(1, "甲公司", Some(100.0)), (2, "乙公司", Some(955_000.0))]);
+        assert_eq!(notice.candidates[1].rank.confidence, 0.5);
+    }
+
+    #[test]
+    fn ranked_text_is_extracted_with_prices_per_candidate() {
+        let text = "第一中标候选人：甲石油工程有限公司，投标报价：123.45万元；\
+                    第二中标候选人：乙设计院，报价 980,000 元；第三名：丙工程公司";
+        let notice = extract_candidate_notice(text).unwrap();
+        assert_eq!(
+            names(&notice),
+            vec![(1, "甲石油工程有限公司", Some(1_234_500.0)), (2, "乙设计院", Some(980_000.0)), (3, "丙工程公司", None)]
+        );
+        let price = notice.candidates[0].bid_price.as_ref().unwrap();
+        assert_eq!(price.value.text, "投标报价：123.45万元");
+        assert_eq!(price.confidence, 0.7);
+    }
+
+    #[test]
+    fn project_number_and_agency_fall_back_to_loose_patterns() {
+        let notice = extract_candidate_notice("本项目 ZYWC-2024-LQ-0815，组织单位：东方招标有限公司").unwrap();
+        let number = notice.project_number.unwrap();
+        assert_eq!(number.value, "ZYWC-2024-LQ-0815");
+        assert_eq!(number.confidence, 0.4);
+        let agency = notice.tender_agency.unwrap();
+        assert_eq!(agency.value, "东方招标有限公司");
+        assert_eq!(agency.confidence, 0.5);
+
+        // 带标签的编号去掉末尾的句点
+        let notice = extract_candidate_notice("招标编号：ABC-123.").unwrap();
+        assert_eq!(notice.project_number.unwrap().value, "ABC-123");
+    }
+
+    #[test]
+    fn parse_rank_accepts_chinese_and_arabic_numbers() {
+        assert_eq!(parse_rank("第一名"), Some(1));
+        assert_eq!(parse_rank("3"), Some(3));
+        assert_eq!(parse_rank("十"), Some(10));
+        assert_eq!(parse_rank("第十二位"), Some(12));
+        assert_eq!(parse_rank("二十"), None);
+        assert_eq!(parse_rank("排名"), None);
+    }
+
+    #[test]
+    fn unrelated_text_yields_nothing() {
+        assert!(extract_candidate_notice("关于春节放假的通知").is_none());
+        assert!(extract_candidate_notice("").is_none());
+    }
+
+    #[test]
+    fn long_snippets_are_truncated() {
+        let line = format!("项目编号：ABCD-1234 {}", "说明".repeat(100));
+        let number = extract_candidate_notice(&line).unwrap().project_number.unwrap();
+        assert_eq!(number.snippet.chars().count(), SNIPPET_CHARS + 1);
+        assert!(number.snippet.ends_with('…'));
+    }
+}
@@ -0,0 +1,22 @@
+// ============================================================================
+// 时钟
+// ============================================================================
+//
+// 定时任务计算执行时间和等待时长时通过 AppCore 的 Clock 读取当前时间，而不是直接调用 Local::now()，
+// 测试中可以换成跟随 tokio 暂停时间前进的时钟（等待本身用 tokio::time::sleep）
+
+use chrono::{DateTime, Local};
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Local>;
+}
+
+/// 系统时钟
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
@@ -0,0 +1,324 @@
+// ============================================================================
+// 配置文件的安全写入与备份
+// ============================================================================
+//
+// 所有配置文件（spider_config.json、smtp_config.json 等）都通过 save 写入：
+//   1. 当前文件是有效的 JSON 时，先复制到 backups/<文件名>/<时间>.json
+//   2. 新内容写到同目录的临时文件（每次写入的文件名都不同）并 fsync
+//   3. rename 覆盖原文件（同一文件系统上是原子操作）
+// 写到一半崩溃只会留下临时文件，原文件保持完整。每个文件保留最近 MAX_BACKUPS 个备份；
+// 配置文件损坏时读取返回 config.corrupted 错误（而不是当作没有配置），可以用 restore_backup 恢复
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::{AppError, AppResult, ErrorKind};
+
+pub const BACKUP_DIR: &str = "backups";
+/// 每个配置文件保留的备份数量
+const MAX_BACKUPS: usize = 5;
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d_%H%M%S_%3f";
+const BACKUP_TIME_LEN: usize = 19;
+
+/// 一个配置文件备份
+#[derive(Serialize, Debug, Clone)]
+pub struct BackupInfo {
+    /// 备份标识，恢复时传回
+    pub id: String,
+    /// 所属的配置文件名
+    pub file: String,
+    pub created_at: String,
+    pub size: u64,
+}
+
+fn backup_dir(app_data_dir: &Path, file_name: &str) -> PathBuf {
+    app_data_dir.join(BACKUP_DIR).join(file_name)
+}
+
+/// 只接受不带路径的文件名，防止通过参数访问应用数据目录以外的文件
+fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("无效的文件名: {}", name));
+    }
+    Ok(())
+}
+
+/// 原子写入：写临时文件、fsync、rename；临时文件名带随机后缀，
+/// 同时保存同一个文件（界面、HTTP API、配置导入）时不会把别人写了一半的临时文件 rename 过去
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), String> {
+    let dir = path.parent().ok_or_else(|| format!("无效的文件路径: {:?}", path))?;
+    fs::create_dir_all(dir).map_err(|e| format!("无法创建目录: {}", e))?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = dir.join(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4().simple()));
+
+    let result = File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(content)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temp, path));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(format!("保存文件 {} 失败: {}", file_name, e));
+    }
+
+    // rename 本身也要落盘，否则断电后目录项可能还是旧的
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+/// 保存配置文件：备份当前的有效版本后原子写入，并清理多余的备份
+pub fn save(app_data_dir: &Path, file_name: &str, content: &[u8]) -> Result<(), String> {
+    check_name(file_name)?;
+    let path = app_data_dir.join(file_name);
+    if let Ok(current) = fs::read(&path) {
+        let unchanged = current == content;
+        if !unchanged && serde_json::from_slice::<serde_json::Value>(&current).is_ok() {
+            backup(app_data_dir, file_name, &current)?;
+        }
+    }
+    write_atomic(&path, content)
+}
+
+/// 序列化后保存
+pub fn save_json<T: Serialize>(app_data_dir: &Path, file_name: &str, value: &T) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(value).map_err(|e| format!("序列化 {} 失败: {}", file_name, e))?;
+    save(app_data_dir, file_name, &content)
+}
+
+fn backup(app_data_dir: &Path, file_name: &str, content: &[u8]) -> Result<(), String> {
+    let dir = backup_dir(app_data_dir, file_name);
+    fs::create_dir_all(&dir).map_err(|e| format!("无法创建备份目录: {}", e))?;
+    let stamp = Local::now().format(BACKUP_TIME_FORMAT).to_string();
+    let mut path = dir.join(format!("{}.json", stamp));
+    let mut suffix = 1;
+    while path.exists() {
+        path = dir.join(format!("{}_{}.json", stamp, suffix));
+        suffix += 1;
+    }
+    write_atomic(&path, content)?;
+
+    // 只保留最近的 MAX_BACKUPS 个
+    for old in list_backups(app_data_dir, file_name)?.into_iter().skip(MAX_BACKUPS) {
+        let _ = fs::remove_file(dir.join(&old.id));
+    }
+    Ok(())
+}
+
+/// 列出配置文件的备份，最新的在前
+pub fn list_backups(app_data_dir: &Path, file_name: &str) -> Result<Vec<BackupInfo>, String> {
+    check_name(file_name)?;
+    let entries = match fs::read_dir(backup_dir(app_data_dir, file_name)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("读取备份目录失败: {}", e)),
+    };
+    let mut backups: Vec<BackupInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            let stem = id.strip_suffix(".json")?;
+            // 同一毫秒内的多个备份带有 _<序号> 后缀
+            let stamp = stem.get(..BACKUP_TIME_LEN)?;
+            let created_at = NaiveDateTime::parse_from_str(stamp, BACKUP_TIME_FORMAT).ok()?;
+            Some(BackupInfo {
+                file: file_name.to_string(),
+                created_at: created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                id,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(backups)
+}
+
+/// 用备份覆盖配置文件；当前文件如果有效，会先成为一个新的备份
+pub fn restore_backup(app_data_dir: &Path, file_name: &str, backup_id: &str) -> Result<(), String> {
+    check_name(file_name)?;
+    check_name(backup_id)?;
+    let content = fs::read(backup_dir(app_data_dir, file_name).join(backup_id))
+        .map_err(|e| format!("读取备份 {} 失败: {}", backup_id, e))?;
+    serde_json::from_slice::<serde_json::Value>(&content)
+        .map_err(|e| format!("备份 {} 已损坏: {}", backup_id, e))?;
+    save(app_data_dir, file_name, &content)
+}
+
+/// 读取配置文件，不存在时返回 None
+pub fn read(app_data_dir: &Path, file_name: &str) -> AppResult<Option<String>> {
+    match fs::read_to_string(app_data_dir.join(file_name)) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(AppError::config(format!("读取配置文件 {} 失败", file_name)).detail(e.to_string())),
+    }
+}
+
+/// 配置文件损坏时的错误，提示可以从备份恢复
+pub fn corrupted(app_data_dir: &Path, file_name: &str, cause: impl std::fmt::Display) -> AppError {
+    let error = match list_backups(app_data_dir, file_name).map(|backups| backups.len()) {
+        Ok(count) if count > 0 => AppError::new(
+            ErrorKind::Config,
+            "config.corrupted",
+            format!("配置文件 {} 已损坏，有 {} 个历史版本可以恢复", file_name, count),
+        )
+        .hint("在历史版本中选择一个恢复（restore_config_backup）"),
+        _ => AppError::new(
+            ErrorKind::Config,
+            "config.corrupted",
+            format!("配置文件 {} 已损坏，且没有可以恢复的历史版本", file_name),
+        )
+        .hint("重新填写并保存配置"),
+    };
+    error.detail(cause.to_string())
+}
+
+/// 读取并解析配置文件；不存在时返回 None，解析失败时返回可恢复的错误
+pub fn load_json<T: DeserializeOwned>(app_data_dir: &Path, file_name: &str) -> AppResult<Option<T>> {
+    match read(app_data_dir, file_name)? {
+        Some(content) => serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| corrupted(app_data_dir, file_name, e)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    const FILE: &str = "spider_config.json";
+
+    fn config(n: usize) -> Vec<u8> {
+        format!("{{\"n\": {}}}", n).into_bytes()
+    }
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn write_atomic_replaces_file_and_leaves_no_temp_files() {
+        let dir = TempDir::new("config_file");
+        let path = dir.path().join("nested").join(FILE);
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert_eq!(entries(path.parent().unwrap()), vec![FILE]);
+    }
+
+    #[test]
+    fn concurrent_writes_never_publish_a_partial_file() {
+        let dir = TempDir::new("config_file");
+        let path = dir.path().join(FILE);
+        // 每个线程写入不同长度的内容，最终文件必须完整等于其中一个
+        let contents: Vec<Vec<u8>> = (0..8).map(|i| vec![b'a' + i as u8; 64 * 1024 * (i + 1)]).collect();
+        std::thread::scope(|scope| {
+            for content in &contents {
+                let path = &path;
+                scope.spawn(move || {
+                    for _ in 0..10 {
+                        write_atomic(path, content).unwrap();
+                    }
+                });
+            }
+        });
+        let written = fs::read(&path).unwrap();
+        assert!(contents.contains(&written));
+        assert_eq!(entries(dir.path()), vec![FILE]);
+    }
+
+    #[test]
+    fn save_keeps_the_latest_backups() {
+        let dir = TempDir::new("config_file");
+        for n in 0..MAX_BACKUPS + 3 {
+            save(dir.path(), FILE, &config(n)).unwrap();
+        }
+        // 内容没有变化时不产生备份
+        save(dir.path(), FILE, &config(MAX_BACKUPS + 2)).unwrap();
+
+        let backups = list_backups(dir.path(), FILE).unwrap();
+        assert_eq!(backups.len(), MAX_BACKUPS);
+        assert!(backups.iter().all(|b| b.file == FILE && b.size > 0));
+        // 最新的在前：备份的是每次保存前的版本
+        let backed_up: Vec<Vec<u8>> = backups
+            .iter()
+            .map(|b| fs::read(backup_dir(dir.path(), FILE).join(&b.id)).unwrap())
+            .collect();
+        let expected: Vec<Vec<u8>> = (0..MAX_BACKUPS + 2).rev().take(MAX_BACKUPS).map(config).collect();
+        assert_eq!(backed_up, expected);
+    }
+
+    #[test]
+    fn invalid_current_file_is_not_backed_up() {
+        let dir = TempDir::new("config_file");
+        fs::write(dir.path().join(FILE), b"{ broken").unwrap();
+        save(dir.path(), FILE, &config(1)).unwrap();
+        assert!(list_backups(dir.path(), FILE).unwrap().is_empty());
+        assert_eq!(fs::read(dir.path().join(FILE)).unwrap(), config(1));
+    }
+
+    #[test]
+    fn corrupted_file_is_reported_with_backup_count() {
+        let dir = TempDir::new("config_file");
+        assert_eq!(load_json::<serde_json::Value>(dir.path(), FILE).unwrap(), None);
+
+        fs::write(dir.path().join(FILE), b"{ broken").unwrap();
+        let error = load_json::<serde_json::Value>(dir.path(), FILE).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Config);
+        assert_eq!(error.code, "config.corrupted");
+        assert!(error.message.contains("没有可以恢复的历史版本"), "{}", error.message);
+
+        save(dir.path(), FILE, &config(1)).unwrap();
+        save(dir.path(), FILE, &config(2)).unwrap();
+        fs::write(dir.path().join(FILE), b"{ broken").unwrap();
+        let error = load_json::<serde_json::Value>(dir.path(), FILE).unwrap_err();
+        assert!(error.message.contains("有 1 个历史版本"), "{}", error.message);
+        assert!(error.detail.is_some());
+    }
+
+    #[test]
+    fn restore_backup_overwrites_and_backs_up_current() {
+        let dir = TempDir::new("config_file");
+        save(dir.path(), FILE, &config(1)).unwrap();
+        save(dir.path(), FILE, &config(2)).unwrap();
+        let backup = list_backups(dir.path(), FILE).unwrap().remove(0);
+
+        restore_backup(dir.path(), FILE, &backup.id).unwrap();
+        assert_eq!(fs::read(dir.path().join(FILE)).unwrap(), config(1));
+        let newest = list_backups(dir.path(), FILE).unwrap().remove(0);
+        assert_eq!(fs::read(backup_dir(dir.path(), FILE).join(newest.id)).unwrap(), config(2));
+
+        // 损坏的备份不会覆盖当前文件
+        fs::write(backup_dir(dir.path(), FILE).join(&backup.id), b"{ broken").unwrap();
+        assert!(restore_backup(dir.path(), FILE, &backup.id).unwrap_err().contains("已损坏"));
+        assert_eq!(fs::read(dir.path().join(FILE)).unwrap(), config(1));
+    }
+
+    #[test]
+    fn names_with_paths_are_rejected() {
+        let dir = TempDir::new("config_file");
+        for name in ["", "../evil.json", "a/b.json", "a\\b.json", ".hidden"] {
+            assert!(save(dir.path(), name, b"{}").is_err(), "{}", name);
+            assert!(list_backups(dir.path(), name).is_err(), "{}", name);
+        }
+        assert!(restore_backup(dir.path(), FILE, "../spider_config.json").is_err());
+    }
+}
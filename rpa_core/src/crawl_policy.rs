@@ -0,0 +1,520 @@
+// ============================================================================
+// 限速与礼貌爬取
+// ============================================================================
+//
+// 所有爬虫执行（手动和定时）都经过 CrawlLimiter：
+//   - 限制同时运行的爬虫数量
+//   - 两次页面请求之间至少间隔 min_page_delay_ms，并加上随机抖动；
+//     一次执行内部的多个页面请求由爬虫按 PageDelay 自行等待
+//   - 验证码（511）或 HTTP 错误在时间窗口内集中出现时，自动指数退避
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config_file;
+use crate::error::AppResult;
+
+pub const SETTINGS_FILE: &str = "crawl_policy.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CrawlPolicy {
+    /// 同时运行的爬虫数量上限
+    #[serde(default = "default_max_concurrent_runs")]
+    pub max_concurrent_runs: u32,
+    /// 两次页面请求之间的最小间隔（毫秒）
+    #[serde(default = "default_min_page_delay_ms")]
+    pub min_page_delay_ms: u64,
+    /// 在最小间隔基础上追加的随机抖动上限（毫秒）
+    #[serde(default = "default_jitter_ms")]
+    pub jitter_ms: u64,
+    #[serde(default = "default_true")]
+    pub backoff_enabled: bool,
+    /// 时间窗口内出现多少次验证码/HTTP 错误后开始退避
+    #[serde(default = "default_backoff_threshold")]
+    pub backoff_threshold: u32,
+    #[serde(default = "default_backoff_window_secs")]
+    pub backoff_window_secs: u64,
+    #[serde(default = "default_backoff_base_secs")]
+    pub backoff_base_secs: u64,
+    #[serde(default = "default_backoff_max_secs")]
+    pub backoff_max_secs: u64,
+}
+
+fn default_max_concurrent_runs() -> u32 {
+    1
+}
+fn default_min_page_delay_ms() -> u64 {
+    3000
+}
+fn default_jitter_ms() -> u64 {
+    2000
+}
+fn default_true() -> bool {
+    true
+}
+fn default_backoff_threshold() -> u32 {
+    3
+}
+fn default_backoff_window_secs() -> u64 {
+    600
+}
+fn default_backoff_base_secs() -> u64 {
+    30
+}
+fn default_backoff_max_secs() -> u64 {
+    900
+}
+
+impl Default for CrawlPolicy {
+    fn default() -> Self {
+        CrawlPolicy {
+            max_concurrent_runs: default_max_concurrent_runs(),
+            min_page_delay_ms: default_min_page_delay_ms(),
+            jitter_ms: default_jitter_ms(),
+            backoff_enabled: true,
+            backoff_threshold: default_backoff_threshold(),
+            backoff_window_secs: default_backoff_window_secs(),
+            backoff_base_secs: default_backoff_base_secs(),
+            backoff_max_secs: default_backoff_max_secs(),
+        }
+    }
+}
+
+impl CrawlPolicy {
+    /// 读取设置；文件不存在时使用默认值，文件损坏时返回可恢复的错误
+    pub fn load(app_data_dir: &Path) -> AppResult<Self> {
+        Ok(config_file::load_json(app_data_dir, SETTINGS_FILE)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, app_data_dir: &Path) -> Result<(), String> {
+        self.validate()?;
+        config_file::save_json(app_data_dir, SETTINGS_FILE, self)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_concurrent_runs == 0 || self.max_concurrent_runs > 8 {
+            return Err("同时运行的爬虫数量必须在 1-8 之间".to_string());
+        }
+        if self.backoff_enabled && self.backoff_threshold == 0 {
+            return Err("退避阈值必须大于 0".to_string());
+        }
+        if self.backoff_base_secs > self.backoff_max_secs {
+            return Err("退避初始时间不能大于最大退避时间".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 传给爬虫进程的页面请求间隔：内置爬虫从参数的 page_delay 字段读取，
+/// 插件从 RPA_PAGE_DELAY_MS / RPA_PAGE_JITTER_MS 环境变量读取
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageDelay {
+    pub min_ms: u64,
+    pub jitter_ms: u64,
+}
+
+impl PageDelay {
+    pub const PARAM: &'static str = "page_delay";
+
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        vec![
+            ("RPA_PAGE_DELAY_MS".to_string(), self.min_ms.to_string()),
+            ("RPA_PAGE_JITTER_MS".to_string(), self.jitter_ms.to_string()),
+        ]
+    }
+}
+
+/// 单次爬虫执行的结果分类，用于判断是否需要退避
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrawlOutcome {
+    Success,
+    Captcha,
+    HttpError,
+    OtherError,
+}
+
+impl CrawlOutcome {
+    /// 根据爬虫输出或错误信息判断结果类型
+    pub fn classify(result: &Result<String, String>) -> Self {
+        match result {
+            Ok(output) => {
+                let compact: String = output.chars().filter(|c| !c.is_whitespace()).collect();
+                // 验证码重试耗尽时 spiders.py 返回 None
+                if compact.contains("\"code\":\"511\"") || compact == "{\"result\":\"None\"}" {
+                    CrawlOutcome::Captcha
+                } else {
+                    CrawlOutcome::Success
+                }
+            }
+            Err(e) => {
+                let lower = e.to_lowercase();
+                if lower.contains("511") || e.contains("验证码") {
+                    CrawlOutcome::Captcha
+                } else if ["connectionerror", "connectionpool", "max retries", "httperror", "timeout", "sslerror", "proxyerror"]
+                    .iter()
+                    .any(|k| lower.contains(k))
+                    || e.contains("超时")
+                {
+                    CrawlOutcome::HttpError
+                } else {
+                    CrawlOutcome::OtherError
+                }
+            }
+        }
+    }
+}
+
+/// 限速器状态，供前端展示
+#[derive(Serialize, Debug, Clone)]
+pub struct CrawlStatus {
+    pub policy: CrawlPolicy,
+    pub running: u32,
+    pub waiting: u32,
+    pub recent_failures: usize,
+    pub backoff_remaining_secs: u64,
+}
+
+struct LimiterState {
+    policy: CrawlPolicy,
+    running: u32,
+    waiting: u32,
+    last_request: Option<Instant>,
+    recent_failures: VecDeque<Instant>,
+    backoff_until: Option<Instant>,
+}
+
+/// 全局请求预算，放在 Tauri 的 managed state 中
+pub struct CrawlLimiter {
+    state: Mutex<LimiterState>,
+    slot_released: Condvar,
+}
+
+/// 运行许可，drop 时释放并发名额
+pub struct RunPermit<'a> {
+    limiter: &'a CrawlLimiter,
+}
+
+impl Drop for RunPermit<'_> {
+    fn drop(&mut self) {
+        let mut state = self.limiter.state.lock().unwrap();
+        state.running = state.running.saturating_sub(1);
+        // 执行期间爬虫自己还会发请求，下一次执行的间隔从本次结束时算起
+        let now = Instant::now();
+        state.last_request = Some(state.last_request.map_or(now, |last| last.max(now)));
+        self.limiter.slot_released.notify_one();
+    }
+}
+
+impl Default for CrawlLimiter {
+    fn default() -> Self {
+        Self::new(CrawlPolicy::default())
+    }
+}
+
+impl CrawlLimiter {
+    pub fn new(policy: CrawlPolicy) -> Self {
+        CrawlLimiter {
+            state: Mutex::new(LimiterState {
+                policy,
+                running: 0,
+                waiting: 0,
+                last_request: None,
+                recent_failures: VecDeque::new(),
+                backoff_until: None,
+            }),
+            slot_released: Condvar::new(),
+        }
+    }
+
+    pub fn set_policy(&self, policy: CrawlPolicy) {
+        self.state.lock().unwrap().policy = policy;
+        // 并发上限可能变大，唤醒所有等待者重新检查
+        self.slot_released.notify_all();
+    }
+
+    /// 当前策略下爬虫在一次执行内部两次页面请求之间应等待的时间
+    pub fn page_delay(&self) -> PageDelay {
+        let state = self.state.lock().unwrap();
+        PageDelay {
+            min_ms: state.policy.min_page_delay_ms,
+            jitter_ms: state.policy.jitter_ms,
+        }
+    }
+
+    /// 获取运行名额，并等待到允许发起下一次页面请求的时间（阻塞，需在后台线程调用）
+    pub fn acquire(&self) -> RunPermit<'_> {
+        let mut state = self.state.lock().unwrap();
+        state.waiting += 1;
+        while state.running >= state.policy.max_concurrent_runs {
+            state = self.slot_released.wait(state).unwrap();
+        }
+        state.waiting -= 1;
+        state.running += 1;
+
+        // 计算需要等待的时间：最小间隔 + 抖动，以及退避
+        let now = Instant::now();
+        let mut ready_at = now;
+        if let Some(last) = state.last_request {
+            let delay = state.policy.min_page_delay_ms + jitter(state.policy.jitter_ms);
+            ready_at = ready_at.max(last + Duration::from_millis(delay));
+        }
+        if let Some(until) = state.backoff_until {
+            ready_at = ready_at.max(until);
+        }
+        // 先占住请求时间，避免并发的其他调用方在同一时刻一起醒来
+        state.last_request = Some(ready_at);
+        drop(state);
+
+        if ready_at > now {
+            let wait = ready_at - now;
+            log_info!("[限速] 等待 {:.1} 秒后发起请求", wait.as_secs_f64());
+            std::thread::sleep(wait);
+        }
+
+        RunPermit { limiter: self }
+    }
+
+    /// 记录一次执行结果，验证码或 HTTP 错误集中出现时触发退避
+    pub fn record(&self, outcome: CrawlOutcome) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let window = Duration::from_secs(state.policy.backoff_window_secs);
+        while let Some(first) = state.recent_failures.front() {
+            if now.duration_since(*first) > window {
+                state.recent_failures.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        match outcome {
+            CrawlOutcome::Success => {
+                state.recent_failures.clear();
+                state.backoff_until = None;
+            }
+            CrawlOutcome::Captcha | CrawlOutcome::HttpError => {
+                state.recent_failures.push_back(now);
+                let failures = state.recent_failures.len() as u32;
+                if state.policy.backoff_enabled && failures >= state.policy.backoff_threshold {
+                    let exponent = (failures - state.policy.backoff_threshold).min(16);
+                    let secs = state
+                        .policy
+                        .backoff_base_secs
+                        .saturating_mul(1u64 << exponent)
+                        .min(state.policy.backoff_max_secs);
+                    state.backoff_until = Some(now + Duration::from_secs(secs));
+                    log_warn!("[限速] ⚠️ {} 次验证码/HTTP 错误，退避 {} 秒", failures, secs);
+                }
+            }
+            CrawlOutcome::OtherError => {}
+        }
+    }
+
+    pub fn status(&self) -> CrawlStatus {
+        let state = self.state.lock().unwrap();
+        let backoff_remaining_secs = state
+            .backoff_until
+            .map(|until| until.saturating_duration_since(Instant::now()).as_secs())
+            .unwrap_or(0);
+        CrawlStatus {
+            policy: state.policy.clone(),
+            running: state.running,
+            waiting: state.waiting,
+            recent_failures: state.recent_failures.len(),
+            backoff_remaining_secs,
+        }
+    }
+}
+
+/// 0..=max 毫秒的随机抖动
+fn jitter(max: u64) -> u64 {
+    if max == 0 {
+        return 0;
+    }
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    nanos % (max + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::sync::Arc;
+
+    /// 不等待的策略，只测退避和并发
+    fn policy(threshold: u32, base: u64, max: u64) -> CrawlPolicy {
+        CrawlPolicy {
+            min_page_delay_ms: 0,
+            jitter_ms: 0,
+            backoff_threshold: threshold,
+            backoff_base_secs: base,
+            backoff_max_secs: max,
+            ..CrawlPolicy::default()
+        }
+    }
+
+    /// 剩余退避秒数（向下取整，允许测试执行期间流逝的一秒）
+    fn assert_backoff(limiter: &CrawlLimiter, secs: u64) {
+        let remaining = limiter.status().backoff_remaining_secs;
+        assert!(remaining == secs || remaining + 1 == secs, "退避 {} 秒，期望 {} 秒", remaining, secs);
+    }
+
+    #[test]
+    fn classify_outputs_and_errors() {
+        let ok = |s: &str| CrawlOutcome::classify(&Ok(s.to_string()));
+        let err = |s: &str| CrawlOutcome::classify(&Err(s.to_string()));
+
+        assert_eq!(ok(r#"{"data": {"list": []}}"#), CrawlOutcome::Success);
+        assert_eq!(ok(r#"{"code": "511", "message": "请输入验证码"}"#), CrawlOutcome::Captcha);
+        assert_eq!(ok(r#"{"result": "None"}"#), CrawlOutcome::Captcha);
+        assert_eq!(err("HTTP 511"), CrawlOutcome::Captcha);
+        assert_eq!(err("验证码识别失败"), CrawlOutcome::Captcha);
+        assert_eq!(err("ConnectionError: Max retries exceeded"), CrawlOutcome::HttpError);
+        assert_eq!(err("ReadTimeout"), CrawlOutcome::HttpError);
+        assert_eq!(err("请求超时"), CrawlOutcome::HttpError);
+        assert_eq!(err("ProxyError: Cannot connect to proxy"), CrawlOutcome::HttpError);
+        assert_eq!(err("KeyError: 'data'"), CrawlOutcome::OtherError);
+    }
+
+    #[test]
+    fn backoff_starts_at_threshold_and_doubles_up_to_max() {
+        let limiter = CrawlLimiter::new(policy(2, 10, 25));
+
+        limiter.record(CrawlOutcome::Captcha);
+        assert_eq!(limiter.status().backoff_remaining_secs, 0);
+        limiter.record(CrawlOutcome::HttpError);
+        assert_backoff(&limiter, 10);
+        limiter.record(CrawlOutcome::Captcha);
+        assert_backoff(&limiter, 20);
+        limiter.record(CrawlOutcome::Captcha);
+        assert_backoff(&limiter, 25);
+        assert_eq!(limiter.status().recent_failures, 4);
+    }
+
+    #[test]
+    fn backoff_exponent_is_capped() {
+        let limiter = CrawlLimiter::new(policy(1, 1, u64::MAX));
+        for _ in 0..40 {
+            limiter.record(CrawlOutcome::Captcha);
+        }
+        assert_backoff(&limiter, 1 << 16);
+    }
+
+    #[test]
+    fn other_errors_do_not_count_and_success_resets() {
+        let limiter = CrawlLimiter::new(policy(2, 10, 100));
+        limiter.record(CrawlOutcome::OtherError);
+        limiter.record(CrawlOutcome::Captcha);
+        assert_eq!(limiter.status().backoff_remaining_secs, 0);
+        limiter.record(CrawlOutcome::Captcha);
+        assert_backoff(&limiter, 10);
+
+        limiter.record(CrawlOutcome::Success);
+        let status = limiter.status();
+        assert_eq!(status.backoff_remaining_secs, 0);
+        assert_eq!(status.recent_failures, 0);
+
+        // 重置后重新从阈值开始计数
+        limiter.record(CrawlOutcome::Captcha);
+        assert_eq!(limiter.status().backoff_remaining_secs, 0);
+    }
+
+    #[test]
+    fn disabled_backoff_only_counts_failures() {
+        let limiter = CrawlLimiter::new(CrawlPolicy { backoff_enabled: false, ..policy(1, 10, 100) });
+        limiter.record(CrawlOutcome::Captcha);
+        limiter.record(CrawlOutcome::HttpError);
+        let status = limiter.status();
+        assert_eq!(status.recent_failures, 2);
+        assert_eq!(status.backoff_remaining_secs, 0);
+    }
+
+    #[test]
+    fn max_concurrent_runs_blocks_until_a_permit_is_dropped() {
+        let limiter = Arc::new(CrawlLimiter::new(policy(3, 30, 900)));
+        let permit = limiter.acquire();
+        assert_eq!(limiter.status().running, 1);
+
+        let (tx, rx) = mpsc::channel();
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            std::thread::spawn(move || {
+                let _permit = limiter.acquire();
+                tx.send(()).unwrap();
+            })
+        };
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err(), "超过并发上限时应该等待");
+        assert_eq!(limiter.status().waiting, 1);
+
+        drop(permit);
+        rx.recv_timeout(Duration::from_secs(5)).expect("释放名额后应该继续执行");
+        waiter.join().unwrap();
+        let status = limiter.status();
+        assert_eq!((status.running, status.waiting), (0, 0));
+    }
+
+    #[test]
+    fn raising_the_limit_wakes_waiters() {
+        let limiter = Arc::new(CrawlLimiter::new(policy(3, 30, 900)));
+        let _permit = limiter.acquire();
+
+        let (tx, rx) = mpsc::channel();
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            std::thread::spawn(move || {
+                let _permit = limiter.acquire();
+                tx.send(()).unwrap();
+            })
+        };
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+
+        limiter.set_policy(CrawlPolicy { max_concurrent_runs: 2, ..policy(3, 30, 900) });
+        rx.recv_timeout(Duration::from_secs(5)).expect("上限变大后应该继续执行");
+        waiter.join().unwrap();
+    }
+
+    #[test]
+    fn next_run_waits_min_delay_after_previous_run_ends() {
+        let limiter = CrawlLimiter::new(CrawlPolicy { min_page_delay_ms: 150, ..policy(3, 30, 900) });
+        let first = limiter.acquire();
+        // 模拟爬虫在一次执行内部继续请求的时间
+        std::thread::sleep(Duration::from_millis(100));
+        drop(first);
+
+        let started = Instant::now();
+        drop(limiter.acquire());
+        assert!(started.elapsed() >= Duration::from_millis(150), "间隔应从上一次执行结束时算起");
+    }
+
+    #[test]
+    fn page_delay_follows_policy() {
+        let limiter = CrawlLimiter::default();
+        limiter.set_policy(CrawlPolicy { min_page_delay_ms: 1200, jitter_ms: 300, ..CrawlPolicy::default() });
+        let delay = limiter.page_delay();
+        assert_eq!(delay, PageDelay { min_ms: 1200, jitter_ms: 300 });
+        assert_eq!(
+            delay.env_vars(),
+            vec![
+                ("RPA_PAGE_DELAY_MS".to_string(), "1200".to_string()),
+                ("RPA_PAGE_JITTER_MS".to_string(), "300".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_values() {
+        assert!(CrawlPolicy::default().validate().is_ok());
+        assert!(CrawlPolicy { max_concurrent_runs: 0, ..CrawlPolicy::default() }.validate().is_err());
+        assert!(CrawlPolicy { max_concurrent_runs: 9, ..CrawlPolicy::default() }.validate().is_err());
+        assert!(CrawlPolicy { backoff_threshold: 0, ..CrawlPolicy::default() }.validate().is_err());
+        assert!(CrawlPolicy { backoff_threshold: 0, backoff_enabled: false, ..CrawlPolicy::default() }.validate().is_ok());
+        assert!(CrawlPolicy { backoff_base_secs: 1000, ..CrawlPolicy::default() }.validate().is_err());
+    }
+}
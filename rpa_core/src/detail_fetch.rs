@@ -0,0 +1,462 @@
+// ============================================================================
+// 公告详情页与附件抓取
+// ============================================================================
+//
+// 列表接口只返回摘要，中标候选人、报价和 PDF 都在详情页上。
+// 详情页和附件按内容的 SHA-256 存放在应用数据目录的 archive/ 下：
+//   archive/<哈希前两位>/<哈希>.<扩展名>
+// 相同内容只存一份，数据库中记录哈希和路径
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use reqwest::blocking::Client;
+use reqwest::Url;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::spider_registry::DetailSource;
+use crate::store::{AttachmentRecord, DetailRecord};
+
+pub const ARCHIVE_DIR: &str = "archive";
+/// 单个详情页最多下载的附件数量
+const MAX_ATTACHMENTS: usize = 20;
+/// 单个文件大小上限
+const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// 详情抓取器，使用进程内的 HTTP 客户端（已应用代理设置）
+pub struct DetailFetcher<'a> {
+    client: &'a Client,
+    archive_root: PathBuf,
+}
+
+impl<'a> DetailFetcher<'a> {
+    pub fn new(client: &'a Client, app_data_dir: &Path) -> Self {
+        DetailFetcher {
+            client,
+            archive_root: app_data_dir.join(ARCHIVE_DIR),
+        }
+    }
+
+    /// 抓取详情页并下载其中的附件；单个附件失败只记录日志，不影响详情页
+    pub fn fetch(&self, source: &DetailSource, url: &str) -> Result<DetailRecord, String> {
+        let base = Url::parse(url).map_err(|e| format!("详情页地址无效 {}: {}", url, e))?;
+        let body = self.download(&base)?;
+        let extension = if source.content_path.is_empty() { "html" } else { "json" };
+        let (sha256, path) = self.archive(&body, extension)?;
+
+        let page = String::from_utf8_lossy(&body);
+        let html = if source.content_path.is_empty() {
+            page.into_owned()
+        } else {
+            let json: Value = serde_json::from_str(&page)
+                .map_err(|e| format!("详情接口返回的不是 JSON: {}", e))?;
+            match json.pointer(&source.content_path) {
+                Some(Value::String(html)) => html.clone(),
+                Some(other) => other.to_string(),
+                None => return Err(format!("详情接口返回中没有 {}", source.content_path)),
+            }
+        };
+
+        let mut attachments = Vec::new();
+        for link in attachment_links(&html, &base, &source.attachment_extensions) {
+            match self.fetch_attachment(&link) {
+                Ok(attachment) => attachments.push(attachment),
+                Err(e) => log_warn!("[详情] 附件下载失败 {}: {}", link, e),
+            }
+        }
+
+        Ok(DetailRecord {
+            url: url.to_string(),
+            sha256,
+            path: path.to_string_lossy().to_string(),
+            text: html_to_text(&html),
+            attachments,
+        })
+    }
+
+    fn fetch_attachment(&self, url: &Url) -> Result<AttachmentRecord, String> {
+        let body = self.download(url)?;
+        let file_name = file_name_from_url(url);
+        let extension = Path::new(&file_name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("bin")
+            .to_lowercase();
+        let (sha256, path) = self.archive(&body, &extension)?;
+        Ok(AttachmentRecord {
+            url: url.to_string(),
+            file_name,
+            sha256,
+            size: body.len() as u64,
+            path: path.to_string_lossy().to_string(),
+        })
+    }
+
+    fn download(&self, url: &Url) -> Result<Vec<u8>, String> {
+        let response = self
+            .client
+            .get(url.clone())
+            .send()
+            .map_err(|e| format!("请求 {} 失败: {}", url, e))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("请求 {} 失败: HTTP {}", url, status));
+        }
+        let mut body = Vec::new();
+        response
+            .take(MAX_FILE_BYTES + 1)
+            .read_to_end(&mut body)
+            .map_err(|e| format!("读取 {} 失败: {}", url, e))?;
+        if body.len() as u64 > MAX_FILE_BYTES {
+            return Err(format!("{} 超过 {} MB，已跳过", url, MAX_FILE_BYTES / 1024 / 1024));
+        }
+        Ok(body)
+    }
+
+    /// 按内容哈希写入归档目录，已存在时直接复用
+    fn archive(&self, content: &[u8], extension: &str) -> Result<(String, PathBuf), String> {
+        let sha256 = hex::encode(Sha256::digest(content));
+        let dir = self.archive_root.join(&sha256[..2]);
+        let path = dir.join(format!("{}.{}", sha256, extension));
+        if !path.exists() {
+            fs::create_dir_all(&dir).map_err(|e| format!("无法创建归档目录: {}", e))?;
+            let temp = path.with_extension(format!("{}.tmp", extension));
+            fs::write(&temp, content)
+                .and_then(|_| fs::rename(&temp, &path))
+                .map_err(|e| format!("保存归档文件失败: {}", e))?;
+        }
+        Ok((sha256, path))
+    }
+}
+
+/// 从链接中取文件名（URL 最后一段，解码百分号编码）
+fn file_name_from_url(url: &Url) -> String {
+    let segment = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("attachment");
+    percent_decode(segment)
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(byte) = input.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// 找出 HTML 中指向附件的链接（按扩展名判断），相对地址按详情页地址解析
+pub fn attachment_links(html: &str, base: &Url, extensions: &[String]) -> Vec<Url> {
+    let mut links: Vec<Url> = Vec::new();
+    // 只转换 ASCII，保证与原文的字节位置一致
+    let lower = html.to_ascii_lowercase();
+    let mut rest = 0;
+    while let Some(found) = lower[rest..].find("href") {
+        let start = rest + found + 4;
+        rest = start;
+        let after = lower[start..].trim_start();
+        if !after.starts_with('=') {
+            continue;
+        }
+        let value_start = start + (lower[start..].len() - after.len()) + 1;
+        let value = html[value_start..].trim_start();
+        let offset = value_start + (html[value_start..].len() - value.len());
+        let (href, consumed) = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => match value[1..].find(quote) {
+                Some(end) => (&value[1..end + 1], end + 2),
+                None => continue,
+            },
+            Some(_) => {
+                let end = value.find(|c: char| c.is_whitespace() || c == '>').unwrap_or(value.len());
+                (&value[..end], end)
+            }
+            None => break,
+        };
+        rest = offset + consumed;
+
+        let href = decode_entities(href.trim());
+        let url = match base.join(&href) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+            _ => continue,
+        };
+        let path = url.path().to_lowercase();
+        let is_attachment = extensions
+            .iter()
+            .any(|ext| path.ends_with(&format!(".{}", ext.to_lowercase())));
+        if is_attachment && !links.contains(&url) {
+            links.push(url);
+            if links.len() >= MAX_ATTACHMENTS {
+                break;
+            }
+        }
+    }
+    links
+}
+
+/// 把 HTML 转成纯文本：去掉 script/style，块级标签换行，表格单元格之间用制表符分隔，
+/// 解码常见实体，合并空白
+pub fn html_to_text(html: &str) -> String {
+    const BLOCK_TAGS: [&str; 16] = [
+        "p", "br", "div", "tr", "li", "h1", "h2", "h3", "h4", "h5", "h6", "table", "ul", "ol", "section", "article",
+    ];
+
+    let mut text = String::with_capacity(html.len() / 2);
+    let mut rest = html;
+    // 单元格内的块级标签不换行，保证表格的一行仍在同一行
+    let mut cell_depth = 0usize;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let tag_part = &rest[start + 1..];
+        let end = match tag_part.find('>') {
+            Some(end) => end,
+            None => {
+                rest = "";
+                break;
+            }
+        };
+        let closing_tag = tag_part.starts_with('/');
+        let tag = tag_part[..end].trim_start_matches('/').trim();
+        let name: String = tag
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        rest = &tag_part[end + 1..];
+
+        if (name == "script" || name == "style") && !closing_tag {
+            // 跳过到对应的结束标签
+            let closing = format!("</{}", name);
+            rest = match rest.to_ascii_lowercase().find(&closing) {
+                Some(index) => &rest[index..],
+                None => "",
+            };
+            continue;
+        }
+        if name == "td" || name == "th" {
+            if closing_tag {
+                cell_depth = cell_depth.saturating_sub(1);
+            } else {
+                cell_depth += 1;
+                text.push('\t');
+            }
+        } else if BLOCK_TAGS.contains(&name.as_str()) {
+            if name == "tr" || name == "table" {
+                cell_depth = 0;
+            }
+            text.push(if cell_depth > 0 { ' ' } else { '\n' });
+        }
+    }
+    text.push_str(rest);
+
+    let decoded = decode_entities(&text);
+    let lines: Vec<String> = decoded
+        .lines()
+        .map(|line| {
+            line.split('\t')
+                .map(|cell| cell.split_whitespace().collect::<Vec<_>>().join(" "))
+                .filter(|cell| !cell.is_empty())
+                .collect::<Vec<_>>()
+                .join("\t")
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines.join("\n")
+}
+
+/// 解码常见 HTML 实体
+fn decode_entities(input: &str) -> String {
+    if !input.contains('&') {
+        return input.to_string();
+    }
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        let end = match candidate.find(';') {
+            Some(end) if end <= 10 => end,
+            _ => {
+                output.push('&');
+                rest = &candidate[1..];
+                continue;
+            }
+        };
+        let entity = &candidate[1..end];
+        let decoded = match entity {
+            "nbsp" => Some(' '),
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "yen" => Some('¥'),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                output.push(c);
+                rest = &candidate[end + 1..];
+            }
+            None => {
+                output.push('&');
+                rest = &candidate[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{serve, TempDir};
+
+    fn client() -> Client {
+        Client::builder().no_proxy().build().unwrap()
+    }
+
+    fn source(content_path: &str) -> DetailSource {
+        DetailSource {
+            url_template: String::new(),
+            content_path: content_path.to_string(),
+            attachment_extensions: vec!["pdf".to_string(), "docx".to_string()],
+        }
+    }
+
+    fn archived_files(dir: &Path) -> usize {
+        fs::read_dir(dir.join(ARCHIVE_DIR))
+            .map(|dirs| dirs.flatten().map(|d| fs::read_dir(d.path()).unwrap().count()).sum())
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn archive_stores_identical_content_once() {
+        let dir = TempDir::new("detail_fetch");
+        let client = client();
+        let fetcher = DetailFetcher::new(&client, dir.path());
+
+        let (sha256, path) = fetcher.archive(b"same", "pdf").unwrap();
+        assert_eq!(sha256, hex::encode(Sha256::digest(b"same")));
+        assert_eq!(path, dir.path().join(ARCHIVE_DIR).join(&sha256[..2]).join(format!("{}.pdf", sha256)));
+        let (again, again_path) = fetcher.archive(b"same", "pdf").unwrap();
+        assert_eq!((again, again_path), (sha256, path));
+        fetcher.archive(b"other", "pdf").unwrap();
+        assert_eq!(archived_files(dir.path()), 2);
+    }
+
+    #[test]
+    fn attachment_names_come_from_the_last_path_segment() {
+        let name = |url: &str| file_name_from_url(&Url::parse(url).unwrap());
+        assert_eq!(name("https://example.com/files/%E6%8B%9B%E6%A0%87%E6%96%87%E4%BB%B6.pdf?v=1"), "招标文件.pdf");
+        assert_eq!(name("https://example.com/files/"), "attachment");
+        assert_eq!(name("https://example.com"), "attachment");
+        assert_eq!(percent_decode("100%25%zz%4"), "100%%zz%4");
+    }
+
+    #[test]
+    fn attachment_links_are_resolved_filtered_and_deduplicated() {
+        let base = Url::parse("https://example.com/notice/1.html").unwrap();
+        let html = r#"<a href="a.pdf">A</a> <a HREF = 'https://cdn.example.com/b.DOCX'>B</a>
+            <a href=/files/c.pdf>C</a> <a href="a.pdf">again</a> <a href="x.html">page</a>
+            <a href="javascript:open('d.pdf')">js</a> <a href="e.pdf?name=a&amp;b=1">E</a>"#;
+        let links: Vec<String> = attachment_links(html, &base, &source("").attachment_extensions)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                "https://example.com/notice/a.pdf",
+                "https://cdn.example.com/b.DOCX",
+                "https://example.com/files/c.pdf",
+                "https://example.com/notice/e.pdf?name=a&b=1",
+            ]
+        );
+    }
+
+    #[test]
+    fn html_table_cells_are_tab_separated() {
+        let html = "<style>p{}</style><h1>公示</h1><table><tr><th>排名</th><th>名称</th></tr>\
+                    <tr><td>1</td><td><p>甲&nbsp;公司</p></td></tr></table><p>A &amp; B</p>";
+        assert_eq!(html_to_text(html), "公示\n排名\t名称\n1\t甲 公司\nA & B");
+    }
+
+    #[test]
+    fn fetch_archives_page_and_attachments() {
+        let base = serve(vec![
+            ("/notice/1", 200, r#"<p>正文</p><a href="/files/%E9%99%84%E4%BB%B6.pdf">附件</a><a href="/files/missing.pdf">缺失</a>"#.into()),
+            ("/files/%E9%99%84%E4%BB%B6.pdf", 200, b"%PDF-1.4".to_vec()),
+        ]);
+        let dir = TempDir::new("detail_fetch");
+        let client = client();
+        let fetcher = DetailFetcher::new(&client, dir.path());
+
+        let url = format!("{}/notice/1", base);
+        let detail = fetcher.fetch(&source(""), &url).unwrap();
+        assert_eq!(detail.url, url);
+        assert!(detail.path.ends_with(".html"));
+        assert_eq!(detail.text, "正文\n附件缺失");
+        // 下载失败的附件只跳过，不影响详情页
+        assert_eq!(detail.attachments.len(), 1);
+        let attachment = &detail.attachments[0];
+        assert_eq!(attachment.file_name, "附件.pdf");
+        assert_eq!(attachment.size, 8);
+        assert!(attachment.path.ends_with(".pdf"));
+        assert_eq!(fs::read(&attachment.path).unwrap(), b"%PDF-1.4");
+    }
+
+    #[test]
+    fn fetch_reads_html_from_json_content_path() {
+        let base = serve(vec![("/api/1", 200, r#"{"data":{"content":"<p>JSON 正文</p>"}}"#.into())]);
+        let dir = TempDir::new("detail_fetch");
+        let client = client();
+        let fetcher = DetailFetcher::new(&client, dir.path());
+
+        let detail = fetcher.fetch(&source("/data/content"), &format!("{}/api/1", base)).unwrap();
+        assert_eq!(detail.text, "JSON 正文");
+        assert!(detail.path.ends_with(".json"));
+    }
+
+    #[test]
+    fn fetch_errors_are_reported() {
+        let base = serve(vec![
+            ("/html", 200, b"<p>not json</p>".to_vec()),
+            ("/json", 200, br#"{"data":{}}"#.to_vec()),
+            ("/error", 500, b"oops".to_vec()),
+        ]);
+        let dir = TempDir::new("detail_fetch");
+        let client = client();
+        let fetcher = DetailFetcher::new(&client, dir.path());
+
+        let error = fetcher.fetch(&source(""), "not a url").unwrap_err();
+        assert!(error.contains("详情页地址无效"), "{}", error);
+        let error = fetcher.fetch(&source(""), &format!("{}/error", base)).unwrap_err();
+        assert!(error.contains("HTTP 500"), "{}", error);
+        let error = fetcher.fetch(&source("/data/content"), &format!("{}/html", base)).unwrap_err();
+        assert!(error.contains("不是 JSON"), "{}", error);
+        let error = fetcher.fetch(&source("/data/content"), &format!("{}/json", base)).unwrap_err();
+        assert!(error.contains("/data/content"), "{}", error);
+        // HTTP 错误不写入归档，下载成功但解析失败的内容仍保留，便于排查
+        assert_eq!(archived_files(dir.path()), 2);
+    }
+}
@@ -0,0 +1,505 @@
+// ============================================================================
+// 运行环境诊断
+// ============================================================================
+//
+// 一次检查爬虫依赖的整条工具链，每一项给出 pass / warn / fail：
+//   - Python 版本和 requirements.txt 中爬虫需要的包（requests、ddddocr、fake-useragent）
+//   - Node.js（spiders.py 通过 js_executor.js 调用 Node 计算加密参数）
+//   - 打包的 spider_bin 是否存在且可执行
+//   - spiders.py 旁边的 decryption.js / jsencrypt.js / js_executor.js
+//   - 上述文件和 spider_bin 与构建时完整性清单是否一致
+//   - 应用数据目录是否可写
+//   - SMTP 服务器是否可达
+
+use std::path::Path;
+use std::process::Command;
+
+use chrono::Local;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config_file;
+use crate::integrity::{self, FileIntegrity, IntegrityStatus};
+use crate::network::{self, NetworkSettings};
+use crate::runtime::{Resolution, RuntimeReport};
+use crate::settings::SmtpConfig;
+
+/// 爬虫需要的 Python 包：(包名, 导入名)
+const REQUIRED_PACKAGES: [(&str, &str); 3] = [
+    ("requests", "requests"),
+    ("ddddocr", "ddddocr"),
+    ("fake-useragent", "fake_useragent"),
+];
+/// spiders.py 运行时需要的脚本文件，和 spiders.py 放在同一目录
+const RESOURCE_FILES: [&str; 3] = ["decryption.js", "jsencrypt.js", "js_executor.js"];
+/// 与 spiders.py 中 _find_node 相同的查找位置
+const NODE_CANDIDATES: [&str; 4] = ["node", "/usr/local/bin/node", "/opt/homebrew/bin/node", "/usr/bin/node"];
+/// 低于此版本时给出警告
+const MIN_PYTHON_MINOR: u32 = 8;
+
+/// 在解释器中逐个导入包，输出 {包名: {ok, version, error}}
+const PACKAGE_CHECK_SCRIPT: &str = r#"
+import importlib, json, sys
+try:
+    from importlib import metadata
+except ImportError:
+    metadata = None
+result = {}
+for package, module in json.loads(sys.argv[1]):
+    try:
+        importlib.import_module(module)
+        try:
+            version = metadata.version(package) if metadata else ""
+        except Exception:
+            version = ""
+        result[package] = {"ok": True, "version": version}
+    except Exception as e:
+        result[package] = {"ok": False, "error": "%s: %s" % (type(e).__name__, e)}
+print(json.dumps(result))
+"#;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// 单项检查结果
+#[derive(Serialize, Debug, Clone)]
+pub struct CheckItem {
+    /// 稳定的检查项标识，如 "python"、"package:ddddocr"
+    pub id: String,
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// 未通过时的处理建议
+    pub hint: Option<String>,
+}
+
+impl CheckItem {
+    fn new(id: impl Into<String>, name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        CheckItem {
+            id: id.into(),
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn hint(mut self, hint: &str) -> Self {
+        if self.status != CheckStatus::Pass {
+            self.hint = Some(hint.to_string());
+        }
+        self
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DiagnosticReport {
+    /// 所有检查项中最差的状态
+    pub status: CheckStatus,
+    pub checks: Vec<CheckItem>,
+    pub generated_at: String,
+}
+
+/// 诊断需要的输入，由调用方从 managed state 和配置文件中收集
+pub struct DiagnoseInput<'a> {
+    pub runtime: &'a RuntimeReport,
+    pub app_data_dir: Option<&'a Path>,
+    pub network: &'a NetworkSettings,
+    pub smtp: &'a SmtpConfig,
+}
+
+pub fn diagnose(input: &DiagnoseInput) -> DiagnosticReport {
+    let mut checks = Vec::new();
+    checks.extend(check_python(input.runtime));
+    checks.push(check_node());
+    checks.push(check_binary(input.runtime));
+    checks.extend(check_resources(input.runtime));
+    checks.extend(check_integrity(input.runtime));
+    checks.push(check_app_data_dir(input.app_data_dir));
+    checks.push(check_smtp(input.network, input.smtp));
+
+    DiagnosticReport {
+        status: checks.iter().map(|c| c.status).max().unwrap_or(CheckStatus::Pass),
+        checks,
+        generated_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    }
+}
+
+/// 解析 "3.11.7" 这样的版本号
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.trim().parse().ok()?;
+    let minor = parts.next()?.trim().parse().ok()?;
+    Some((major, minor))
+}
+
+fn check_python(runtime: &RuntimeReport) -> Vec<CheckItem> {
+    let Some(python) = &runtime.python.path else {
+        let tried: Vec<String> = runtime
+            .python
+            .candidates
+            .iter()
+            .map(|c| format!("{}（{}）", c.path, c.rejected.as_deref().unwrap_or("可用")))
+            .collect();
+        let mut checks = vec![CheckItem::new("python", "Python", CheckStatus::Fail, format!("未找到 Python 3，已尝试: {}", tried.join("，")))
+            .hint("安装 Python 3 并加入 PATH，或在运行环境设置中指定解释器路径")];
+        // 没有解释器时包检查无法进行，同样标记为失败
+        for (package, _) in REQUIRED_PACKAGES {
+            checks.push(CheckItem::new(format!("package:{}", package), format!("Python 包 {}", package), CheckStatus::Fail, "未检查（没有 Python）"));
+        }
+        return checks;
+    };
+
+    let version = runtime.python.version.clone().unwrap_or_default();
+    let status = match parse_version(&version) {
+        Some((3, minor)) if minor >= MIN_PYTHON_MINOR => CheckStatus::Pass,
+        _ => CheckStatus::Warn,
+    };
+    let mut checks = vec![CheckItem::new("python", "Python", status, format!("{} ({})", version, python))
+        .hint(&format!("建议使用 Python 3.{} 及以上版本", MIN_PYTHON_MINOR))];
+    checks.extend(check_packages(python));
+    checks
+}
+
+/// 在指定解释器中逐个导入爬虫需要的包
+pub fn check_packages(python: &str) -> Vec<CheckItem> {
+    let packages = serde_json::to_string(&REQUIRED_PACKAGES).unwrap_or_default();
+    let output = Command::new(python)
+        .arg("-c")
+        .arg(PACKAGE_CHECK_SCRIPT)
+        .arg(&packages)
+        .output();
+    let result: Result<Value, String> = match output {
+        Ok(output) if output.status.success() => serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("无法解析检查结果: {}", e)),
+        Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        Err(e) => Err(format!("无法执行 Python: {}", e)),
+    };
+
+    REQUIRED_PACKAGES
+        .iter()
+        .map(|(package, _)| {
+            let id = format!("package:{}", package);
+            let name = format!("Python 包 {}", package);
+            let item = match &result {
+                Ok(value) => match value.get(package) {
+                    Some(entry) if entry["ok"].as_bool() == Some(true) => {
+                        let version = entry["version"].as_str().unwrap_or_default();
+                        CheckItem::new(id, name, CheckStatus::Pass, format!("已安装 {}", version).trim().to_string())
+                    }
+                    Some(entry) => CheckItem::new(id, name, CheckStatus::Fail, entry["error"].as_str().unwrap_or("导入失败")),
+                    None => CheckItem::new(id, name, CheckStatus::Fail, "检查结果中没有此包"),
+                },
+                Err(e) => CheckItem::new(id, name, CheckStatus::Fail, e.clone()),
+            };
+            item.hint("在运行环境设置中安装私有 Python 环境，或执行 pip install --no-deps -r requirements.lock")
+        })
+        .collect()
+}
+
+fn check_node() -> CheckItem {
+    let from_env = std::env::var("NODE_PATH")
+        .ok()
+        .map(|dir| Path::new(&dir).join("node").to_string_lossy().to_string());
+    let mut tried = Vec::new();
+    for candidate in NODE_CANDIDATES.iter().map(|c| c.to_string()).chain(from_env) {
+        match Command::new(&candidate).arg("--version").output() {
+            Ok(output) if output.status.success() => {
+                let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                return CheckItem::new("node", "Node.js", CheckStatus::Pass, format!("{} ({})", version, candidate));
+            }
+            Ok(output) => tried.push(format!("{}（退出码 {}）", candidate, output.status)),
+            Err(_) => tried.push(format!("{}（不存在）", candidate)),
+        }
+    }
+    CheckItem::new("node", "Node.js", CheckStatus::Fail, format!("未找到 Node.js，已尝试: {}", tried.join("，")))
+        .hint("安装 Node.js 并加入 PATH，爬虫需要它计算请求加密参数")
+}
+
+fn check_binary(runtime: &RuntimeReport) -> CheckItem {
+    match &runtime.binary.path {
+        Some(path) => CheckItem::new("spider_bin", "打包的爬虫程序", CheckStatus::Pass, path.clone()),
+        None => {
+            // 有 Python 时打包程序只是备用方案
+            let status = if runtime.python.path.is_some() { CheckStatus::Warn } else { CheckStatus::Fail };
+            let reasons: Vec<String> = runtime
+                .binary
+                .candidates
+                .iter()
+                .filter(|c| c.rejected.as_deref() != Some("不存在"))
+                .map(|c| format!("{}（{}）", c.path, c.rejected.as_deref().unwrap_or("")))
+                .collect();
+            let detail = if reasons.is_empty() {
+                format!("未找到 spider_bin（尝试了 {} 个路径）", runtime.binary.candidates.len())
+            } else {
+                format!("spider_bin 不可用: {}", reasons.join("，"))
+            };
+            CheckItem::new("spider_bin", "打包的爬虫程序", status, detail)
+                .hint("执行 中国石油招标投标网/build_bin.sh 构建，或在运行环境设置中指定路径")
+        }
+    }
+}
+
+fn check_resources(runtime: &RuntimeReport) -> Vec<CheckItem> {
+    let Some(script) = &runtime.script.path else {
+        let mut checks = vec![CheckItem::new("resource:spiders.py", "spiders.py", CheckStatus::Fail, "未找到爬虫脚本")
+            .hint("重新安装应用，或在运行环境设置中指定 spiders.py 的路径")];
+        for file in RESOURCE_FILES {
+            checks.push(CheckItem::new(format!("resource:{}", file), file, CheckStatus::Fail, "未检查（没有找到 spiders.py）"));
+        }
+        return checks;
+    };
+
+    let dir = Path::new(script).parent().unwrap_or(Path::new("."));
+    let mut checks = vec![CheckItem::new("resource:spiders.py", "spiders.py", CheckStatus::Pass, script.clone())];
+    for file in RESOURCE_FILES {
+        let path = dir.join(file);
+        let item = if path.is_file() {
+            CheckItem::new(format!("resource:{}", file), file, CheckStatus::Pass, path.to_string_lossy())
+        } else {
+            CheckItem::new(format!("resource:{}", file), file, CheckStatus::Fail, format!("{} 不存在", path.to_string_lossy()))
+                .hint("重新安装应用，这些文件需要和 spiders.py 放在同一目录")
+        };
+        checks.push(item);
+    }
+    checks
+}
+
+/// 采用的候选是否来自用户配置
+fn is_user_configured(resolution: &Resolution) -> bool {
+    resolution
+        .candidates
+        .iter()
+        .any(|c| c.rejected.is_none() && c.source == "用户配置")
+}
+
+/// 对照构建时的完整性清单校验采用的脚本和 spider_bin
+fn check_integrity(runtime: &RuntimeReport) -> Vec<CheckItem> {
+    let mut results: Vec<(FileIntegrity, bool)> = Vec::new();
+    if let Some(script) = &runtime.script.path {
+        let user = is_user_configured(&runtime.script);
+        results.extend(integrity::verify_script(Path::new(script)).into_iter().map(|r| (r, user)));
+    }
+    if let Some(binary) = &runtime.binary.path {
+        let user = is_user_configured(&runtime.binary);
+        results.extend(integrity::verify_binary(Path::new(binary)).into_iter().map(|r| (r, user)));
+    }
+
+    results
+        .into_iter()
+        .map(|(result, user)| {
+            let status = match result.status {
+                IntegrityStatus::Verified => CheckStatus::Pass,
+                // 用户指定的文件不在清单范围内，只提示
+                _ if user => CheckStatus::Warn,
+                IntegrityStatus::Unlisted => CheckStatus::Warn,
+                IntegrityStatus::Mismatch | IntegrityStatus::Unreadable => CheckStatus::Fail,
+            };
+            let detail = match result.status {
+                IntegrityStatus::Verified => format!("SHA-256 {}", result.actual.as_deref().unwrap_or_default()),
+                _ => result.describe(),
+            };
+            CheckItem::new(format!("integrity:{}", result.name), format!("完整性 {}", result.name), status, detail)
+                .hint("文件可能被修改或替换，请重新安装应用；开发时可在运行环境设置中把完整性校验改为 warn")
+        })
+        .collect()
+}
+
+fn check_app_data_dir(app_data_dir: Option<&Path>) -> CheckItem {
+    let Some(dir) = app_data_dir else {
+        return CheckItem::new("app_data_dir", "应用数据目录", CheckStatus::Fail, "无法获取应用数据目录");
+    };
+    let probe = dir.join(".write_test");
+    let result = config_file::write_atomic(&probe, b"ok").and_then(|_| {
+        std::fs::remove_file(&probe).map_err(|e| format!("删除测试文件失败: {}", e))
+    });
+    match result {
+        Ok(()) => CheckItem::new("app_data_dir", "应用数据目录", CheckStatus::Pass, format!("{} 可写", dir.to_string_lossy())),
+        Err(e) => CheckItem::new("app_data_dir", "应用数据目录", CheckStatus::Fail, format!("{}: {}", dir.to_string_lossy(), e))
+            .hint("检查目录权限和磁盘空间，配置和公告数据都保存在这里"),
+    }
+}
+
+fn check_smtp(settings: &NetworkSettings, smtp: &SmtpConfig) -> CheckItem {
+    if smtp.server.trim().is_empty() {
+        return CheckItem::new("smtp", "SMTP 服务器", CheckStatus::Warn, "未配置 SMTP 服务器")
+            .hint("在邮件设置中填写 SMTP 服务器，否则无法发送邮件");
+    }
+    let probe = network::probe_smtp(settings, &smtp.server, smtp.port);
+    let via = if probe.via_proxy { "，经代理" } else { "" };
+    // 邮件只影响通知，不影响爬取
+    let status = if probe.ok { CheckStatus::Pass } else { CheckStatus::Warn };
+    CheckItem::new("smtp", "SMTP 服务器", status, format!("{}: {}（{} ms{}）", probe.target, probe.detail, probe.elapsed_ms, via))
+        .hint("检查 SMTP 服务器地址、端口和网络代理设置")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::Candidate;
+    use crate::test_support::TempDir;
+
+    fn resolved(path: &str, source: &str) -> Resolution {
+        Resolution {
+            path: Some(path.to_string()),
+            version: None,
+            candidates: vec![Candidate { path: path.to_string(), source: source.to_string(), rejected: None }],
+        }
+    }
+
+    fn rejected(candidates: &[(&str, &str)]) -> Resolution {
+        Resolution {
+            path: None,
+            version: None,
+            candidates: candidates
+                .iter()
+                .map(|(path, reason)| Candidate { path: path.to_string(), source: "工作目录".to_string(), rejected: Some(reason.to_string()) })
+                .collect(),
+        }
+    }
+
+    fn report(python: Resolution, script: Resolution, binary: Resolution) -> RuntimeReport {
+        RuntimeReport { python, script, binary, resolved_at: String::new() }
+    }
+
+    fn statuses(checks: &[CheckItem]) -> Vec<(String, CheckStatus)> {
+        checks.iter().map(|c| (c.id.clone(), c.status)).collect()
+    }
+
+    /// 带 spiders.py 和指定 JS 辅助脚本的目录，返回 spiders.py 的路径
+    fn script_dir(dir: &Path, helpers: &[&str]) -> String {
+        let script = dir.join("spiders.py");
+        std::fs::write(&script, "print('test')").unwrap();
+        for helper in helpers {
+            std::fs::write(dir.join(helper), "// test").unwrap();
+        }
+        script.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn versions_and_status_order() {
+        assert_eq!(parse_version("3.11.7"), Some((3, 11)));
+        assert_eq!(parse_version(" 3 . 8 "), Some((3, 8)));
+        assert_eq!(parse_version("3"), None);
+        assert_eq!(parse_version("Python 3.8"), None);
+        // 报告的总体状态取最差的一项
+        assert!(CheckStatus::Pass < CheckStatus::Warn && CheckStatus::Warn < CheckStatus::Fail);
+    }
+
+    #[test]
+    fn hint_is_only_kept_for_failures() {
+        assert_eq!(CheckItem::new("a", "A", CheckStatus::Pass, "").hint("建议").hint, None);
+        assert_eq!(CheckItem::new("a", "A", CheckStatus::Warn, "").hint("建议").hint.as_deref(), Some("建议"));
+    }
+
+    #[test]
+    fn missing_python_fails_python_and_packages() {
+        let runtime = report(rejected(&[("python3", "不存在")]), Resolution::default(), Resolution::default());
+        let checks = check_python(&runtime);
+        assert_eq!(checks.len(), 1 + REQUIRED_PACKAGES.len());
+        assert!(checks.iter().all(|c| c.status == CheckStatus::Fail));
+        assert!(checks[0].detail.contains("python3（不存在）"), "{}", checks[0].detail);
+        assert_eq!(checks[1].id, "package:requests");
+    }
+
+    #[test]
+    fn old_python_is_a_warning_and_unusable_interpreter_fails_packages() {
+        let dir = TempDir::new("diagnostics");
+        let python = dir.path().join("no_such_python").to_string_lossy().to_string();
+        let mut resolution = resolved(&python, "用户配置");
+
+        resolution.version = Some("3.7.9".to_string());
+        let checks = check_python(&report(resolution.clone(), Resolution::default(), Resolution::default()));
+        assert_eq!(checks[0].status, CheckStatus::Warn);
+        assert!(checks[1..].iter().all(|c| c.status == CheckStatus::Fail && c.detail.contains("无法执行 Python")));
+
+        resolution.version = Some("3.12.1".to_string());
+        let checks = check_python(&report(resolution, Resolution::default(), Resolution::default()));
+        assert_eq!(checks[0].status, CheckStatus::Pass);
+        assert_eq!(checks[0].hint, None);
+    }
+
+    #[test]
+    fn missing_binary_is_a_warning_only_when_python_exists() {
+        let python = resolved("python3", "PATH");
+        let binary = rejected(&[("/a/spider_bin", "不存在"), ("/b/spider_bin", "没有执行权限")]);
+
+        let with_python = check_binary(&report(python, Resolution::default(), binary.clone()));
+        assert_eq!(with_python.status, CheckStatus::Warn);
+        // 只列出存在但不可用的候选
+        assert_eq!(with_python.detail, "spider_bin 不可用: /b/spider_bin（没有执行权限）");
+
+        let without_python = check_binary(&report(Resolution::default(), Resolution::default(), rejected(&[("/a/spider_bin", "不存在")])));
+        assert_eq!(without_python.status, CheckStatus::Fail);
+        assert_eq!(without_python.detail, "未找到 spider_bin（尝试了 1 个路径）");
+
+        let found = check_binary(&report(Resolution::default(), Resolution::default(), resolved("/c/spider_bin", "资源目录")));
+        assert_eq!(found.status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn resources_next_to_the_script_are_checked() {
+        let dir = TempDir::new("diagnostics");
+        let script = script_dir(dir.path(), &RESOURCE_FILES[..2]);
+        let checks = check_resources(&report(Resolution::default(), resolved(&script, "用户配置"), Resolution::default()));
+        assert_eq!(
+            statuses(&checks),
+            [
+                ("resource:spiders.py".to_string(), CheckStatus::Pass),
+                ("resource:decryption.js".to_string(), CheckStatus::Pass),
+                ("resource:jsencrypt.js".to_string(), CheckStatus::Pass),
+                ("resource:js_executor.js".to_string(), CheckStatus::Fail),
+            ]
+        );
+
+        let missing = check_resources(&report(Resolution::default(), Resolution::default(), Resolution::default()));
+        assert!(missing.iter().all(|c| c.status == CheckStatus::Fail));
+        assert_eq!(missing.len(), 1 + RESOURCE_FILES.len());
+    }
+
+    #[test]
+    fn integrity_failures_are_warnings_for_user_configured_files() {
+        let dir = TempDir::new("diagnostics");
+        // 内容与清单不一致的 spiders.py，缺少一个辅助脚本
+        let script = script_dir(dir.path(), &RESOURCE_FILES[..2]);
+
+        let auto = check_integrity(&report(Resolution::default(), resolved(&script, "工作目录"), Resolution::default()));
+        assert_eq!(auto.len(), 1 + RESOURCE_FILES.len());
+        assert_eq!(auto[0].id, "integrity:spiders.py");
+        assert_eq!(auto[0].status, CheckStatus::Fail);
+        assert_eq!(auto[3].status, CheckStatus::Fail, "{}", auto[3].detail);
+
+        let user = check_integrity(&report(Resolution::default(), resolved(&script, "用户配置"), Resolution::default()));
+        assert!(user.iter().all(|c| c.status == CheckStatus::Warn), "{:?}", statuses(&user));
+
+        // 没有找到脚本和 spider_bin 时不做完整性检查（由其他检查项报告）
+        assert!(check_integrity(&report(Resolution::default(), Resolution::default(), Resolution::default())).is_empty());
+    }
+
+    #[test]
+    fn app_data_dir_must_be_writable() {
+        let dir = TempDir::new("diagnostics");
+        let ok = check_app_data_dir(Some(dir.path()));
+        assert_eq!(ok.status, CheckStatus::Pass);
+        assert!(!dir.path().join(".write_test").exists());
+
+        // 路径中间是一个文件，目录无法创建
+        let blocker = dir.path().join("blocker");
+        std::fs::write(&blocker, "").unwrap();
+        assert_eq!(check_app_data_dir(Some(&blocker.join("data"))).status, CheckStatus::Fail);
+        assert_eq!(check_app_data_dir(None).status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn unconfigured_smtp_is_a_warning() {
+        let smtp = SmtpConfig { server: "  ".to_string(), ..SmtpConfig::default() };
+        let item = check_smtp(&NetworkSettings::default(), &smtp);
+        assert_eq!(item.status, CheckStatus::Warn);
+        assert!(item.hint.is_some());
+    }
+}
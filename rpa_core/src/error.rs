@@ -0,0 +1,157 @@
+// ============================================================================
+// 命令统一的错误类型
+// ============================================================================
+//
+// Tauri 命令返回 Result<_, AppError>，前端收到的是结构化的对象而不是拼好的字符串：
+//   { kind, code, message, detail, hints, retryable, fields }
+// kind 是大类（用于决定界面如何展示），code 是稳定的错误码（如 "smtp.auth_failed"），
+// 前端按 code 做判断，不再匹配错误文本。
+// 内部模块仍然可以返回 String：From<String> 把它当作 internal 错误，
+// From<AppError> for String 让返回 AppError 的函数可以在返回 String 的函数中直接使用 ?
+// message、detail 和 hints 在构造时经过脱敏（redact.rs），错误中不会带出密码和密钥
+
+use std::fmt;
+use std::ops::Deref;
+
+use serde::Serialize;
+
+use crate::redact::redact;
+use crate::spider_params::{self, FieldError};
+
+pub type AppResult<T> = Result<T, AppError>;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// 用户输入不合法
+    Validation,
+    /// 要操作的对象（定时任务、公告等）不存在
+    NotFound,
+    /// 配置缺失、损坏或无法保存
+    Config,
+    /// 运行环境问题：Python、依赖包、爬虫脚本或可执行文件
+    Environment,
+    /// 网络连接失败或超时
+    Network,
+    /// 邮件服务器返回错误
+    Smtp,
+    /// 爬虫或脚本开始执行后失败
+    Execution,
+    /// 其他内部错误
+    Internal,
+}
+
+/// 错误内容放在 Box 中，保持 Result<_, AppError> 的体积较小
+#[derive(Serialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct AppError(Box<ErrorInfo>);
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ErrorInfo {
+    pub kind: ErrorKind,
+    /// 稳定的错误码，格式为 "<领域>.<原因>"
+    pub code: String,
+    /// 给用户看的说明
+    pub message: String,
+    /// 技术细节（原始错误、命令输出等）
+    pub detail: Option<String>,
+    /// 处理建议，按顺序展示
+    pub hints: Vec<String>,
+    /// 稍后重试可能成功（网络抖动、验证码、服务器临时错误）
+    pub retryable: bool,
+    /// 字段级校验错误（仅 validation）
+    pub fields: Vec<FieldError>,
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, code: &str, message: impl Into<String>) -> Self {
+        AppError(Box::new(ErrorInfo {
+            kind,
+            code: code.to_string(),
+            message: redact(&message.into()),
+            detail: None,
+            hints: Vec::new(),
+            retryable: false,
+            fields: Vec::new(),
+        }))
+    }
+
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.0.detail = Some(redact(&detail.into()));
+        self
+    }
+
+    pub fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.0.hints.push(redact(&hint.into()));
+        self
+    }
+
+    pub fn retryable(mut self) -> Self {
+        self.0.retryable = true;
+        self
+    }
+
+    /// 字段校验失败
+    pub fn validation(errors: Vec<FieldError>) -> Self {
+        let mut error = AppError::new(ErrorKind::Validation, "validation.failed", spider_params::describe_errors(&errors));
+        error.0.fields = errors;
+        error
+    }
+
+    /// 单个输入值不合法
+    pub fn invalid_input(code: &str, message: impl Into<String>) -> Self {
+        AppError::new(ErrorKind::Validation, code, message)
+    }
+
+    /// 要操作的对象不存在，code 为 "<领域>.not_found"
+    pub fn not_found(domain: &str, message: impl Into<String>) -> Self {
+        AppError::new(ErrorKind::NotFound, &format!("{}.not_found", domain), message)
+    }
+
+    /// 配置无法读取或保存
+    pub fn config(message: impl Into<String>) -> Self {
+        AppError::new(ErrorKind::Config, "config.io", message)
+    }
+
+    /// 后台任务（spawn_blocking）异常结束
+    pub fn task(error: impl fmt::Display) -> Self {
+        AppError::new(ErrorKind::Internal, "internal.task_failed", "任务执行失败").detail(error.to_string())
+    }
+}
+
+impl Deref for AppError {
+    type Target = ErrorInfo;
+
+    fn deref(&self) -> &ErrorInfo {
+        &self.0
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.detail {
+            Some(detail) => write!(f, "{}: {}", self.message, detail),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::new(ErrorKind::Internal, "internal", message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::from(message.to_string())
+    }
+}
+
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,471 @@
+// ============================================================================
+// 导出本地公告数据
+// ============================================================================
+//
+// 支持 CSV（带 BOM，Excel 直接打开不乱码）、XLSX、JSON Lines 和 Markdown 表格。
+// 数据逐行从数据库读出并写入文件，历史数据再多也不会一次性载入内存。
+// 标题、正文都来自网站，CSV 和 XLSX 中以 = + - @ 等开头的单元格会加上 ' 前缀，
+// 防止在 Excel 中打开时被当作公式执行
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use rust_xlsxwriter::{Format, Workbook, Worksheet};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::store::{Announcement, AnnouncementStore, SearchFilters};
+
+/// XLSX 单个工作表的最大行数（含表头）
+const XLSX_MAX_ROWS: usize = 1_048_576;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+    Jsonl,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Markdown => "md",
+        }
+    }
+
+    /// HTTP 下载时的 Content-Type
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            ExportFormat::Jsonl => "application/x-ndjson",
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+        }
+    }
+
+    /// 保存对话框中显示的文件类型名称
+    pub fn filter_name(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV 文件",
+            ExportFormat::Xlsx => "Excel 工作簿",
+            ExportFormat::Jsonl => "JSON Lines",
+            ExportFormat::Markdown => "Markdown 文档",
+        }
+    }
+}
+
+/// 导出结果
+#[derive(Serialize, Debug, Clone)]
+pub struct ExportSummary {
+    pub path: PathBuf,
+    pub format: ExportFormat,
+    pub rows: usize,
+}
+
+/// 表格类格式（CSV / XLSX / Markdown）的列
+const COLUMNS: [&str; 14] = [
+    "ID", "爬虫", "标题", "项目类型", "发布日期", "链接", "任务", "首次获取时间", "正文", "详情页", "附件",
+    "项目编号", "招标代理机构", "中标候选人",
+];
+
+fn cells(a: &Announcement) -> [String; 14] {
+    let notice = a.candidate_notice.as_ref();
+    [
+        a.id.to_string(),
+        a.spider_id.clone(),
+        a.title.clone(),
+        a.project_type.clone().unwrap_or_default(),
+        a.publish_date.clone().unwrap_or_default(),
+        a.url.clone().unwrap_or_default(),
+        a.job.clone(),
+        a.first_seen_at.clone(),
+        a.content.clone(),
+        a.detail_url.clone().unwrap_or_default(),
+        a.attachments
+            .iter()
+            .map(|t| format!("{} ({})", t.file_name, t.path))
+            .collect::<Vec<_>>()
+            .join("; "),
+        notice
+            .and_then(|n| n.project_number.as_ref())
+            .map(|f| f.value.clone())
+            .unwrap_or_default(),
+        notice
+            .and_then(|n| n.tender_agency.as_ref())
+            .map(|f| f.value.clone())
+            .unwrap_or_default(),
+        notice.map(|n| n.candidates_summary()).unwrap_or_default(),
+    ]
+}
+
+/// 按格式逐行写出
+trait RowWriter {
+    fn write_row(&mut self, announcement: &Announcement) -> Result<(), String>;
+    fn finish(self: Box<Self>) -> Result<(), String>;
+}
+
+fn io_error(e: std::io::Error) -> String {
+    format!("写入导出文件失败: {}", e)
+}
+
+struct CsvWriter {
+    out: BufWriter<File>,
+}
+
+/// 以这些字符开头的单元格在表格软件中会被当作公式
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// 给会被当作公式的单元格加上 ' 前缀，表格软件按文本显示
+fn neutralize_formula(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", value).into()
+    } else {
+        value.into()
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    let value = neutralize_formula(value);
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl CsvWriter {
+    fn create(path: &Path) -> Result<Self, String> {
+        let mut out = BufWriter::new(File::create(path).map_err(io_error)?);
+        out.write_all("\u{FEFF}".as_bytes()).map_err(io_error)?;
+        let mut writer = CsvWriter { out };
+        writer.write_line(&COLUMNS.map(String::from))?;
+        Ok(writer)
+    }
+
+    fn write_line(&mut self, fields: &[String]) -> Result<(), String> {
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        write!(self.out, "{}\r\n", line.join(",")).map_err(io_error)
+    }
+}
+
+impl RowWriter for CsvWriter {
+    fn write_row(&mut self, announcement: &Announcement) -> Result<(), String> {
+        self.write_line(&cells(announcement))
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), String> {
+        self.out.flush().map_err(io_error)
+    }
+}
+
+struct JsonlWriter {
+    out: BufWriter<File>,
+}
+
+impl RowWriter for JsonlWriter {
+    fn write_row(&mut self, a: &Announcement) -> Result<(), String> {
+        let line = json!({
+            "id": a.id,
+            "spider_id": a.spider_id,
+            "source_id": a.source_id,
+            "title": a.title,
+            "content": a.content,
+            "project_type": a.project_type,
+            "publish_date": a.publish_date,
+            "url": a.url,
+            "job": a.job,
+            "run_id": a.run_id,
+            "first_seen_at": a.first_seen_at,
+            "last_seen_at": a.last_seen_at,
+            "detail_url": a.detail_url,
+            "detail_path": a.detail_path,
+            "detail_fetched_at": a.detail_fetched_at,
+            "attachments": a.attachments,
+            "candidate_notice": a.candidate_notice,
+            "raw": a.raw,
+        });
+        writeln!(self.out, "{}", line).map_err(io_error)
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), String> {
+        self.out.flush().map_err(io_error)
+    }
+}
+
+struct MarkdownWriter {
+    out: BufWriter<File>,
+}
+
+fn markdown_cell(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+impl MarkdownWriter {
+    fn create(path: &Path) -> Result<Self, String> {
+        let mut out = BufWriter::new(File::create(path).map_err(io_error)?);
+        writeln!(out, "| {} |", COLUMNS.join(" | ")).map_err(io_error)?;
+        writeln!(out, "|{}", " --- |".repeat(COLUMNS.len())).map_err(io_error)?;
+        Ok(MarkdownWriter { out })
+    }
+}
+
+impl RowWriter for MarkdownWriter {
+    fn write_row(&mut self, announcement: &Announcement) -> Result<(), String> {
+        let row: Vec<String> = cells(announcement).iter().map(|c| markdown_cell(c)).collect();
+        writeln!(self.out, "| {} |", row.join(" | ")).map_err(io_error)
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), String> {
+        self.out.flush().map_err(io_error)
+    }
+}
+
+struct XlsxWriter {
+    workbook: Workbook,
+    path: PathBuf,
+    next_row: usize,
+    /// 行数上限（含表头），测试中可以调小
+    max_rows: usize,
+}
+
+fn xlsx_error(e: rust_xlsxwriter::XlsxError) -> String {
+    format!("写入 Excel 文件失败: {}", e)
+}
+
+impl XlsxWriter {
+    fn create(path: &Path) -> Result<Self, String> {
+        let mut workbook = Workbook::new();
+        // 常量内存模式：写完一行即落盘到临时文件
+        let sheet = workbook.add_worksheet_with_constant_memory();
+        sheet.set_name("公告").map_err(xlsx_error)?;
+        let header = Format::new().set_bold();
+        for (col, name) in COLUMNS.iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, *name, &header).map_err(xlsx_error)?;
+        }
+        sheet.set_column_width(2, 60).map_err(xlsx_error)?;
+        sheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+        Ok(XlsxWriter {
+            workbook,
+            path: path.to_path_buf(),
+            next_row: 1,
+            max_rows: XLSX_MAX_ROWS,
+        })
+    }
+
+    fn sheet(&mut self) -> Result<&mut Worksheet, String> {
+        self.workbook.worksheet_from_index(0).map_err(xlsx_error)
+    }
+}
+
+impl RowWriter for XlsxWriter {
+    fn write_row(&mut self, announcement: &Announcement) -> Result<(), String> {
+        if self.next_row >= self.max_rows {
+            return Err(format!("超过 Excel 单个工作表的行数上限（{} 行），请缩小导出范围或改用 CSV", self.max_rows));
+        }
+        let row = self.next_row as u32;
+        let sheet = self.sheet()?;
+        for (col, value) in cells(announcement).iter().enumerate() {
+            sheet.write_string(row, col as u16, neutralize_formula(value)).map_err(xlsx_error)?;
+        }
+        self.next_row += 1;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), String> {
+        self.workbook.save(&self.path).map_err(xlsx_error)
+    }
+}
+
+fn create_writer(format: ExportFormat, path: &Path) -> Result<Box<dyn RowWriter>, String> {
+    Ok(match format {
+        ExportFormat::Csv => Box::new(CsvWriter::create(path)?),
+        ExportFormat::Xlsx => Box::new(XlsxWriter::create(path)?),
+        ExportFormat::Jsonl => Box::new(JsonlWriter {
+            out: BufWriter::new(File::create(path).map_err(io_error)?),
+        }),
+        ExportFormat::Markdown => Box::new(MarkdownWriter::create(path)?),
+    })
+}
+
+/// 把符合条件的公告导出到 path；keyword 为全文检索关键字，可以为空
+pub fn export_announcements(
+    store: &AnnouncementStore,
+    path: &Path,
+    format: ExportFormat,
+    keyword: &str,
+    filters: &SearchFilters,
+) -> Result<ExportSummary, String> {
+    let writer = create_writer(format, path)?;
+    write_all(store, writer, path, format, keyword, filters)
+}
+
+fn write_all(
+    store: &AnnouncementStore,
+    mut writer: Box<dyn RowWriter>,
+    path: &Path,
+    format: ExportFormat,
+    keyword: &str,
+    filters: &SearchFilters,
+) -> Result<ExportSummary, String> {
+    let result = store
+        .for_each_announcement(keyword, filters, |announcement| writer.write_row(announcement))
+        .and_then(|rows| writer.finish().map(|_| rows));
+
+    match result {
+        Ok(rows) => Ok(ExportSummary {
+            path: path.to_path_buf(),
+            format,
+            rows,
+        }),
+        Err(e) => {
+            // 不留下写了一半的文件
+            let _ = std::fs::remove_file(path);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::NewAnnouncement;
+    use crate::test_support::TempDir;
+    use std::io::Read;
+
+    fn store_with(titles: &[&str]) -> AnnouncementStore {
+        let store = AnnouncementStore::open_in_memory().unwrap();
+        let rows: Vec<serde_json::Value> = titles
+            .iter()
+            .enumerate()
+            .map(|(i, title)| json!({ "id": i.to_string(), "title": title, "content": format!("第一行\n第二行 {}", i) }))
+            .collect();
+        let mapped: Vec<_> = rows.iter().map(|row| row.as_object().unwrap().clone()).collect();
+        let items: Vec<NewAnnouncement> = rows
+            .iter()
+            .zip(&mapped)
+            .map(|(row, mapped)| NewAnnouncement { row, mapped, detail_url: None })
+            .collect();
+        store.upsert_announcements("test", "manual", "run-1", &items).unwrap();
+        store
+    }
+
+    fn export(store: &AnnouncementStore, dir: &TempDir, format: ExportFormat) -> (PathBuf, usize) {
+        let path = dir.path().join(format!("export.{}", format.extension()));
+        let summary = export_announcements(store, &path, format, "", &SearchFilters::default()).unwrap();
+        (path, summary.rows)
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(csv_field("普通标题"), "普通标题");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("说 \"明\""), "\"说 \"\"明\"\"\"");
+        assert_eq!(csv_field("两\r\n行"), "\"两\r\n行\"");
+    }
+
+    #[test]
+    fn formula_like_cells_are_neutralized() {
+        for value in ["=HYPERLINK(\"http://evil\")", "+1", "-1+2", "@SUM(A1)", "\tTAB", "\rCR"] {
+            assert_eq!(neutralize_formula(value), format!("'{}", value));
+        }
+        assert_eq!(neutralize_formula("中标=结果"), "中标=结果");
+        assert_eq!(csv_field("=1,2"), "\"'=1,2\"");
+    }
+
+    #[test]
+    fn csv_export_has_bom_header_and_one_record_per_row() {
+        let dir = TempDir::new("export");
+        let store = store_with(&["管道, 工程", "=cmd|' /C calc'!A0"]);
+        let (path, rows) = export(&store, &dir, ExportFormat::Csv);
+        assert_eq!(rows, 2);
+
+        let content = std::fs::read_to_string(path).unwrap();
+        let content = content.strip_prefix('\u{FEFF}').expect("缺少 BOM");
+        assert!(content.starts_with(&format!("{}\r\n", COLUMNS.join(","))));
+        assert!(content.contains("\"管道, 工程\""));
+        assert!(content.contains("'=cmd|' /C calc'!A0"));
+        assert!(!content.contains(",=cmd"));
+        // 正文中的换行在引号内，记录之间用 \r\n 分隔
+        assert!(content.contains("\"第一行\n第二行 0\""));
+        assert_eq!(content.matches("\r\n").count(), 3);
+    }
+
+    #[test]
+    fn markdown_cells_escape_pipes_and_newlines() {
+        assert_eq!(markdown_cell("a|b"), "a\\|b");
+        assert_eq!(markdown_cell("a\\b"), "a\\\\b");
+        assert_eq!(markdown_cell("一\r\n二\n三"), "一<br>二<br>三");
+
+        let dir = TempDir::new("export");
+        let store = store_with(&["甲|乙"]);
+        let (path, _) = export(&store, &dir, ExportFormat::Markdown);
+        let content = std::fs::read_to_string(path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], format!("|{}", " --- |".repeat(COLUMNS.len())));
+        assert!(lines[2].contains("甲\\|乙"));
+        assert!(lines[2].contains("第一行<br>第二行 0"));
+    }
+
+    #[test]
+    fn jsonl_export_writes_one_object_per_line() {
+        let dir = TempDir::new("export");
+        let store = store_with(&["甲", "乙"]);
+        let (path, rows) = export(&store, &dir, ExportFormat::Jsonl);
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), rows);
+        assert!(lines.iter().all(|line| line["raw"]["title"] == line["title"]));
+    }
+
+    #[test]
+    fn xlsx_cells_are_written_as_neutralized_text() {
+        let dir = TempDir::new("export");
+        let store = store_with(&["=1+1"]);
+        let (path, rows) = export(&store, &dir, ExportFormat::Xlsx);
+        assert_eq!(rows, 1);
+
+        let mut archive = zip::ZipArchive::new(File::open(path).unwrap()).unwrap();
+        let mut sheet = String::new();
+        archive.by_name("xl/worksheets/sheet1.xml").unwrap().read_to_string(&mut sheet).unwrap();
+        assert!(sheet.contains("'=1+1"));
+        assert!(!sheet.contains("<f>"));
+    }
+
+    #[test]
+    fn xlsx_row_limit_fails_and_removes_partial_file() {
+        let dir = TempDir::new("export");
+        let store = store_with(&["甲", "乙", "丙"]);
+        let path = dir.path().join("export.xlsx");
+        let mut writer = XlsxWriter::create(&path).unwrap();
+        // 表头 + 2 行
+        writer.max_rows = 3;
+
+        let error = write_all(&store, Box::new(writer), &path, ExportFormat::Xlsx, "", &SearchFilters::default()).unwrap_err();
+        assert!(error.contains("行数上限（3 行）"), "{}", error);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn failed_export_leaves_no_file() {
+        let dir = TempDir::new("export");
+        let store = store_with(&["甲"]);
+        // 目标目录不存在
+        let path = dir.path().join("missing").join("export.csv");
+        assert!(export_announcements(&store, &path, ExportFormat::Csv, "", &SearchFilters::default()).is_err());
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,464 @@
+// ============================================================================
+// 本机 HTTP API（可选）
+// ============================================================================
+//
+// 供脚本和本机的其他程序调用，接口直接使用 Tauri 命令背后的同一组函数
+// （AppCore::run_spider、scheduler、AnnouncementStore、export），行为与界面一致：
+//   POST /api/v1/runs                         执行爬虫
+//   GET  /api/v1/runs                         执行历史
+//   GET/POST /api/v1/schedules                定时任务列表 / 新建
+//   GET/PUT/DELETE /api/v1/schedules/{id}     查看 / 替换 / 取消定时任务
+//   GET  /api/v1/announcements[/{id}]         检索公告 / 公告详情
+//   GET  /api/v1/export                       导出公告（下载文件）
+//   GET  /api/v1/openapi.json                 OpenAPI 文档（不需要令牌）
+// 只监听 127.0.0.1，其余请求都要带 Authorization: Bearer <token>。默认关闭，
+// 设置保存在 api_settings.json，令牌为空时自动生成，并登记为脱敏密钥
+
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use axum::body::Body;
+use axum::extract::{Path as UrlPath, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncRead, ReadBuf};
+use tokio::task::JoinHandle;
+use tokio_util::io::ReaderStream;
+
+use crate::app_core::{AppCore, RunOptions};
+use crate::backend::BackendOptions;
+use crate::config_file;
+use crate::error::{AppError, AppResult, ErrorKind};
+use crate::export::{self, ExportFormat};
+use crate::redact;
+use crate::scheduler::{self, ScheduleInfo};
+use crate::settings::{default_spider_id, SpiderConfig};
+use crate::spider_params::FieldError;
+use crate::spider_registry::SpiderRunOutput;
+use crate::store::{self, Announcement, RunRecord, SearchFilters, SearchPage};
+
+pub const SETTINGS_FILE: &str = "api_settings.json";
+/// OpenAPI 文档，servers 中的端口在返回时替换为实际端口
+const OPENAPI_DOCUMENT: &str = include_str!("openapi.json");
+/// 令牌的最小长度，避免使用容易猜到的短令牌
+const MIN_TOKEN_CHARS: usize = 16;
+/// 检索接口的页码上限
+const MAX_SEARCH_PAGE: u32 = 100_000;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// 访问令牌，为空时保存前自动生成
+    #[serde(default)]
+    pub token: String,
+}
+
+fn default_port() -> u16 {
+    17365
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        ApiSettings {
+            enabled: false,
+            port: default_port(),
+            token: String::new(),
+        }
+    }
+}
+
+impl ApiSettings {
+    /// 读取设置；文件不存在时使用默认值（关闭），文件损坏时返回可恢复的错误
+    pub fn load(app_data_dir: &Path) -> AppResult<Self> {
+        Ok(config_file::load_json(app_data_dir, SETTINGS_FILE)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, app_data_dir: &Path) -> Result<(), String> {
+        self.validate()?;
+        config_file::save_json(app_data_dir, SETTINGS_FILE, self)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port < 1024 {
+            return Err("HTTP API 端口必须在 1024-65535 之间".to_string());
+        }
+        let token = self.token.trim();
+        if !token.is_empty() && token.chars().count() < MIN_TOKEN_CHARS {
+            return Err(format!("访问令牌至少 {} 个字符", MIN_TOKEN_CHARS));
+        }
+        Ok(())
+    }
+
+    /// 令牌为空时生成一个随机令牌，返回是否生成了新令牌
+    pub fn ensure_token(&mut self) -> bool {
+        self.token = self.token.trim().to_string();
+        if !self.token.is_empty() {
+            return false;
+        }
+        self.token = uuid::Uuid::new_v4().simple().to_string();
+        true
+    }
+}
+
+/// 正在运行的 HTTP 服务，放在 Tauri 的 managed state 中
+#[derive(Default)]
+pub struct ApiServer {
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ApiServer {
+    /// 按设置（重新）启动服务；设置为关闭时只停止正在运行的服务
+    pub async fn apply(&self, core: Arc<AppCore>, settings: &ApiSettings) -> AppResult<()> {
+        self.stop().await;
+        if !settings.enabled {
+            return Ok(());
+        }
+        settings.validate().map_err(|e| AppError::invalid_input("api.invalid_settings", e))?;
+        let token = settings.token.trim().to_string();
+        if token.is_empty() {
+            return Err(AppError::config("HTTP API 缺少访问令牌"));
+        }
+        redact::register_secret(&token);
+
+        let address = ("127.0.0.1", settings.port);
+        let listener = tokio::net::TcpListener::bind(address).await.map_err(|e| {
+            AppError::new(ErrorKind::Environment, "api.bind_failed", format!("无法监听 127.0.0.1:{}", settings.port))
+                .detail(e.to_string())
+                .hint("端口可能被其他程序占用，换一个端口后重试")
+        })?;
+        let app = router(ApiState { core, token: Arc::from(token), port: settings.port });
+        let port = settings.port;
+        let handle = tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                log_warn!("[HTTP API] 服务异常结束: {}", e);
+            }
+        });
+        *self.task.lock().unwrap() = Some(handle);
+        log_info!("[HTTP API] 已启动: http://127.0.0.1:{}/api/v1", port);
+        Ok(())
+    }
+
+    /// 停止服务并等待端口释放
+    pub async fn stop(&self) {
+        let handle = self.task.lock().unwrap().take();
+        if let Some(handle) = handle {
+            handle.abort();
+            let _ = handle.await;
+            log_info!("[HTTP API] 已停止");
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ApiState {
+    core: Arc<AppCore>,
+    token: Arc<str>,
+    port: u16,
+}
+
+fn router(state: ApiState) -> Router {
+    let protected = Router::new()
+        .route("/runs", get(list_runs).post(run_spider))
+        .route("/schedules", get(list_schedules).post(create_schedule))
+        .route("/schedules/{id}", get(get_schedule).put(replace_schedule).delete(cancel_schedule))
+        .route("/announcements", get(search_announcements))
+        .route("/announcements/{id}", get(get_announcement))
+        .route("/export", get(export_announcements))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token));
+    let api = Router::new().route("/openapi.json", get(openapi)).merge(protected);
+    Router::new().nest("/api/v1", api).with_state(state)
+}
+
+/// 接口错误：AppError 序列化后的 JSON，HTTP 状态码由错误类型决定
+struct ApiError(StatusCode, AppError);
+
+impl From<AppError> for ApiError {
+    fn from(error: AppError) -> Self {
+        let status = match error.kind {
+            ErrorKind::NotFound => StatusCode::NOT_FOUND,
+            ErrorKind::Validation => StatusCode::BAD_REQUEST,
+            ErrorKind::Environment => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorKind::Network | ErrorKind::Smtp => StatusCode::BAD_GATEWAY,
+            ErrorKind::Config | ErrorKind::Execution | ErrorKind::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError(status, error)
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        ApiError::from(AppError::from(message))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(self.1)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+/// 在后台线程执行数据库和爬虫调用
+async fn blocking<T, E>(f: impl FnOnce() -> Result<T, E> + Send + 'static) -> ApiResult<T>
+where
+    T: Send + 'static,
+    E: Into<AppError> + Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(res) => res.map_err(|e| ApiError::from(e.into())),
+        Err(e) => Err(AppError::task(e).into()),
+    }
+}
+
+/// 逐字节比较全部内容，耗时与令牌在第几个字符不同无关
+fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+async fn require_token(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| token_matches(token.trim(), &state.token));
+    if !authorized {
+        log_warn!("[HTTP API] 拒绝未授权的请求: {} {}", request.method(), request.uri().path());
+        let error = AppError::new(ErrorKind::Validation, "api.unauthorized", "缺少或错误的访问令牌")
+            .hint("在 Authorization 请求头中以 Bearer 方式携带 api_settings.json 中的访问令牌");
+        return ApiError(StatusCode::UNAUTHORIZED, error).into_response();
+    }
+    log_info!("[HTTP API] {} {}", request.method(), request.uri().path());
+    next.run(request).await
+}
+
+async fn openapi(State(state): State<ApiState>) -> ApiResult<Json<Value>> {
+    let mut document: Value =
+        serde_json::from_str(OPENAPI_DOCUMENT).map_err(|e| format!("OpenAPI 文档无效: {}", e))?;
+    document["servers"] = serde_json::json!([{ "url": format!("http://127.0.0.1:{}/api/v1", state.port) }]);
+    Ok(Json(document))
+}
+
+/// POST /runs 的请求体，字段与 run_spider 命令的参数相同
+#[derive(Deserialize)]
+struct RunRequest {
+    #[serde(default)]
+    spider_id: Option<String>,
+    params: Value,
+    #[serde(default)]
+    fetch_details: bool,
+    #[serde(default)]
+    backend: BackendOptions,
+}
+
+async fn run_spider(State(state): State<ApiState>, Json(request): Json<RunRequest>) -> ApiResult<Json<SpiderRunOutput>> {
+    let core = state.core;
+    let output = blocking(move || {
+        let options = RunOptions {
+            job: store::MANUAL_JOB.to_string(),
+            fetch_details: request.fetch_details,
+            backend: request.backend,
+        };
+        core.run_spider(&request.spider_id.unwrap_or_else(default_spider_id), &options, request.params)
+    })
+    .await?;
+    Ok(Json(output))
+}
+
+#[derive(Deserialize)]
+struct RunsQuery {
+    limit: Option<u32>,
+}
+
+async fn list_runs(State(state): State<ApiState>, Query(query): Query<RunsQuery>) -> ApiResult<Json<Vec<RunRecord>>> {
+    let core = state.core;
+    let runs = blocking(move || core.store.list_runs(query.limit.unwrap_or(50))).await?;
+    Ok(Json(runs))
+}
+
+fn schedule_not_found(id: &str) -> ApiError {
+    AppError::not_found("schedule", format!("未找到定时任务: {}", id)).into()
+}
+
+async fn list_schedules(State(state): State<ApiState>) -> Json<Vec<ScheduleInfo>> {
+    Json(state.core.schedules.list())
+}
+
+async fn get_schedule(State(state): State<ApiState>, UrlPath(id): UrlPath<String>) -> ApiResult<Json<ScheduleInfo>> {
+    state.core.schedules.get(&id).map(Json).ok_or_else(|| schedule_not_found(&id))
+}
+
+/// 请求体与 start_scheduled_spider 命令的 config 相同
+async fn create_schedule(
+    State(state): State<ApiState>,
+    Json(config): Json<SpiderConfig>,
+) -> ApiResult<(StatusCode, Json<ScheduleInfo>)> {
+    let job = scheduler::plan(&state.core, &config, state.core.now())?;
+    let info = scheduler::start(&state.core, job);
+    log_info!("[HTTP API] 已登记定时任务 {}，执行时间: {}", info.id, info.next_run);
+    Ok((StatusCode::CREATED, Json(info)))
+}
+
+async fn replace_schedule(
+    State(state): State<ApiState>,
+    UrlPath(id): UrlPath<String>,
+    Json(config): Json<SpiderConfig>,
+) -> ApiResult<Json<ScheduleInfo>> {
+    let job = scheduler::plan(&state.core, &config, state.core.now())?;
+    let info = scheduler::replace(&state.core, &id, job).ok_or_else(|| schedule_not_found(&id))?;
+    log_info!("[HTTP API] 已替换定时任务 {}，执行时间: {}", info.id, info.next_run);
+    Ok(Json(info))
+}
+
+async fn cancel_schedule(State(state): State<ApiState>, UrlPath(id): UrlPath<String>) -> ApiResult<StatusCode> {
+    if state.core.schedules.cancel(&id) {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(schedule_not_found(&id))
+    }
+}
+
+/// 检索条件：q 为全文检索关键字，其余字段与 SearchFilters 相同
+#[derive(Deserialize)]
+struct SearchQuery {
+    #[serde(default)]
+    q: String,
+    spider_id: Option<String>,
+    job: Option<String>,
+    project_type: Option<String>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+}
+
+impl SearchQuery {
+    fn filters(&self) -> SearchFilters {
+        SearchFilters {
+            spider_id: self.spider_id.clone(),
+            job: self.job.clone(),
+            project_type: self.project_type.clone(),
+            date_from: self.date_from.clone(),
+            date_to: self.date_to.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PageQuery {
+    page: Option<u32>,
+    page_size: Option<u32>,
+}
+
+impl PageQuery {
+    /// 页码和每页条数，超出范围时返回字段级校验错误（400）
+    fn validate(&self) -> AppResult<(u32, u32)> {
+        let page = self.page.unwrap_or(1);
+        let page_size = self.page_size.unwrap_or(20);
+        let mut errors = Vec::new();
+        if page < 1 {
+            errors.push(FieldError::new("page", "minimum", "页码必须大于等于 1".to_string()));
+        } else if page > MAX_SEARCH_PAGE {
+            errors.push(FieldError::new("page", "maximum", format!("页码不能超过 {}", MAX_SEARCH_PAGE)));
+        }
+        if page_size < 1 {
+            errors.push(FieldError::new("page_size", "minimum", "每页条数必须大于等于 1".to_string()));
+        } else if page_size > store::MAX_PAGE_SIZE {
+            errors.push(FieldError::new("page_size", "maximum", format!("每页条数不能超过 {}", store::MAX_PAGE_SIZE)));
+        }
+        if errors.is_empty() {
+            Ok((page, page_size))
+        } else {
+            Err(AppError::validation(errors))
+        }
+    }
+}
+
+async fn search_announcements(
+    State(state): State<ApiState>,
+    Query(search): Query<SearchQuery>,
+    Query(page): Query<PageQuery>,
+) -> ApiResult<Json<SearchPage>> {
+    let (page, page_size) = page.validate()?;
+    let core = state.core;
+    let result = blocking(move || core.store.search(&search.q, &search.filters(), page, page_size)).await?;
+    Ok(Json(result))
+}
+
+async fn get_announcement(State(state): State<ApiState>, UrlPath(id): UrlPath<i64>) -> ApiResult<Json<Announcement>> {
+    let core = state.core;
+    blocking(move || core.store.get_announcement(id))
+        .await?
+        .map(Json)
+        .ok_or_else(|| AppError::not_found("announcement", format!("未找到公告: {}", id)).into())
+}
+
+#[derive(Deserialize)]
+struct ExportQuery {
+    format: ExportFormat,
+}
+
+/// 导出用的临时文件：按块读取，读完或连接断开（响应体被丢弃）时删除
+struct ExportFile {
+    file: Option<tokio::fs::File>,
+    path: PathBuf,
+}
+
+impl AsyncRead for ExportFile {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        match self.file.as_mut() {
+            Some(file) => Pin::new(file).poll_read(cx, buf),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+impl Drop for ExportFile {
+    fn drop(&mut self) {
+        // 先关闭文件，Windows 上打开的文件不能删除
+        drop(self.file.take());
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            log_warn!("[HTTP API] 删除临时导出文件失败 {:?}: {}", self.path, e);
+        }
+    }
+}
+
+/// 导出到临时文件后以流的形式返回，不把整个文件读入内存；文件名与界面导出的默认文件名相同
+async fn export_announcements(
+    State(state): State<ApiState>,
+    Query(search): Query<SearchQuery>,
+    Query(query): Query<ExportQuery>,
+) -> ApiResult<Response> {
+    let core = state.core;
+    let format = query.format;
+    let path = std::env::temp_dir().join(format!("rpa_export_{}.{}", uuid::Uuid::new_v4().simple(), format.extension()));
+    let export_path = path.clone();
+    let summary = blocking(move || export::export_announcements(&core.store, &export_path, format, &search.q, &search.filters())).await?;
+    let file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(e) => {
+            let _ = std::fs::remove_file(&path);
+            return Err(format!("读取导出文件失败: {}", e).into());
+        }
+    };
+    let body = Body::from_stream(ReaderStream::new(ExportFile { file: Some(file), path }));
+
+    log_info!("[HTTP API] 已导出 {} 条公告（{}）", summary.rows, format.extension());
+    let file_name = format!("announcements_{}.{}", Local::now().format("%Y%m%d_%H%M%S"), format.extension());
+    let headers = [
+        (header::CONTENT_TYPE, format.mime_type().to_string()),
+        (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name)),
+    ];
+    Ok((headers, [("x-export-rows", summary.rows.to_string())], body).into_response())
+}
@@ -0,0 +1,249 @@
+// ============================================================================
+// 界面语言与消息目录
+// ============================================================================
+//
+// 面向用户的后端文字（错误说明和处理建议、邮件主题和正文、前端通知）按 key 从目录中取，
+// 每个 key 有中文和英文两个版本，文字中的 {name} 由调用方传入的参数替换。
+// 语言保存在应用数据目录的 locale_settings.json 中，默认中文。
+// 日志和 AppError 的 detail（原始错误、命令输出）不做翻译
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config_file;
+use crate::error::AppResult;
+
+pub const SETTINGS_FILE: &str = "locale_settings.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Zh,
+    En,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LocaleSettings {
+    #[serde(default)]
+    pub locale: Locale,
+}
+
+impl LocaleSettings {
+    /// 读取设置；文件不存在时使用中文
+    pub fn load(app_data_dir: &Path) -> AppResult<Self> {
+        Ok(config_file::load_json(app_data_dir, SETTINGS_FILE)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, app_data_dir: &Path) -> Result<(), String> {
+        config_file::save_json(app_data_dir, SETTINGS_FILE, self)
+    }
+}
+
+/// (key, 中文, English)
+const CATALOG: &[(&str, &str, &str)] = &[
+    // 定时任务
+    ("schedule.invalid_month", "月份必须在 1-12 之间", "Month must be between 1 and 12"),
+    ("schedule.invalid_day", "日期必须在 1-31 之间", "Day must be between 1 and 31"),
+    ("schedule.invalid_hour", "小时必须在 0-23 之间", "Hour must be between 0 and 23"),
+    ("schedule.invalid_minute", "分钟必须在 0-59 之间", "Minute must be between 0 and 59"),
+    ("schedule.invalid_second", "秒必须在 0-59 之间", "Second must be between 0 and 59"),
+    ("schedule.invalid_date", "无效的日期", "Invalid date"),
+    ("schedule.invalid_clock", "无效的时间", "Invalid time"),
+    ("schedule.invalid_local_time", "无法创建目标时间", "The target time does not exist in the local time zone"),
+    ("schedule.time_passed", "执行时间 {time} 已过", "The scheduled time {time} has already passed"),
+    ("schedule.time_passed.hint", "请选择未来的时间", "Choose a time in the future"),
+    ("schedule.started", "定时任务已启动：将在 {time} 执行，结果将发送到 {email}", "Scheduled task started: it will run at {time} and send the results to {email}"),
+    // 爬虫执行
+    ("backend.python", "Python", "Python"),
+    ("backend.binary", "打包版本", "bundled binary"),
+    ("backend.plugin", "插件", "plugin"),
+    ("spider.unavailable", "{backend} 不可用: {error}", "{backend} unavailable: {error}"),
+    ("spider.execution_failed", "爬虫执行失败（{backend}）", "Spider run failed ({backend})"),
+    ("spider.captcha", "爬虫执行失败（{backend}）：验证码识别失败", "Spider run failed ({backend}): captcha recognition failed"),
+    ("spider.captcha.hint", "稍后重试，或在限速设置中增大请求间隔", "Retry later, or increase the request interval in the rate limit settings"),
+    ("network.request_failed", "爬虫执行失败（{backend}）：网络请求失败", "Spider run failed ({backend}): network request failed"),
+    ("network.request_failed.hint", "检查网络连接和代理设置", "Check the network connection and proxy settings"),
+    ("spider.no_backend", "没有可用的爬虫执行环境", "No spider backend is available"),
+    ("spider.no_backend.hint_python", "在运行环境设置中安装私有 Python 环境，或安装 Python 3 及依赖", "Install the private Python environment in the runtime settings, or install Python 3 and the dependencies"),
+    ("spider.no_backend.hint_binary", "检查打包的 spider_bin 是否存在", "Check that the bundled spider_bin exists"),
+    ("spider.no_backend.hint_diagnose", "调用环境诊断查看详细原因", "Run the environment diagnostics for details"),
+    // 邮件
+    ("email.sent", "邮件已成功发送到: {to}", "Email sent to: {to}"),
+    ("email.send_failed", "邮件发送失败: {error}", "Failed to send email: {error}"),
+    ("email.scheduled_subject", "爬虫任务执行结果", "Spider task result"),
+    ("email.result_ok", "爬虫执行成功：\n{data}", "Spider run succeeded:\n{data}"),
+    ("email.result_failed", "爬虫执行失败：\n{error}", "Spider run failed:\n{error}"),
+    ("email.new_announcements", "新增公告 {count} 条：", "{count} new announcements:"),
+    ("email.announcement_detail", "详情", "Details"),
+    ("email.announcement_archive", "本地归档", "Local archive"),
+    ("email.announcement_attachment", "附件", "Attachment"),
+    ("email.announcement_candidates", "中标候选人", "Winning candidates"),
+    ("smtp.not_configured", "邮件发送失败: 请先配置 SMTP 服务器", "Failed to send email: configure the SMTP server first"),
+    ("smtp.not_configured.hint_fill", "在应用界面填写 SMTP 配置：服务器 smtp.qq.com，端口 587，用户名为完整的QQ邮箱", "Fill in the SMTP settings in the app: server smtp.qq.com, port 587, the full QQ mail address as the user name"),
+    ("smtp.not_configured.hint_password", "密码处填写QQ邮箱授权码（不是登录密码）：登录QQ邮箱网页版 -> 设置 -> 账户 -> 开启SMTP服务 -> 生成授权码", "Use the QQ mail authorization code as the password, not the login password: QQ mail web -> Settings -> Account -> enable SMTP -> generate an authorization code"),
+    ("smtp.not_configured.hint_save", "点击'保存 SMTP 配置'按钮后重新执行任务", "Click 'Save SMTP settings' and run the task again"),
+    ("smtp.invalid_recipient", "无效的收件人地址: {to}", "Invalid recipient address: {to}"),
+    ("smtp.invalid_recipient.format", "邮箱地址格式不正确: {to}", "Malformed email address: {to}"),
+    ("smtp.invalid_recipient.format_hint", "请检查邮箱地址是否正确（例如: user@example.com）", "Check the email address (for example: user@example.com)"),
+    ("smtp.invalid_recipient.typo", "邮箱地址可能有拼写错误: {to}", "The email address may contain a typo: {to}"),
+    ("smtp.invalid_recipient.typo_hint", "应该是 @gmail.com 而不是 @gamil.com", "Use @gmail.com instead of @gamil.com"),
+    ("smtp.invalid_sender", "无效的发件人地址", "Invalid sender address"),
+    ("smtp.invalid_sender.hint", "SMTP 用户名请填写完整的邮箱地址", "Use the full email address as the SMTP user name"),
+    ("smtp.auth_failed", "SMTP 认证失败", "SMTP authentication failed"),
+    ("smtp.auth_failed.hint_account", "检查邮箱账号是否为完整邮箱地址", "Check that the account is a full email address"),
+    ("smtp.auth_failed.hint_password", "密码处填写授权码，而不是登录密码", "Use the authorization code as the password, not the login password"),
+    ("smtp.auth_failed.hint_service", "确认邮箱已开启 SMTP 服务", "Make sure SMTP is enabled for the mailbox"),
+    ("smtp.temporary_failure", "邮件服务器暂时无法处理请求", "The mail server cannot handle the request right now"),
+    ("smtp.temporary_failure.hint", "稍后重试", "Retry later"),
+    ("smtp.rejected", "邮件服务器拒绝了这封邮件", "The mail server rejected the message"),
+    ("smtp.rejected.hint", "检查收件人地址和邮件内容", "Check the recipient address and the message content"),
+    ("smtp.tls_failed", "与 SMTP 服务器建立加密连接失败", "Could not establish an encrypted connection to the SMTP server"),
+    ("smtp.tls_failed.hint", "检查端口：587 使用 STARTTLS，465 使用 SSL", "Check the port: 587 uses STARTTLS, 465 uses SSL"),
+    ("smtp.connection_failed", "无法连接 SMTP 服务器", "Could not connect to the SMTP server"),
+    ("smtp.connection_failed.hint_server", "检查 SMTP 服务器地址和端口是否正确", "Check the SMTP server address and port"),
+    ("smtp.connection_failed.hint_network", "检查网络连接和代理设置", "Check the network connection and proxy settings"),
+    // 前端通知
+    ("notify.email_sent", "邮件已发送", "Email sent"),
+    ("notify.email_sent_to", "结果已发送到 {email}", "Results sent to {email}"),
+    ("notify.email_failed", "邮件发送失败", "Email failed"),
+    ("notify.smtp_incomplete", "请先完成 SMTP 配置", "Complete the SMTP settings first"),
+    ("notify.test_email_sent", "测试邮件已发送", "Test email sent"),
+    ("notify.test_email_sent_to", "邮件已发送到 {email}", "Email sent to {email}"),
+    ("notify.schedule_started", "定时任务已启动", "Scheduled task started"),
+    ("notify.schedule_runs_at", "任务将在 {time} 执行", "The task will run at {time}"),
+    ("notify.config_saved", "配置已保存", "Settings saved"),
+    ("notify.config_saved_body", "配置信息已成功保存", "The settings were saved"),
+    ("notify.smtp_saved_body", "SMTP 配置已成功保存", "SMTP settings saved"),
+    ("notify.smtp_saved_incomplete", "SMTP 配置已保存，但用户名或密码为空", "SMTP settings saved, but the user name or password is empty"),
+    ("notify.save_failed", "保存失败", "Save failed"),
+    ("notify.load_failed", "加载失败", "Load failed"),
+    ("notify.smtp_server_required", "SMTP 服务器地址不能为空", "The SMTP server address is required"),
+    ("notify.smtp_port_invalid", "SMTP 端口必须是 1-65535 之间的数字", "The SMTP port must be a number between 1 and 65535"),
+    ("notify.smtp_save_failed", "SMTP 配置保存失败: {error}", "Failed to save SMTP settings: {error}"),
+    ("notify.smtp_load_failed", "SMTP 配置加载失败: {error}", "Failed to load SMTP settings: {error}"),
+    ("notify.copied", "复制成功", "Copied"),
+];
+
+fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    CATALOG.iter().find(|(k, _, _)| *k == key).map(|(_, zh, en)| match locale {
+        Locale::Zh => *zh,
+        Locale::En => *en,
+    })
+}
+
+/// 取 key 对应的文字，目录中没有时返回 key 本身
+pub fn text(locale: Locale, key: &str) -> String {
+    lookup(locale, key).unwrap_or(key).to_string()
+}
+
+/// 取 key 对应的文字并替换 {name} 参数
+pub fn format(locale: Locale, key: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(text(locale, key), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+/// 前端使用的部分（notify.*），key 到文字的映射
+pub fn frontend_catalog(locale: Locale) -> BTreeMap<&'static str, &'static str> {
+    CATALOG
+        .iter()
+        .filter(|(key, _, _)| key.starts_with("notify."))
+        .filter_map(|(key, _, _)| lookup(locale, key).map(|text| (*key, text)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    use crate::test_support::TempDir;
+
+    /// 文字中的 {name} 参数
+    fn placeholders(text: &str) -> BTreeSet<&str> {
+        text.split('{').skip(1).filter_map(|rest| rest.split_once('}').map(|(name, _)| name)).collect()
+    }
+
+    #[test]
+    fn catalog_keys_are_unique_and_translated() {
+        let mut keys = BTreeSet::new();
+        for (key, zh, en) in CATALOG {
+            assert!(keys.insert(*key), "重复的 key: {}", key);
+            assert!(!zh.trim().is_empty() && !en.trim().is_empty(), "{} 缺少翻译", key);
+            assert_eq!(placeholders(zh), placeholders(en), "{} 的中英文参数不一致", key);
+            assert!(!en.chars().any(|c| ('\u{4e00}'..='\u{9fff}').contains(&c)), "{} 的英文包含中文: {}", key, en);
+        }
+    }
+
+    #[test]
+    fn frontend_catalog_has_the_same_keys_in_both_locales() {
+        let zh = frontend_catalog(Locale::Zh);
+        let en = frontend_catalog(Locale::En);
+        assert!(!zh.is_empty());
+        assert!(zh.keys().eq(en.keys()));
+        assert!(zh.keys().all(|key| key.starts_with("notify.")));
+        assert_ne!(zh.get("notify.copied"), en.get("notify.copied"));
+    }
+
+    #[test]
+    fn frontend_keys_exist_in_catalog() {
+        // 前端 t("notify.xxx") 用到的 key 都要在目录中，否则界面直接显示 key
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../rpa_app/src");
+        let mut pending = vec![src];
+        let mut used = BTreeSet::new();
+        while let Some(dir) = pending.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+            for path in entries.flatten().map(|entry| entry.path()) {
+                if path.is_dir() {
+                    pending.push(path);
+                } else if path.extension().is_some_and(|ext| ext == "ts" || ext == "vue") {
+                    let content = std::fs::read_to_string(&path).unwrap();
+                    for rest in content.split("t(\"notify.").skip(1) {
+                        if let Some((name, _)) = rest.split_once('"') {
+                            used.insert(format!("notify.{}", name));
+                        }
+                    }
+                }
+            }
+        }
+        assert!(!used.is_empty(), "没有找到前端源码");
+        let catalog = frontend_catalog(Locale::Zh);
+        for key in &used {
+            assert!(catalog.contains_key(key.as_str()), "目录中没有前端使用的 {}", key);
+        }
+    }
+
+    #[test]
+    fn text_and_format() {
+        assert_eq!(text(Locale::Zh, "notify.copied"), "复制成功");
+        assert_eq!(text(Locale::En, "notify.copied"), "Copied");
+        // 目录中没有的 key 原样返回
+        assert_eq!(text(Locale::En, "no.such.key"), "no.such.key");
+        assert_eq!(
+            format(Locale::En, "schedule.started", &[("time", &"08:00"), ("email", &"a@example.com")]),
+            "Scheduled task started: it will run at 08:00 and send the results to a@example.com"
+        );
+        // 没有传入的参数保持原样
+        assert_eq!(format(Locale::Zh, "schedule.time_passed", &[]), "执行时间 {time} 已过");
+    }
+
+    #[test]
+    fn locale_is_persisted() {
+        let dir = TempDir::new("i18n");
+        assert_eq!(LocaleSettings::load(dir.path()).unwrap().locale, Locale::Zh);
+
+        LocaleSettings { locale: Locale::En }.save(dir.path()).unwrap();
+        let raw = std::fs::read_to_string(dir.path().join(SETTINGS_FILE)).unwrap();
+        assert!(raw.contains("\"en\""), "{}", raw);
+        assert_eq!(LocaleSettings::load(dir.path()).unwrap().locale, Locale::En);
+
+        std::fs::write(dir.path().join(SETTINGS_FILE), r#"{"locale": "fr"}"#).unwrap();
+        assert!(LocaleSettings::load(dir.path()).is_err());
+        std::fs::write(dir.path().join(SETTINGS_FILE), "{}").unwrap();
+        assert_eq!(LocaleSettings::load(dir.path()).unwrap().locale, Locale::Zh);
+    }
+}
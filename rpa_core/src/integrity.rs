@@ -0,0 +1,257 @@
+// ============================================================================
+// 打包文件的完整性校验
+// ============================================================================
+//
+// build.rs 在编译时计算 spiders.py、JS 辅助脚本和 spider_bin 的 SHA-256，生成 integrity.json
+// 并编译进程序。执行前按文件名对照清单校验：
+//   - enforce（默认）：哈希不一致或清单中没有的文件拒绝执行，运行环境解析会跳过这个候选
+//   - warn：只记录警告，仍然执行（开发时修改了脚本但没有重新编译）
+// 用户在运行环境设置中指定的路径不在清单范围内，只给出警告
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// build.rs 生成的清单
+const MANIFEST_JSON: &str = include_str!(concat!(env!("OUT_DIR"), "/integrity.json"));
+/// spiders.py 运行时从同目录加载的脚本
+pub const SCRIPT_HELPERS: [&str; 3] = ["decryption.js", "jsencrypt.js", "js_executor.js"];
+pub const SCRIPT_NAME: &str = "spiders.py";
+pub const BINARY_NAME: &str = "spider_bin";
+
+#[derive(Deserialize, Debug, Default)]
+struct Manifest {
+    files: BTreeMap<String, String>,
+}
+
+fn parse_manifest(json: &str) -> Result<Manifest, String> {
+    serde_json::from_str(json).map_err(|e| format!("完整性清单格式错误: {}", e))
+}
+
+/// 清单由 build.rs 生成，格式错误说明构建有问题：记录错误并按空清单处理，
+/// enforce 模式下所有文件都会被拒绝，而不是悄悄放行
+fn manifest() -> &'static Manifest {
+    static MANIFEST: OnceLock<Manifest> = OnceLock::new();
+    MANIFEST.get_or_init(|| {
+        parse_manifest(MANIFEST_JSON).unwrap_or_else(|e| {
+            log_error!("[完整性] ❌ {}，请重新构建应用", e);
+            Manifest::default()
+        })
+    })
+}
+
+/// 校验不通过时的处理方式
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityMode {
+    #[default]
+    Enforce,
+    Warn,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityStatus {
+    /// 与清单一致
+    Verified,
+    /// 哈希不一致
+    Mismatch,
+    /// 清单中没有这个文件（构建时不存在）
+    Unlisted,
+    /// 文件无法读取
+    Unreadable,
+}
+
+/// 单个文件的校验结果
+#[derive(Serialize, Debug, Clone)]
+pub struct FileIntegrity {
+    /// 清单中的文件名
+    pub name: String,
+    pub path: String,
+    pub status: IntegrityStatus,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+impl FileIntegrity {
+    pub fn describe(&self) -> String {
+        match self.status {
+            IntegrityStatus::Verified => format!("{} 校验通过", self.name),
+            IntegrityStatus::Mismatch => format!(
+                "{} 的 SHA-256 与完整性清单不一致（期望 {}，实际 {}）",
+                self.name,
+                self.expected.as_deref().unwrap_or_default(),
+                self.actual.as_deref().unwrap_or_default()
+            ),
+            IntegrityStatus::Unlisted => format!("完整性清单中没有 {}（构建时不存在）", self.name),
+            IntegrityStatus::Unreadable => format!("无法读取 {} 进行校验", self.name),
+        }
+    }
+}
+
+fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// 按清单中的文件名校验一个文件
+pub fn verify_file(name: &str, path: &Path) -> FileIntegrity {
+    verify_against(&manifest().files, name, path)
+}
+
+fn verify_against(files: &BTreeMap<String, String>, name: &str, path: &Path) -> FileIntegrity {
+    let expected = files.get(name).cloned();
+    let actual = sha256_file(path).ok();
+    let status = match (&expected, &actual) {
+        (_, None) => IntegrityStatus::Unreadable,
+        (None, Some(_)) => IntegrityStatus::Unlisted,
+        (Some(expected), Some(actual)) if expected.eq_ignore_ascii_case(actual) => IntegrityStatus::Verified,
+        (Some(_), Some(_)) => IntegrityStatus::Mismatch,
+    };
+    FileIntegrity {
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        status,
+        expected,
+        actual,
+    }
+}
+
+/// 校验 spiders.py 和同目录下的 JS 辅助脚本
+pub fn verify_script(script: &Path) -> Vec<FileIntegrity> {
+    let dir = script.parent().unwrap_or(Path::new("."));
+    let mut results = vec![verify_file(SCRIPT_NAME, script)];
+    results.extend(SCRIPT_HELPERS.iter().map(|name| verify_file(name, &dir.join(name))));
+    results
+}
+
+pub fn verify_binary(binary: &Path) -> Vec<FileIntegrity> {
+    vec![verify_file(BINARY_NAME, binary)]
+}
+
+/// 按校验模式处理结果：enforce 时有任何文件未通过即返回错误，warn 时只记录警告
+pub fn enforce(results: &[FileIntegrity], mode: IntegrityMode) -> Result<(), String> {
+    let failures: Vec<String> = results
+        .iter()
+        .filter(|r| r.status != IntegrityStatus::Verified)
+        .map(FileIntegrity::describe)
+        .collect();
+    if failures.is_empty() {
+        return Ok(());
+    }
+    match mode {
+        IntegrityMode::Enforce => Err(format!("完整性校验失败: {}", failures.join("；"))),
+        IntegrityMode::Warn => {
+            for failure in &failures {
+                log_warn!("[完整性] ⚠️ {}", failure);
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    const CONTENT: &[u8] = b"print('hello')\n";
+    /// CONTENT 的 SHA-256
+    const CONTENT_SHA256: &str = "03e693d9f2f687e0f40e36a8df7fcb4d1c22974012b7c2a55c000eb30f305824";
+
+    fn listed(name: &str, hash: &str) -> BTreeMap<String, String> {
+        BTreeMap::from([(name.to_string(), hash.to_string())])
+    }
+
+    fn result(status: IntegrityStatus) -> FileIntegrity {
+        FileIntegrity {
+            name: SCRIPT_NAME.to_string(),
+            path: String::new(),
+            status,
+            expected: Some("aa".to_string()),
+            actual: Some("bb".to_string()),
+        }
+    }
+
+    #[test]
+    fn verify_file_statuses() {
+        let dir = TempDir::new("integrity");
+        let path = dir.path().join(SCRIPT_NAME);
+        std::fs::write(&path, CONTENT).unwrap();
+        let actual = sha256_file(&path).unwrap();
+
+        // 清单中的哈希大小写不敏感
+        let verified = verify_against(&listed(SCRIPT_NAME, &actual.to_uppercase()), SCRIPT_NAME, &path);
+        assert_eq!(verified.status, IntegrityStatus::Verified);
+        assert_eq!(verified.actual.as_deref(), Some(actual.as_str()));
+
+        let mismatch = verify_against(&listed(SCRIPT_NAME, &"0".repeat(64)), SCRIPT_NAME, &path);
+        assert_eq!(mismatch.status, IntegrityStatus::Mismatch);
+        assert!(mismatch.describe().contains(&actual));
+
+        let unlisted = verify_against(&listed(BINARY_NAME, &actual), SCRIPT_NAME, &path);
+        assert_eq!(unlisted.status, IntegrityStatus::Unlisted);
+        assert_eq!(unlisted.expected, None);
+
+        let unreadable = verify_against(&listed(SCRIPT_NAME, &actual), SCRIPT_NAME, &dir.path().join("missing.py"));
+        assert_eq!(unreadable.status, IntegrityStatus::Unreadable);
+        assert_eq!(unreadable.actual, None);
+    }
+
+    #[test]
+    fn sha256_matches_known_digest() {
+        let dir = TempDir::new("integrity");
+        let path = dir.path().join("content");
+        std::fs::write(&path, CONTENT).unwrap();
+        assert_eq!(sha256_file(&path).unwrap(), CONTENT_SHA256);
+    }
+
+    #[test]
+    fn enforce_rejects_failures_and_warn_lets_them_through() {
+        let verified = [result(IntegrityStatus::Verified)];
+        assert!(enforce(&verified, IntegrityMode::Enforce).is_ok());
+        assert!(enforce(&[], IntegrityMode::Enforce).is_ok());
+
+        for status in [IntegrityStatus::Mismatch, IntegrityStatus::Unlisted, IntegrityStatus::Unreadable] {
+            let results = [result(IntegrityStatus::Verified), result(status)];
+            let error = enforce(&results, IntegrityMode::Enforce).unwrap_err();
+            assert!(error.contains(&results[1].describe()), "{}", error);
+            assert!(enforce(&results, IntegrityMode::Warn).is_ok());
+        }
+    }
+
+    #[test]
+    fn malformed_manifest_is_an_error() {
+        assert!(parse_manifest("{").is_err());
+        assert!(parse_manifest("{}").is_err());
+        assert!(parse_manifest(r#"{"files": ["spiders.py"]}"#).is_err());
+        let manifest = parse_manifest(r#"{"files": {"spiders.py": "aa"}}"#).unwrap();
+        assert_eq!(manifest.files.get(SCRIPT_NAME).map(String::as_str), Some("aa"));
+    }
+
+    #[test]
+    fn embedded_manifest_covers_bundled_scripts() {
+        let manifest = parse_manifest(MANIFEST_JSON).unwrap();
+        // build.rs 与这里读取的是同一份脚本，编译后没有改动时全部校验通过
+        let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("../中国石油招标投标网").join(SCRIPT_NAME);
+        for name in std::iter::once(SCRIPT_NAME).chain(SCRIPT_HELPERS) {
+            assert!(manifest.files.contains_key(name), "清单中没有 {}", name);
+        }
+        let results = verify_script(&script);
+        assert!(results.iter().all(|r| r.status == IntegrityStatus::Verified), "{:?}", results);
+    }
+}
@@ -0,0 +1,44 @@
+// ============================================================================
+// rpa_core：与界面无关的应用核心
+// ============================================================================
+//
+// rpa_app 和 erjinzhi_app 共用的爬虫执行、邮件发送、定时任务、配置读写和公告数据库。
+// 两个应用通过 AppIdentity（应用标识、显示名称、功能开关）区分，
+// Tauri 命令留在各自的 src-tauri 中，只做参数转换和线程切换
+
+#[macro_use]
+pub mod logging;
+pub mod app_core;
+pub mod automation;
+pub mod backend;
+pub mod candidate_extract;
+pub mod clock;
+pub mod config_file;
+pub mod crawl_policy;
+pub mod detail_fetch;
+pub mod diagnostics;
+pub mod error;
+pub mod export;
+#[cfg(feature = "http-api")]
+pub mod http_api;
+pub mod i18n;
+pub mod integrity;
+pub mod mailer;
+pub mod network;
+pub mod process;
+pub mod spider_params;
+pub mod spider_registry;
+pub mod spider_worker;
+pub mod profile;
+pub mod redact;
+pub mod runtime;
+pub mod scheduler;
+pub mod settings;
+pub mod store;
+pub mod venv;
+#[cfg(test)]
+mod test_support;
+
+pub use app_core::{AppCore, AppFeatures, AppIdentity, AppPaths, RunOptions, Services};
+pub use settings::{SmtpConfig, SpiderConfig};
+pub use spider_params::SpiderParams;
@@ -0,0 +1,374 @@
+// ============================================================================
+// 结构化日志
+// ============================================================================
+//
+// 打包后的 GUI 应用没有控制台，println! 输出看不到。后端日志统一使用 log_info! / log_warn! / log_error!：
+//   - 仍然输出到控制台（开发时）
+//   - 以 JSON Lines 写入应用日志目录的 rpa_app.log，超过 MAX_FILE_BYTES 时轮转为 rpa_app.1.log ... rpa_app.<MAX_FILES>.log
+//   - 通过监听器转发给前端（log-line 事件）
+// 每条记录包含时间、级别、标签（消息开头的 [标签]）、消息和当前执行的 run_id，消息在输出前经过脱敏（redact.rs）。
+// run_id 按线程记录：with_run_id 执行期间当前线程写出的日志都带上这个 ID，用于按一次执行筛选日志。
+// init 之前的日志只输出到控制台
+
+use std::cell::RefCell;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+
+use chrono::{DateTime, Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+
+pub const LOG_FILE: &str = "rpa_app.log";
+/// 单个日志文件的最大字节数
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// 保留的轮转文件数量
+const MAX_FILES: usize = 5;
+/// query_logs 默认返回的最大条数
+const DEFAULT_QUERY_LIMIT: usize = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// 一条日志记录（日志文件中的一行）
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LogRecord {
+    /// RFC 3339 格式的本地时间
+    pub ts: String,
+    pub level: Level,
+    /// 消息开头 [] 中的标签，如 "邮件"、"定时任务"
+    pub target: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+}
+
+/// query_logs 的筛选条件
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct LogQuery {
+    /// 最低级别，如 warn 表示 warn 和 error
+    #[serde(default)]
+    pub level: Option<Level>,
+    #[serde(default)]
+    pub run_id: Option<String>,
+    /// 只返回此时间（RFC 3339）之后的记录
+    #[serde(default)]
+    pub since: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+type Listener = Box<dyn Fn(&LogRecord) + Send + Sync>;
+
+struct FileSink {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+}
+
+static SINK: Mutex<Option<FileSink>> = Mutex::new(None);
+static LISTENER: RwLock<Option<Listener>> = RwLock::new(None);
+/// 所有级别的日志都输出到 stderr（命令行工具把 stdout 留给结果输出）
+static CONSOLE_STDERR: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static RUN_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// 开始写入日志文件
+pub fn init(log_dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(log_dir).map_err(|e| format!("创建日志目录失败: {}", e))?;
+    let sink = FileSink::open(log_dir)?;
+    *SINK.lock().unwrap_or_else(|e| e.into_inner()) = Some(sink);
+    Ok(())
+}
+
+/// 设置日志监听器（每写一条日志调用一次）
+pub fn set_listener(listener: impl Fn(&LogRecord) + Send + Sync + 'static) {
+    *LISTENER.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(listener));
+}
+
+/// 设置为 true 后 info 级别的日志也输出到 stderr
+pub fn set_console_stderr(enabled: bool) {
+    CONSOLE_STDERR.store(enabled, Ordering::Relaxed);
+}
+
+/// 在当前线程上执行 f，期间写出的日志都带上 run_id
+pub fn with_run_id<T>(run_id: &str, f: impl FnOnce() -> T) -> T {
+    let previous = RUN_ID.with(|current| current.replace(Some(run_id.to_string())));
+    let result = f();
+    RUN_ID.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+/// 拆分消息开头的 [标签]
+fn split_target(message: &str) -> (String, String) {
+    if let Some(rest) = message.strip_prefix('[') {
+        if let Some(end) = rest.find(']') {
+            return (rest[..end].to_string(), rest[end + 1..].trim_start().to_string());
+        }
+    }
+    (String::new(), message.to_string())
+}
+
+/// 当前时间、当前线程 run_id 下的一条记录
+fn new_record(level: Level, message: &str) -> LogRecord {
+    let (target, message) = split_target(message);
+    LogRecord {
+        ts: Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+        level,
+        target,
+        message,
+        run_id: RUN_ID.with(|current| current.borrow().clone()),
+    }
+}
+
+/// 写一条日志，由 log_info! / log_warn! / log_error! 调用
+pub fn write(level: Level, message: String) {
+    let message = crate::redact::redact(&message);
+    if level >= Level::Warn || CONSOLE_STDERR.load(Ordering::Relaxed) {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
+
+    let record = new_record(level, &message);
+    if let Some(sink) = SINK.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        if let Err(e) = sink.append(&record) {
+            eprintln!("[日志] 写入日志文件失败: {}", e);
+        }
+    }
+    if let Some(listener) = LISTENER.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        listener(&record);
+    }
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => { $crate::logging::write($crate::logging::Level::Info, format!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)*) => { $crate::logging::write($crate::logging::Level::Warn, format!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => { $crate::logging::write($crate::logging::Level::Error, format!($($arg)*)) };
+}
+
+/// 第 index 个日志文件：0 为当前文件，1.. 为轮转后的文件（数字越大越旧）
+fn file_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(LOG_FILE)
+    } else {
+        dir.join(LOG_FILE.replace(".log", &format!(".{}.log", index)))
+    }
+}
+
+impl FileSink {
+    fn open(dir: &Path) -> Result<Self, String> {
+        let path = file_path(dir, 0);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("打开日志文件 {:?} 失败: {}", path, e))?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(FileSink { dir: dir.to_path_buf(), file, size, max_bytes: MAX_FILE_BYTES })
+    }
+
+    fn append(&mut self, record: &LogRecord) -> Result<(), String> {
+        let mut line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+        line.push('\n');
+        if self.size > 0 && self.size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes()).map_err(|e| e.to_string())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// rpa_app.log -> rpa_app.1.log -> ... -> rpa_app.<MAX_FILES>.log，最旧的文件删除
+    fn rotate(&mut self) -> Result<(), String> {
+        let _ = fs::remove_file(file_path(&self.dir, MAX_FILES));
+        for index in (0..MAX_FILES).rev() {
+            let from = file_path(&self.dir, index);
+            if from.exists() {
+                fs::rename(&from, file_path(&self.dir, index + 1)).map_err(|e| format!("轮转日志文件失败: {}", e))?;
+            }
+        }
+        *self = FileSink { max_bytes: self.max_bytes, ..FileSink::open(&self.dir)? };
+        Ok(())
+    }
+}
+
+/// 读取一个日志文件中的记录，无法解析的行跳过
+fn read_file(path: &Path) -> Vec<LogRecord> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+/// 从新到旧依次读取日志文件，直到 enough 返回 true；结果按时间从旧到新排列
+fn read_files(log_dir: &Path, enough: impl Fn(&[LogRecord]) -> bool) -> Vec<LogRecord> {
+    let mut records = Vec::new();
+    for index in 0..=MAX_FILES {
+        let mut older = read_file(&file_path(log_dir, index));
+        older.append(&mut records);
+        records = older;
+        if enough(&records) {
+            break;
+        }
+    }
+    records
+}
+
+/// 最近的 lines 条日志
+pub fn tail(log_dir: &Path, lines: usize) -> Vec<LogRecord> {
+    let records = read_files(log_dir, |records| records.len() >= lines);
+    let skip = records.len().saturating_sub(lines);
+    records.into_iter().skip(skip).collect()
+}
+
+/// 按级别、run_id 和起始时间筛选日志，返回最近的 limit 条（从旧到新）
+pub fn query(log_dir: &Path, query: &LogQuery) -> Result<Vec<LogRecord>, String> {
+    let since = query
+        .since
+        .as_deref()
+        .map(|since| DateTime::parse_from_rfc3339(since).map_err(|e| format!("无效的起始时间 {}: {}", since, e)))
+        .transpose()?;
+    let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+
+    let matches = |record: &LogRecord| {
+        query.level.is_none_or(|level| record.level >= level)
+            && query.run_id.as_ref().is_none_or(|run_id| record.run_id.as_ref() == Some(run_id))
+            && since.is_none_or(|since| {
+                DateTime::parse_from_rfc3339(&record.ts).is_ok_and(|ts| ts >= since)
+            })
+    };
+    // 文件从新到旧读取，第一条记录已早于 since 时不再读更旧的文件
+    let records = read_files(log_dir, |records| {
+        since.is_some_and(|since| {
+            records
+                .first()
+                .and_then(|record| DateTime::parse_from_rfc3339(&record.ts).ok())
+                .is_some_and(|ts| ts < since)
+        })
+    });
+    let matched: Vec<LogRecord> = records.into_iter().filter(|record| matches(record)).collect();
+    let skip = matched.len().saturating_sub(limit);
+    Ok(matched.into_iter().skip(skip).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    /// 每个文件只放得下 per_file 条 message 长度相同的记录
+    fn small_sink(dir: &Path, per_file: u64) -> FileSink {
+        let line = serde_json::to_string(&new_record(Level::Info, "[测试] 第 00 条")).unwrap().len() as u64 + 1;
+        FileSink { max_bytes: line * per_file, ..FileSink::open(dir).unwrap() }
+    }
+
+    fn messages(records: &[LogRecord]) -> Vec<String> {
+        records.iter().map(|r| r.message.clone()).collect()
+    }
+
+    #[test]
+    fn target_is_split_from_message() {
+        assert_eq!(split_target("[邮件] 发送成功"), ("邮件".to_string(), "发送成功".to_string()));
+        assert_eq!(split_target("没有标签"), (String::new(), "没有标签".to_string()));
+        assert_eq!(split_target("[未闭合 标签"), (String::new(), "[未闭合 标签".to_string()));
+    }
+
+    #[test]
+    fn run_id_tags_records_on_the_current_thread_only() {
+        assert_eq!(new_record(Level::Info, "外面").run_id, None);
+        with_run_id("run-1", || {
+            assert_eq!(new_record(Level::Info, "执行中").run_id.as_deref(), Some("run-1"));
+            // 嵌套执行结束后恢复外层的 run_id
+            with_run_id("run-2", || assert_eq!(new_record(Level::Info, "嵌套").run_id.as_deref(), Some("run-2")));
+            assert_eq!(new_record(Level::Info, "执行中").run_id.as_deref(), Some("run-1"));
+            // 其他线程不受影响
+            let other = std::thread::spawn(|| new_record(Level::Info, "其他线程").run_id).join().unwrap();
+            assert_eq!(other, None);
+        });
+        assert_eq!(new_record(Level::Info, "外面").run_id, None);
+    }
+
+    #[test]
+    fn files_rotate_and_oldest_is_dropped() {
+        let dir = TempDir::new("logging");
+        let mut sink = small_sink(dir.path(), 2);
+        let total = 2 * (MAX_FILES + 2);
+        for i in 0..total {
+            sink.append(&new_record(Level::Info, &format!("[测试] 第 {:02} 条", i))).unwrap();
+        }
+
+        for index in 0..=MAX_FILES {
+            assert!(file_path(dir.path(), index).exists(), "缺少第 {} 个日志文件", index);
+        }
+        assert!(!file_path(dir.path(), MAX_FILES + 1).exists());
+        // 当前文件是最新的两条，最旧的两条随被删除的文件丢弃
+        assert_eq!(messages(&read_file(&file_path(dir.path(), 0))), ["第 12 条", "第 13 条"]);
+        assert_eq!(messages(&read_file(&file_path(dir.path(), MAX_FILES))), ["第 02 条", "第 03 条"]);
+
+        // tail 跨文件按从旧到新返回
+        assert_eq!(messages(&tail(dir.path(), 3)), ["第 11 条", "第 12 条", "第 13 条"]);
+        assert_eq!(tail(dir.path(), 100).len(), 2 * (MAX_FILES + 1));
+    }
+
+    #[test]
+    fn reopened_sink_continues_current_file() {
+        let dir = TempDir::new("logging");
+        small_sink(dir.path(), 2).append(&new_record(Level::Info, "[测试] 第 00 条")).unwrap();
+        let mut sink = small_sink(dir.path(), 2);
+        sink.append(&new_record(Level::Info, "[测试] 第 01 条")).unwrap();
+        assert!(!file_path(dir.path(), 1).exists());
+        sink.append(&new_record(Level::Info, "[测试] 第 02 条")).unwrap();
+        assert_eq!(messages(&read_file(&file_path(dir.path(), 1))), ["第 00 条", "第 01 条"]);
+    }
+
+    #[test]
+    fn query_filters_by_level_run_id_since_and_limit() {
+        let dir = TempDir::new("logging");
+        let mut sink = FileSink::open(dir.path()).unwrap();
+        let mut old = new_record(Level::Error, "[测试] 很早的错误");
+        old.ts = "2020-01-01T00:00:00.000+08:00".to_string();
+        sink.append(&old).unwrap();
+        sink.append(&new_record(Level::Info, "[测试] 普通")).unwrap();
+        with_run_id("run-1", || {
+            sink.append(&new_record(Level::Warn, "[测试] 警告")).unwrap();
+            sink.append(&new_record(Level::Error, "[测试] 错误")).unwrap();
+        });
+        // 无法解析的行跳过
+        writeln!(sink.file, "not json").unwrap();
+
+        let run = |q: LogQuery| messages(&query(dir.path(), &q).unwrap());
+        assert_eq!(run(LogQuery::default()).len(), 4);
+        assert_eq!(run(LogQuery { level: Some(Level::Warn), ..Default::default() }), ["很早的错误", "警告", "错误"]);
+        assert_eq!(run(LogQuery { run_id: Some("run-1".to_string()), ..Default::default() }), ["警告", "错误"]);
+        assert_eq!(
+            run(LogQuery { since: Some("2024-01-01T00:00:00+08:00".to_string()), ..Default::default() }),
+            ["普通", "警告", "错误"]
+        );
+        assert_eq!(run(LogQuery { limit: Some(1), ..Default::default() }), ["错误"]);
+        assert!(query(dir.path(), &LogQuery { since: Some("昨天".to_string()), ..Default::default() }).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+// ============================================================================
+// 邮件发送（SMTP）
+// ============================================================================
+//
+// 桌面应用的 send_email 命令、定时任务和命令行工具共用这里的发送逻辑：
+//   - SMTP 必须配置用户名和授权码，不依赖系统邮件客户端
+//   - QQ 邮箱和 587 端口使用 STARTTLS，其他端口使用 SSL
+//   - 配置了代理时通过本地隧道连接 SMTP 服务器（network.rs）
+// 连接方式由 route 决定，实际连接和发送交给 MailTransport（AppCore 中默认为 SmtpMailTransport），
+// 测试中可以换成连接本地 SMTP 服务器的实现。
+// 发送失败按 lettre 的错误分为认证失败、临时失败、被拒收、TLS 失败和连接失败，附带处理建议
+
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{Message, SmtpTransport, Transport};
+
+use crate::error::{AppError, AppResult, ErrorKind};
+use crate::i18n::{self, Locale};
+use crate::network::{self, NetworkSettings};
+use crate::settings::SmtpConfig;
+
+/// 一封待发送的邮件
+pub struct Email<'a> {
+    /// 发件人名称
+    pub from_name: &'a str,
+    pub to: &'a str,
+    pub subject: &'a str,
+    pub body: &'a str,
+}
+
+/// SMTP 连接的加密方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// 先明文连接再用 STARTTLS 升级
+    StartTls,
+    /// 连接建立后直接 TLS（SSL）
+    Wrapper,
+}
+
+/// 按 SMTP 配置和网络设置决定的连接方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmtpRoute {
+    pub server: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    /// 经代理隧道连接
+    pub via_proxy: bool,
+}
+
+/// QQ 邮箱和 587 端口使用 STARTTLS，其他端口使用 SSL；服务器需要走代理时经隧道连接
+pub fn route(smtp_config: &SmtpConfig, network_settings: &NetworkSettings) -> SmtpRoute {
+    let security = if smtp_config.server.contains("qq.com") || smtp_config.port == 587 {
+        SmtpSecurity::StartTls
+    } else {
+        SmtpSecurity::Wrapper
+    };
+    SmtpRoute {
+        server: smtp_config.server.clone(),
+        port: smtp_config.port,
+        security,
+        via_proxy: network_settings.proxy_for_host(&smtp_config.server),
+    }
+}
+
+/// 发件人地址：用户名不是完整邮箱时按 QQ 号补全
+pub fn from_address(sender_name: &str, username: &str) -> String {
+    if username.contains('@') {
+        format!("{} <{}>", sender_name, username)
+    } else {
+        format!("{} <{}@qq.com>", sender_name, username)
+    }
+}
+
+/// 按连接方式连接 SMTP 服务器并发送邮件
+pub trait MailTransport: Send + Sync {
+    fn send(
+        &self,
+        route: &SmtpRoute,
+        network_settings: &NetworkSettings,
+        credentials: Credentials,
+        message: &Message,
+    ) -> Result<(), lettre::transport::smtp::Error>;
+}
+
+/// 通过 lettre 连接真实的 SMTP 服务器
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SmtpMailTransport;
+
+impl MailTransport for SmtpMailTransport {
+    fn send(
+        &self,
+        route: &SmtpRoute,
+        network_settings: &NetworkSettings,
+        credentials: Credentials,
+        message: &Message,
+    ) -> Result<(), lettre::transport::smtp::Error> {
+        let smtp_server = &route.server;
+        let use_starttls = route.security == SmtpSecurity::StartTls;
+
+        // 配置了代理时，通过本地隧道经代理连接 SMTP 服务器（TLS 仍按真实服务器域名校验）
+        let tunnel = if route.via_proxy {
+            match network::LocalTunnel::start(network_settings, smtp_server, route.port) {
+                Ok(tunnel) => Some(tunnel),
+                Err(e) => {
+                    log_warn!("[邮件] ⚠️ {}，直接连接 SMTP 服务器", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // 对于QQ邮箱，使用STARTTLS
+        let mailer = if let Some(tunnel) = &tunnel {
+            let tls_parameters = TlsParameters::new(smtp_server.clone())?;
+            let tls = if use_starttls {
+                Tls::Required(tls_parameters)
+            } else {
+                Tls::Wrapper(tls_parameters)
+            };
+            SmtpTransport::builder_dangerous("127.0.0.1")
+                .port(tunnel.local_port)
+                .tls(tls)
+                .credentials(credentials)
+                .build()
+        } else if use_starttls {
+            SmtpTransport::starttls_relay(smtp_server)?
+                .port(route.port)
+                .credentials(credentials)
+                .build()
+        } else {
+            SmtpTransport::relay(smtp_server)?
+                .port(route.port)
+                .credentials(credentials)
+                .build()
+        };
+
+        mailer.send(message).map(|_| ())
+    }
+}
+
+/// 把 lettre 的发送错误分为认证失败、连接失败和其他错误
+fn smtp_send_error(error: lettre::transport::smtp::Error, locale: Locale) -> AppError {
+    let code = error.status().map(|code| code.to_string()).unwrap_or_default();
+    let detail = error.to_string();
+    let text = |key: &str| i18n::text(locale, key);
+    // 535 / 534 / 530：用户名或授权码错误、需要认证
+    if ["535", "534", "530"].contains(&code.as_str()) || detail.to_lowercase().contains("authentication") {
+        AppError::new(ErrorKind::Smtp, "smtp.auth_failed", text("smtp.auth_failed"))
+            .detail(detail)
+            .hint(text("smtp.auth_failed.hint_account"))
+            .hint(text("smtp.auth_failed.hint_password"))
+            .hint(text("smtp.auth_failed.hint_service"))
+    } else if error.is_transient() {
+        AppError::new(ErrorKind::Smtp, "smtp.temporary_failure", text("smtp.temporary_failure"))
+            .detail(detail)
+            .hint(text("smtp.temporary_failure.hint"))
+            .retryable()
+    } else if error.is_permanent() {
+        AppError::new(ErrorKind::Smtp, "smtp.rejected", text("smtp.rejected"))
+            .detail(detail)
+            .hint(text("smtp.rejected.hint"))
+    } else if error.is_tls() {
+        AppError::new(ErrorKind::Smtp, "smtp.tls_failed", text("smtp.tls_failed"))
+            .detail(detail)
+            .hint(text("smtp.tls_failed.hint"))
+    } else {
+        AppError::new(ErrorKind::Network, "smtp.connection_failed", text("smtp.connection_failed"))
+            .detail(detail)
+            .hint(text("smtp.connection_failed.hint_server"))
+            .hint(text("smtp.connection_failed.hint_network"))
+            .retryable()
+    }
+}
+
+/// 按 SMTP 配置和网络设置发送一封纯文本邮件，成功时返回给用户看的提示
+pub fn send(
+    transport: &dyn MailTransport,
+    smtp_config: &SmtpConfig,
+    network_settings: &NetworkSettings,
+    locale: Locale,
+    email: &Email,
+) -> AppResult<String> {
+    let text = |key: &str| i18n::text(locale, key);
+    let smtp_username = &smtp_config.username;
+    let smtp_password = &smtp_config.password;
+    let to = email.to;
+
+    // 强制要求配置 SMTP，不依赖系统邮件客户端（系统邮件客户端不可靠）
+    if smtp_username.is_empty() || smtp_password.is_empty() {
+        log_error!("[邮件] ❌ SMTP 未配置");
+        return Err(AppError::new(ErrorKind::Config, "smtp.not_configured", text("smtp.not_configured"))
+            .hint(text("smtp.not_configured.hint_fill"))
+            .hint(text("smtp.not_configured.hint_password"))
+            .hint(text("smtp.not_configured.hint_save")));
+    }
+
+    // 使用 SMTP 发送邮件
+    let from_address = from_address(email.from_name, smtp_username);
+    let recipient_error = |key: &str| AppError::invalid_input("smtp.invalid_recipient", i18n::format(locale, key, &[("to", &to)]));
+
+    // 验证邮箱地址格式
+    if !to.contains('@') || !to.contains('.') {
+        return Err(recipient_error("smtp.invalid_recipient.format").hint(text("smtp.invalid_recipient.format_hint")));
+    }
+
+    if to.contains("@gamil.com") {
+        return Err(recipient_error("smtp.invalid_recipient.typo").hint(text("smtp.invalid_recipient.typo_hint")));
+    }
+
+    let message = Message::builder()
+        .from(from_address.parse().map_err(|e: lettre::address::AddressError| {
+            AppError::new(ErrorKind::Config, "smtp.invalid_sender", text("smtp.invalid_sender"))
+                .detail(e.to_string())
+                .hint(text("smtp.invalid_sender.hint"))
+        })?)
+        .to(to.parse().map_err(|e: lettre::address::AddressError| {
+            recipient_error("smtp.invalid_recipient").detail(e.to_string())
+        })?)
+        .subject(email.subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(email.body.to_string())
+        .map_err(|e| AppError::from(format!("创建邮件失败: {}", e)))?;
+
+    let creds = Credentials::new(smtp_username.clone(), smtp_password.clone());
+
+    match transport.send(&route(smtp_config, network_settings), network_settings, creds, &message) {
+        Ok(()) => Ok(i18n::format(locale, "email.sent", &[("to", &to)])),
+        Err(e) => Err(smtp_send_error(e, locale)),
+    }
+}
@@ -0,0 +1,600 @@
+// ============================================================================
+// 网络设置（代理）
+// ============================================================================
+//
+// 代理设置保存在应用数据目录的 network_settings.json 中，用于：
+//   - 爬虫子进程：通过 HTTP_PROXY / HTTPS_PROXY / ALL_PROXY / NO_PROXY 环境变量注入
+//   - 进程内 HTTP 客户端：build_http_client
+//   - SMTP：HTTP CONNECT / SOCKS5 代理通过本地隧道转发（HTTPS 代理不支持，直接连接）
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::config_file;
+use crate::error::AppResult;
+
+pub const SETTINGS_FILE: &str = "network_settings.json";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 代理类型
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyKind {
+    #[default]
+    Http,
+    Https,
+    Socks5,
+}
+
+impl ProxyKind {
+    fn scheme(&self) -> &'static str {
+        match self {
+            ProxyKind::Http => "http",
+            ProxyKind::Https => "https",
+            // socks5h：由代理服务器解析域名
+            ProxyKind::Socks5 => "socks5h",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NetworkSettings {
+    #[serde(default)]
+    pub proxy_enabled: bool,
+    #[serde(default)]
+    pub proxy_kind: ProxyKind,
+    #[serde(default)]
+    pub proxy_host: String,
+    #[serde(default)]
+    pub proxy_port: u16,
+    #[serde(default)]
+    pub proxy_username: String,
+    #[serde(default)]
+    pub proxy_password: String,
+    /// 不走代理的主机，支持 `example.com`、`.example.com`、`*`
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    /// 界面提交时要求清除已保存的代理密码（只用于提交，不会写入设置文件）
+    #[serde(default, skip_serializing)]
+    pub clear_proxy_password: bool,
+}
+
+/// SOCKS5 用户名、密码和目标主机名都用一个字节表示长度
+const SOCKS5_MAX_FIELD_LEN: usize = 255;
+
+impl NetworkSettings {
+    /// 读取设置；文件不存在时使用默认值，文件损坏时返回可恢复的错误
+    pub fn load(app_data_dir: &Path) -> AppResult<Self> {
+        Ok(config_file::load_json(app_data_dir, SETTINGS_FILE)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, app_data_dir: &Path) -> Result<(), String> {
+        self.validate()?;
+        config_file::save_json(app_data_dir, SETTINGS_FILE, self)
+    }
+
+    /// 返回给界面的设置：不带已保存的代理密码
+    pub fn without_password(mut self) -> Self {
+        self.proxy_password.clear();
+        self
+    }
+
+    /// 界面拿不到已保存的密码：提交的密码为空、且代理地址和用户名都没变时沿用已保存的密码；
+    /// 勾选了 clear_proxy_password 时清除密码
+    pub fn keep_stored_password(&mut self, app_data_dir: &Path) -> AppResult<()> {
+        if std::mem::take(&mut self.clear_proxy_password) {
+            self.proxy_password.clear();
+            return Ok(());
+        }
+        if self.proxy_password.is_empty() {
+            let stored = Self::load(app_data_dir)?;
+            // 换了代理服务器或用户名时不能把旧密码发给新的服务器
+            if stored.proxy_host.trim() == self.proxy_host.trim() && stored.proxy_username == self.proxy_username {
+                self.proxy_password = stored.proxy_password;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.proxy_enabled {
+            return Ok(());
+        }
+        if self.proxy_host.trim().is_empty() {
+            return Err("请填写代理服务器地址".to_string());
+        }
+        if self.proxy_host.contains("://") || self.proxy_host.contains('/') {
+            return Err("代理服务器地址只需填写主机名或 IP，不要包含协议和路径".to_string());
+        }
+        if self.proxy_port == 0 {
+            return Err("请填写代理端口".to_string());
+        }
+        if self.proxy_kind == ProxyKind::Socks5
+            && (self.proxy_username.len() > SOCKS5_MAX_FIELD_LEN || self.proxy_password.len() > SOCKS5_MAX_FIELD_LEN)
+        {
+            return Err(format!("SOCKS5 代理的用户名和密码不能超过 {} 字节", SOCKS5_MAX_FIELD_LEN));
+        }
+        Ok(())
+    }
+
+    fn is_active(&self) -> bool {
+        self.proxy_enabled && !self.proxy_host.trim().is_empty() && self.proxy_port != 0
+    }
+
+    /// 代理 URL，用户名和密码会做百分号编码
+    pub fn proxy_url(&self) -> Option<String> {
+        if !self.is_active() {
+            return None;
+        }
+        let auth = if self.proxy_username.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "{}:{}@",
+                percent_encode(&self.proxy_username),
+                percent_encode(&self.proxy_password)
+            )
+        };
+        Some(format!(
+            "{}://{}{}:{}",
+            self.proxy_kind.scheme(),
+            auth,
+            self.proxy_host.trim(),
+            self.proxy_port
+        ))
+    }
+
+    /// 主机是否命中 no_proxy 列表
+    pub fn bypasses_proxy(&self, host: &str) -> bool {
+        let host = host.trim().to_ascii_lowercase();
+        self.no_proxy.iter().any(|entry| {
+            let entry = entry.trim().to_ascii_lowercase();
+            if entry.is_empty() {
+                return false;
+            }
+            if entry == "*" {
+                return true;
+            }
+            let domain = entry.trim_start_matches("*.").trim_start_matches('.');
+            host == domain || host.ends_with(&format!(".{}", domain))
+        })
+    }
+
+    /// 指定主机是否需要走代理
+    pub fn proxy_for_host(&self, host: &str) -> bool {
+        self.is_active() && !self.bypasses_proxy(host)
+    }
+
+    /// 注入到子进程的环境变量（大小写两种写法都设置，兼容 requests / Node）
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let url = match self.proxy_url() {
+            Some(url) => url,
+            None => return Vec::new(),
+        };
+        let no_proxy = self.no_proxy.join(",");
+        let mut vars = Vec::new();
+        for name in ["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY"] {
+            vars.push((name.to_string(), url.clone()));
+            vars.push((name.to_ascii_lowercase(), url.clone()));
+        }
+        if !no_proxy.is_empty() {
+            vars.push(("NO_PROXY".to_string(), no_proxy.clone()));
+            vars.push(("no_proxy".to_string(), no_proxy));
+        }
+        vars
+    }
+
+    pub fn apply_to_command(&self, command: &mut Command) {
+        command.envs(self.env_vars());
+    }
+
+    /// 构建使用当前代理设置的 HTTP 客户端
+    pub fn build_http_client(&self) -> Result<reqwest::blocking::Client, String> {
+        let mut builder = reqwest::blocking::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(Duration::from_secs(30));
+        if let Some(url) = self.proxy_url() {
+            let no_proxy = reqwest::NoProxy::from_string(&self.no_proxy.join(","));
+            let proxy = reqwest::Proxy::all(&url)
+                .map_err(|e| format!("代理地址无效: {}", e))?
+                .no_proxy(no_proxy);
+            builder = builder.proxy(proxy);
+        } else {
+            builder = builder.no_proxy();
+        }
+        builder.build().map_err(|e| format!("创建 HTTP 客户端失败: {}", e))
+    }
+
+    /// 通过代理建立到目标主机的 TCP 隧道（HTTP CONNECT 或 SOCKS5）
+    pub fn open_tunnel(&self, host: &str, port: u16) -> Result<TcpStream, String> {
+        let proxy_addr = (self.proxy_host.trim(), self.proxy_port)
+            .to_socket_addrs()
+            .map_err(|e| format!("解析代理地址失败: {}", e))?
+            .next()
+            .ok_or("解析代理地址失败")?;
+        let mut stream = TcpStream::connect_timeout(&proxy_addr, CONNECT_TIMEOUT)
+            .map_err(|e| format!("连接代理服务器失败: {}", e))?;
+        stream.set_read_timeout(Some(CONNECT_TIMEOUT)).ok();
+
+        match self.proxy_kind {
+            ProxyKind::Http => self.http_connect(&mut stream, host, port)?,
+            ProxyKind::Socks5 => self.socks5_connect(&mut stream, host, port)?,
+            ProxyKind::Https => {
+                return Err("HTTPS 代理不支持隧道转发 SMTP 连接".to_string());
+            }
+        }
+
+        stream.set_read_timeout(None).ok();
+        Ok(stream)
+    }
+
+    fn http_connect(&self, stream: &mut TcpStream, host: &str, port: u16) -> Result<(), String> {
+        let mut request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+        if !self.proxy_username.is_empty() {
+            let token = base64::engine::general_purpose::STANDARD
+                .encode(format!("{}:{}", self.proxy_username, self.proxy_password));
+            request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", token));
+        }
+        request.push_str("\r\n");
+        stream
+            .write_all(request.as_bytes())
+            .map_err(|e| format!("发送 CONNECT 请求失败: {}", e))?;
+
+        // 逐字节读取响应头，避免把隧道中的后续数据读进缓冲区
+        let mut head = Vec::new();
+        let mut byte = [0u8; 1];
+        while !head.ends_with(b"\r\n\r\n") {
+            if head.len() > 8192 {
+                return Err("代理响应头过长".to_string());
+            }
+            stream
+                .read_exact(&mut byte)
+                .map_err(|e| format!("读取代理响应失败: {}", e))?;
+            head.push(byte[0]);
+        }
+        let head = String::from_utf8_lossy(&head);
+        let status_line = head.lines().next().unwrap_or_default();
+        match status_line.split_whitespace().nth(1) {
+            Some("200") => Ok(()),
+            Some("407") => Err("代理认证失败，请检查代理用户名和密码".to_string()),
+            _ => Err(format!("代理拒绝连接: {}", status_line)),
+        }
+    }
+
+    fn socks5_connect(&self, stream: &mut TcpStream, host: &str, port: u16) -> Result<(), String> {
+        let io_err = |e: io::Error| format!("SOCKS5 握手失败: {}", e);
+        // 长度字段只有一个字节，超长时直接拒绝，不能截断后发出错误的报文
+        if host.len() > SOCKS5_MAX_FIELD_LEN {
+            return Err(format!("SOCKS5 目标主机名不能超过 {} 字节", SOCKS5_MAX_FIELD_LEN));
+        }
+        if self.proxy_username.len() > SOCKS5_MAX_FIELD_LEN || self.proxy_password.len() > SOCKS5_MAX_FIELD_LEN {
+            return Err(format!("SOCKS5 代理的用户名和密码不能超过 {} 字节", SOCKS5_MAX_FIELD_LEN));
+        }
+        let use_auth = !self.proxy_username.is_empty();
+
+        let greeting: &[u8] = if use_auth { &[0x05, 0x01, 0x02] } else { &[0x05, 0x01, 0x00] };
+        stream.write_all(greeting).map_err(io_err)?;
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply).map_err(io_err)?;
+        match reply {
+            [0x05, 0x00] => {}
+            [0x05, 0x02] if use_auth => {
+                let mut auth = vec![0x01, self.proxy_username.len() as u8];
+                auth.extend_from_slice(self.proxy_username.as_bytes());
+                auth.push(self.proxy_password.len() as u8);
+                auth.extend_from_slice(self.proxy_password.as_bytes());
+                stream.write_all(&auth).map_err(io_err)?;
+                let mut auth_reply = [0u8; 2];
+                stream.read_exact(&mut auth_reply).map_err(io_err)?;
+                if auth_reply[1] != 0x00 {
+                    return Err("SOCKS5 代理认证失败，请检查代理用户名和密码".to_string());
+                }
+            }
+            _ => return Err("SOCKS5 代理不接受当前的认证方式".to_string()),
+        }
+
+        let mut connect = vec![0x05, 0x01, 0x00, 0x03, host.len() as u8];
+        connect.extend_from_slice(host.as_bytes());
+        connect.extend_from_slice(&port.to_be_bytes());
+        stream.write_all(&connect).map_err(io_err)?;
+
+        let mut head = [0u8; 4];
+        stream.read_exact(&mut head).map_err(io_err)?;
+        if head[1] != 0x00 {
+            return Err(format!("SOCKS5 代理拒绝连接（错误码 {}）", head[1]));
+        }
+        // 读掉绑定地址
+        let addr_len = match head[3] {
+            0x01 => 4,
+            0x04 => 16,
+            0x03 => {
+                let mut len = [0u8; 1];
+                stream.read_exact(&mut len).map_err(io_err)?;
+                len[0] as usize
+            }
+            _ => return Err("SOCKS5 响应格式错误".to_string()),
+        };
+        let mut rest = vec![0u8; addr_len + 2];
+        stream.read_exact(&mut rest).map_err(io_err)?;
+        Ok(())
+    }
+}
+
+/// 本地转发隧道：lettre 无法直接使用代理，因此在 127.0.0.1 上监听，
+/// 把每个连接通过代理转发到目标 SMTP 服务器（TLS 仍由 lettre 按真实域名校验）
+pub struct LocalTunnel {
+    pub local_port: u16,
+    stop: Arc<AtomicBool>,
+}
+
+impl LocalTunnel {
+    pub fn start(settings: &NetworkSettings, host: &str, port: u16) -> Result<Self, String> {
+        if settings.proxy_kind == ProxyKind::Https {
+            return Err("HTTPS 代理不支持隧道转发 SMTP 连接".to_string());
+        }
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .map_err(|e| format!("创建本地隧道失败: {}", e))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("创建本地隧道失败: {}", e))?;
+        let local_port = listener
+            .local_addr()
+            .map_err(|e| format!("创建本地隧道失败: {}", e))?
+            .port();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let settings = settings.clone();
+        let host = host.to_string();
+        std::thread::spawn(move || {
+            while !stop_flag.load(Ordering::SeqCst) {
+                match listener.accept() {
+                    Ok((client, _)) => {
+                        let _ = client.set_nonblocking(false);
+                        match settings.open_tunnel(&host, port) {
+                            Ok(upstream) => pipe(client, upstream),
+                            Err(e) => {
+                                log_warn!("[代理] 建立 SMTP 隧道失败: {}", e);
+                                let _ = client.shutdown(Shutdown::Both);
+                            }
+                        }
+                    }
+                    Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(Duration::from_millis(50));
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+
+        Ok(LocalTunnel { local_port, stop })
+    }
+}
+
+impl Drop for LocalTunnel {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+/// 双向转发两个 TCP 连接
+fn pipe(client: TcpStream, upstream: TcpStream) {
+    let (mut client_read, mut upstream_write) = match (client.try_clone(), upstream.try_clone()) {
+        (Ok(c), Ok(u)) => (c, u),
+        _ => return,
+    };
+    let (mut upstream_read, mut client_write) = (upstream, client);
+    std::thread::spawn(move || {
+        let _ = io::copy(&mut client_read, &mut upstream_write);
+        let _ = upstream_write.shutdown(Shutdown::Write);
+    });
+    std::thread::spawn(move || {
+        let _ = io::copy(&mut upstream_read, &mut client_write);
+        let _ = client_write.shutdown(Shutdown::Write);
+    });
+}
+
+fn percent_encode(input: &str) -> String {
+    input
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// 单项连通性检测结果
+#[derive(Serialize, Debug, Clone)]
+pub struct ProbeResult {
+    pub target: String,
+    pub ok: bool,
+    pub via_proxy: bool,
+    pub elapsed_ms: u128,
+    pub detail: String,
+}
+
+/// 检测 HTTP(S) 连通性
+pub fn probe_http(settings: &NetworkSettings, url: &str) -> ProbeResult {
+    let started = Instant::now();
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default();
+    let via_proxy = settings.proxy_for_host(&host);
+    let result = settings
+        .build_http_client()
+        .and_then(|client| client.get(url).send().map_err(|e| format!("请求失败: {}", e)));
+    let (ok, detail) = match result {
+        Ok(response) => (
+            response.status().is_success() || response.status().is_redirection(),
+            format!("HTTP {}", response.status()),
+        ),
+        Err(e) => (false, e),
+    };
+    ProbeResult {
+        target: url.to_string(),
+        ok,
+        via_proxy,
+        elapsed_ms: started.elapsed().as_millis(),
+        detail,
+    }
+}
+
+/// 检测 SMTP 服务器 TCP 连通性（走代理时通过隧道连接，并读取服务器问候语）
+pub fn probe_smtp(settings: &NetworkSettings, host: &str, port: u16) -> ProbeResult {
+    let started = Instant::now();
+    let via_proxy = settings.proxy_for_host(host) && settings.proxy_kind != ProxyKind::Https;
+    let stream = if via_proxy {
+        settings.open_tunnel(host, port)
+    } else {
+        (host, port)
+            .to_socket_addrs()
+            .map_err(|e| format!("解析 SMTP 服务器地址失败: {}", e))
+            .and_then(|mut addrs| addrs.next().ok_or_else(|| "解析 SMTP 服务器地址失败".to_string()))
+            .and_then(|addr| {
+                TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+                    .map_err(|e| format!("连接 SMTP 服务器失败: {}", e))
+            })
+    };
+    let (ok, detail) = match stream {
+        Ok(stream) => {
+            stream.set_read_timeout(Some(CONNECT_TIMEOUT)).ok();
+            let mut greeting = String::new();
+            match BufReader::new(stream).read_line(&mut greeting) {
+                // 465 端口为隐式 TLS，不会返回明文问候语，能建立连接即可
+                Ok(_) if greeting.starts_with("220") => (true, greeting.trim().to_string()),
+                Ok(_) | Err(_) if port == 465 => (true, "已建立 TCP 连接（隐式 TLS）".to_string()),
+                Ok(_) => (false, format!("SMTP 服务器返回异常: {}", greeting.trim())),
+                Err(e) => (false, format!("读取 SMTP 问候语失败: {}", e)),
+            }
+        }
+        Err(e) => (false, e),
+    };
+    ProbeResult {
+        target: format!("{}:{}", host, port),
+        ok,
+        via_proxy,
+        elapsed_ms: started.elapsed().as_millis(),
+        detail,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn stored(dir: &Path) -> NetworkSettings {
+        let settings = NetworkSettings {
+            proxy_enabled: true,
+            proxy_host: "127.0.0.1".to_string(),
+            proxy_port: 7890,
+            proxy_username: "user".to_string(),
+            proxy_password: "secret".to_string(),
+            ..Default::default()
+        };
+        settings.save(dir).unwrap();
+        settings
+    }
+
+    #[test]
+    fn stored_proxy_password_is_hidden_and_kept() {
+        let dir = TempDir::new("network");
+        stored(dir.path());
+
+        // 界面读到的设置不带密码，原样提交时沿用已保存的密码
+        let mut submitted = NetworkSettings::load(dir.path()).unwrap().without_password();
+        assert_eq!(submitted.proxy_password, "");
+        submitted.proxy_port = 7891;
+        submitted.keep_stored_password(dir.path()).unwrap();
+        assert_eq!(submitted.proxy_password, "secret");
+
+        // 填写了新密码时使用新密码
+        submitted.proxy_password = "changed".to_string();
+        submitted.keep_stored_password(dir.path()).unwrap();
+        assert_eq!(submitted.proxy_password, "changed");
+    }
+
+    #[test]
+    fn stored_password_is_not_sent_to_another_proxy_or_user() {
+        let dir = TempDir::new("network");
+        stored(dir.path());
+
+        let mut other_host = NetworkSettings::load(dir.path()).unwrap().without_password();
+        other_host.proxy_host = "proxy.example.com".to_string();
+        other_host.keep_stored_password(dir.path()).unwrap();
+        assert_eq!(other_host.proxy_password, "");
+
+        let mut other_user = NetworkSettings::load(dir.path()).unwrap().without_password();
+        other_user.proxy_username = "admin".to_string();
+        other_user.keep_stored_password(dir.path()).unwrap();
+        assert_eq!(other_user.proxy_password, "");
+    }
+
+    #[test]
+    fn clear_flag_removes_the_password_and_is_not_saved() {
+        let dir = TempDir::new("network");
+        stored(dir.path());
+
+        let mut submitted = NetworkSettings::load(dir.path()).unwrap().without_password();
+        submitted.clear_proxy_password = true;
+        submitted.keep_stored_password(dir.path()).unwrap();
+        assert_eq!(submitted.proxy_password, "");
+        assert!(!submitted.clear_proxy_password);
+        submitted.save(dir.path()).unwrap();
+
+        let raw = std::fs::read_to_string(dir.path().join(SETTINGS_FILE)).unwrap();
+        assert!(!raw.contains("clear_proxy_password"), "{}", raw);
+        assert_eq!(NetworkSettings::load(dir.path()).unwrap().proxy_password, "");
+
+        // 清除之后再提交空密码，保持为空
+        let mut again = NetworkSettings::load(dir.path()).unwrap();
+        again.keep_stored_password(dir.path()).unwrap();
+        assert_eq!(again.proxy_password, "");
+    }
+
+    #[test]
+    fn socks5_credentials_longer_than_255_bytes_are_rejected() {
+        let socks = |username: String, password: String| NetworkSettings {
+            proxy_enabled: true,
+            proxy_kind: ProxyKind::Socks5,
+            proxy_host: "127.0.0.1".to_string(),
+            proxy_port: 1080,
+            proxy_username: username,
+            proxy_password: password,
+            ..Default::default()
+        };
+        // 按字节计：85 个汉字 255 字节，86 个汉字 258 字节
+        assert!(socks("user".to_string(), "密".repeat(85)).validate().is_ok());
+        assert!(socks("user".to_string(), "密".repeat(86)).validate().is_err());
+        assert!(socks("u".repeat(256), String::new()).validate().is_err());
+
+        // HTTP 代理的认证信息不受此限制
+        let http = NetworkSettings { proxy_kind: ProxyKind::Http, ..socks("u".repeat(300), "p".repeat(300)) };
+        assert!(http.validate().is_ok());
+    }
+
+    #[test]
+    fn socks5_tunnel_rejects_long_host_before_sending() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let settings = NetworkSettings {
+            proxy_enabled: true,
+            proxy_kind: ProxyKind::Socks5,
+            proxy_host: "127.0.0.1".to_string(),
+            proxy_port: listener.local_addr().unwrap().port(),
+            ..Default::default()
+        };
+        let host = format!("{}.example.com", "a".repeat(250));
+        let error = settings.open_tunnel(&host, 25).unwrap_err();
+        assert!(error.contains("255"), "{}", error);
+    }
+}
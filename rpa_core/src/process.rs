@@ -0,0 +1,98 @@
+// ============================================================================
+// 外部进程
+// ============================================================================
+//
+// 一次性执行的外部程序（打包的 spider_bin、外部插件、自动化脚本）都通过 AppCore 的 ProcessRunner 启动：
+// 调用方描述要执行的命令（ProcessSpec），拿到退出状态和输出（ProcessOutput），
+// 测试中可以换成不启动真实进程的实现。
+// 常驻的 Python worker（spider_worker.rs）需要持续读写管道，不经过这里
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// 要执行的命令
+#[derive(Debug, Clone, Default)]
+pub struct ProcessSpec {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    /// 额外的环境变量（如代理）
+    pub env: Vec<(String, String)>,
+    pub cwd: Option<PathBuf>,
+    /// 写入 stdin 的内容，写完后关闭 stdin
+    pub stdin: Option<Vec<u8>>,
+}
+
+impl ProcessSpec {
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        ProcessSpec {
+            program: program.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn envs(mut self, env: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.env.extend(env);
+        self
+    }
+
+    pub fn cwd(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cwd = Some(dir.into());
+        self
+    }
+
+    pub fn stdin(mut self, input: impl Into<Vec<u8>>) -> Self {
+        self.stdin = Some(input.into());
+        self
+    }
+}
+
+/// 进程结束后的结果
+#[derive(Debug, Clone, Default)]
+pub struct ProcessOutput {
+    /// 退出码为 0
+    pub success: bool,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+pub trait ProcessRunner: Send + Sync {
+    /// 启动进程并等待结束；无法启动（程序不存在、没有权限）时返回 Err
+    fn run(&self, spec: &ProcessSpec) -> io::Result<ProcessOutput>;
+}
+
+/// 启动真实的子进程
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemProcessRunner;
+
+impl ProcessRunner for SystemProcessRunner {
+    fn run(&self, spec: &ProcessSpec) -> io::Result<ProcessOutput> {
+        let mut command = Command::new(&spec.program);
+        command
+            .args(&spec.args)
+            .envs(spec.env.iter().map(|(k, v)| (k, v)))
+            .stdin(if spec.stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(dir) = &spec.cwd {
+            command.current_dir(dir);
+        }
+
+        let mut child = command.spawn()?;
+        if let (Some(input), Some(mut stdin)) = (&spec.stdin, child.stdin.take()) {
+            stdin.write_all(input)?;
+            // stdin 在这里关闭，子进程读到 EOF
+        }
+        let output = child.wait_with_output()?;
+        Ok(ProcessOutput {
+            success: output.status.success(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    }
+}
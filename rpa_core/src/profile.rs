@@ -0,0 +1,584 @@
+// ============================================================================
+// 配置档案导入导出
+// ============================================================================
+//
+// 把本机配置打包成一个 zip 文件，在新同事的电脑上一次导入：
+//   profile.json            档案清单（格式版本、导出时间、包含的条目）
+//   spider_config.json      爬虫配置、定时任务和推送内容
+//   crawl_policy.json       限速规则
+//   network_settings.json   网络与代理设置（不含代理密码）
+//   smtp_config.json        SMTP 服务器、端口和用户名（不含密码）
+//   credentials.json        SMTP 密码和代理密码，用导出口令加密（PBKDF2-SHA256 + AES-256-GCM）
+//   plugins/<id>/...        用户爬虫插件（规则集）
+// 导出时没有设置口令就不导出任何密码；导入时档案里没有的密码保留本机原值。
+// 导入只新增或覆盖文件，不会删除本机已有的插件
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine;
+use chrono::Local;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::config_file;
+use crate::crawl_policy::{self, CrawlPolicy};
+use crate::network::{self, NetworkSettings};
+use crate::settings::{self, SmtpConfig};
+
+pub const PROFILE_EXTENSION: &str = "zip";
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST_ENTRY: &str = "profile.json";
+const CREDENTIALS_ENTRY: &str = "credentials.json";
+const PLUGINS_DIR: &str = "plugins";
+const PBKDF2_ROUNDS: u32 = 200_000;
+/// 档案中单个文件的大小上限
+const MAX_ENTRY_BYTES: u64 = 20 * 1024 * 1024;
+
+/// 档案中的文件：(档案内路径, 内容)
+type ProfileFiles = Vec<(String, Vec<u8>)>;
+
+/// 档案清单
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Manifest {
+    format_version: u32,
+    created_at: String,
+    app_version: String,
+    entries: Vec<String>,
+    has_credentials: bool,
+}
+
+/// 需要口令保护的凭据
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Credentials {
+    smtp_password: String,
+    proxy_password: String,
+}
+
+/// credentials.json 的内容
+#[derive(Serialize, Deserialize, Debug)]
+struct EncryptedCredentials {
+    kdf: String,
+    rounds: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// 导出结果
+#[derive(Serialize, Debug, Clone)]
+pub struct ProfileExportSummary {
+    pub path: PathBuf,
+    pub entries: Vec<String>,
+    pub includes_credentials: bool,
+}
+
+/// 导入时对本机文件的影响
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportAction {
+    /// 本机没有，新建
+    Create,
+    /// 本机已有且内容不同，覆盖
+    Overwrite,
+    /// 内容相同，跳过
+    Unchanged,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ImportEntry {
+    /// 相对应用数据目录的路径
+    pub path: String,
+    pub label: String,
+    pub action: ImportAction,
+}
+
+/// 导入结果；dry_run 为 true 时只是预览，没有写入任何文件
+#[derive(Serialize, Debug, Clone)]
+pub struct ImportReport {
+    pub path: PathBuf,
+    pub created_at: String,
+    pub entries: Vec<ImportEntry>,
+    /// 档案中是否带有加密的凭据
+    pub has_credentials: bool,
+    /// 凭据是否随本次导入写入（没有提供口令时保留本机原有密码）
+    pub credentials_imported: bool,
+    pub dry_run: bool,
+}
+
+/// 档案中的配置文件及其说明
+fn config_files() -> [(&'static str, &'static str); 4] {
+    [
+        (settings::SETTINGS_FILE, "爬虫配置与定时任务"),
+        (crawl_policy::SETTINGS_FILE, "限速规则"),
+        (network::SETTINGS_FILE, "网络与代理设置"),
+        (settings::SMTP_FILE, "SMTP 设置"),
+    ]
+}
+
+fn label_of(path: &str) -> String {
+    if let Some((_, label)) = config_files().iter().find(|(file, _)| *file == path) {
+        return label.to_string();
+    }
+    let plugin = path
+        .strip_prefix("plugins/")
+        .and_then(|rest| rest.split('/').next())
+        .unwrap_or(path);
+    format!("爬虫插件 {}", plugin)
+}
+
+fn zip_error(e: zip::result::ZipError) -> String {
+    format!("读写档案失败: {}", e)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| format!("序列化失败: {}", e))
+}
+
+fn parse_json<T: DeserializeOwned>(name: &str, bytes: &[u8]) -> Result<T, String> {
+    serde_json::from_slice(bytes).map_err(|e| format!("{} 格式不正确: {}", name, e))
+}
+
+/// 读取本机配置文件，不存在时返回 None
+fn read_local<T: DeserializeOwned>(app_data_dir: &Path, name: &str) -> Result<Option<T>, String> {
+    Ok(config_file::load_json(app_data_dir, name)?)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], rounds: u32) -> Key<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, rounds, &mut key);
+    key.into()
+}
+
+fn encrypt_credentials(credentials: &Credentials, passphrase: &str) -> Result<EncryptedCredentials, String> {
+    let base64 = base64::engine::general_purpose::STANDARD;
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt, PBKDF2_ROUNDS));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let plaintext = to_json(credentials)?;
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| "加密凭据失败".to_string())?;
+    Ok(EncryptedCredentials {
+        kdf: "pbkdf2-sha256".to_string(),
+        rounds: PBKDF2_ROUNDS,
+        salt: base64.encode(salt),
+        nonce: base64.encode(nonce),
+        ciphertext: base64.encode(ciphertext),
+    })
+}
+
+fn decrypt_credentials(encrypted: &EncryptedCredentials, passphrase: &str) -> Result<Credentials, String> {
+    let base64 = base64::engine::general_purpose::STANDARD;
+    if encrypted.kdf != "pbkdf2-sha256" {
+        return Err(format!("不支持的密钥派生算法: {}", encrypted.kdf));
+    }
+    // 轮数来自档案，不接受其他值：过大时派生密钥会长时间占满 CPU
+    if encrypted.rounds != PBKDF2_ROUNDS {
+        return Err(format!("不支持的密钥派生轮数: {}", encrypted.rounds));
+    }
+    let decode = |value: &str| base64.decode(value).map_err(|_| "凭据数据已损坏".to_string());
+    let salt = decode(&encrypted.salt)?;
+    let nonce = decode(&encrypted.nonce)?;
+    let ciphertext = decode(&encrypted.ciphertext)?;
+    if nonce.len() != 12 {
+        return Err("凭据数据已损坏".to_string());
+    }
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt, encrypted.rounds));
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| "口令错误或档案已损坏，无法解密凭据".to_string())?;
+    parse_json(CREDENTIALS_ENTRY, &plaintext)
+}
+
+/// 递归列出用户插件目录下的文件，返回 (档案内路径, 本机路径)
+fn plugin_files(app_data_dir: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let mut files = Vec::new();
+    let mut pending = vec![app_data_dir.join(PLUGINS_DIR)];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("读取插件目录失败: {}", e)),
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(app_data_dir) {
+                let name = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push((name, path));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// 导出配置档案；passphrase 为空时不导出密码
+pub fn export_profile(
+    app_data_dir: &Path,
+    path: &Path,
+    passphrase: Option<&str>,
+) -> Result<ProfileExportSummary, String> {
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    let mut files: ProfileFiles = Vec::new();
+    let mut credentials = Credentials::default();
+
+    if let Some(spider) = settings::load(app_data_dir)? {
+        files.push((settings::SETTINGS_FILE.to_string(), to_json(&spider)?));
+    }
+    if let Some(policy) = read_local::<CrawlPolicy>(app_data_dir, crawl_policy::SETTINGS_FILE)? {
+        files.push((crawl_policy::SETTINGS_FILE.to_string(), to_json(&policy)?));
+    }
+    if let Some(mut network) = read_local::<NetworkSettings>(app_data_dir, network::SETTINGS_FILE)? {
+        credentials.proxy_password = std::mem::take(&mut network.proxy_password);
+        files.push((network::SETTINGS_FILE.to_string(), to_json(&network)?));
+    }
+    if let Some(mut smtp) = read_local::<SmtpConfig>(app_data_dir, settings::SMTP_FILE)? {
+        credentials.smtp_password = std::mem::take(&mut smtp.password);
+        files.push((settings::SMTP_FILE.to_string(), to_json(&smtp)?));
+    }
+    for (name, local) in plugin_files(app_data_dir)? {
+        let bytes = fs::read(&local).map_err(|e| format!("读取 {} 失败: {}", name, e))?;
+        if bytes.len() as u64 > MAX_ENTRY_BYTES {
+            return Err(format!("{} 超过 {} MB，无法导出", name, MAX_ENTRY_BYTES / 1024 / 1024));
+        }
+        files.push((name, bytes));
+    }
+    if files.is_empty() {
+        return Err("本机还没有任何配置，无需导出".to_string());
+    }
+
+    let entries: Vec<String> = files.iter().map(|(name, _)| name.clone()).collect();
+    if let Some(passphrase) = passphrase {
+        files.push((CREDENTIALS_ENTRY.to_string(), to_json(&encrypt_credentials(&credentials, passphrase)?)?));
+    }
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        entries: entries.clone(),
+        has_credentials: passphrase.is_some(),
+    };
+
+    let result = write_archive(path, &manifest, &files);
+    if result.is_err() {
+        // 不留下写了一半的文件
+        let _ = fs::remove_file(path);
+    }
+    result?;
+    Ok(ProfileExportSummary {
+        path: path.to_path_buf(),
+        entries,
+        includes_credentials: manifest.has_credentials,
+    })
+}
+
+fn write_archive(path: &Path, manifest: &Manifest, files: &ProfileFiles) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("创建档案文件失败: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(MANIFEST_ENTRY, options).map_err(zip_error)?;
+    zip.write_all(&to_json(manifest)?).map_err(|e| format!("写入档案失败: {}", e))?;
+    for (name, bytes) in files {
+        zip.start_file(name.as_str(), options).map_err(zip_error)?;
+        zip.write_all(bytes).map_err(|e| format!("写入档案失败: {}", e))?;
+    }
+    zip.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+/// 读出档案中的所有文件；拒绝指向档案外的路径和未知的条目
+fn read_archive(path: &Path) -> Result<(Manifest, ProfileFiles), String> {
+    let file = File::open(path).map_err(|e| format!("打开档案失败: {}", e))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("不是有效的配置档案: {}", e))?;
+    let mut manifest = None;
+    let mut files = Vec::new();
+
+    for index in 0..zip.len() {
+        let mut entry = zip.by_index(index).map_err(zip_error)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry
+            .enclosed_name()
+            .map(|p| p.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"))
+            .ok_or_else(|| format!("档案中包含非法路径: {}", entry.name()))?;
+        if entry.size() > MAX_ENTRY_BYTES {
+            return Err(format!("档案中的 {} 过大", name));
+        }
+        let mut bytes = Vec::with_capacity(entry.size() as usize);
+        entry
+            .by_ref()
+            .take(MAX_ENTRY_BYTES)
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("读取档案中的 {} 失败: {}", name, e))?;
+
+        let known = name == MANIFEST_ENTRY
+            || name == CREDENTIALS_ENTRY
+            || name.starts_with("plugins/")
+            || config_files().iter().any(|(file, _)| *file == name);
+        if !known {
+            return Err(format!("档案中包含未知文件: {}", name));
+        }
+        if name == MANIFEST_ENTRY {
+            manifest = Some(parse_json::<Manifest>(MANIFEST_ENTRY, &bytes)?);
+        } else {
+            files.push((name, bytes));
+        }
+    }
+
+    let manifest = manifest.ok_or("档案中缺少 profile.json，不是有效的配置档案")?;
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "档案格式版本 {} 高于当前程序支持的版本 {}，请升级程序",
+            manifest.format_version, FORMAT_VERSION
+        ));
+    }
+    Ok((manifest, files))
+}
+
+/// 内容相同（JSON 文件按值比较，忽略格式差异）
+fn same_content(existing: &[u8], incoming: &[u8]) -> bool {
+    if existing == incoming {
+        return true;
+    }
+    match (
+        serde_json::from_slice::<Value>(existing),
+        serde_json::from_slice::<Value>(incoming),
+    ) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// 校验档案中的一个文件，补上密码，返回要写入本机的内容
+fn prepare_file(
+    app_data_dir: &Path,
+    name: &str,
+    bytes: &[u8],
+    credentials: Option<&Credentials>,
+) -> Result<Vec<u8>, String> {
+    if name == settings::SETTINGS_FILE {
+        let document: Value = parse_json(name, bytes)?;
+        let spider = settings::migrate(document)?.settings;
+        let errors = spider.validate();
+        if !errors.is_empty() {
+            return Err(format!("档案中的爬虫配置无效: {}", crate::spider_params::describe_errors(&errors)));
+        }
+        to_json(&spider)
+    } else if name == crawl_policy::SETTINGS_FILE {
+        let policy: CrawlPolicy = parse_json(name, bytes)?;
+        policy.validate()?;
+        to_json(&policy)
+    } else if name == network::SETTINGS_FILE {
+        let mut network: NetworkSettings = parse_json(name, bytes)?;
+        network.proxy_password = match credentials {
+            Some(credentials) => credentials.proxy_password.clone(),
+            None => read_local::<NetworkSettings>(app_data_dir, name)
+                .ok()
+                .flatten()
+                .map(|local| local.proxy_password)
+                .unwrap_or_default(),
+        };
+        network.validate()?;
+        to_json(&network)
+    } else if name == settings::SMTP_FILE {
+        let mut smtp: SmtpConfig = parse_json(name, bytes)?;
+        smtp.password = match credentials {
+            Some(credentials) => credentials.smtp_password.clone(),
+            None => read_local::<SmtpConfig>(app_data_dir, name)
+                .ok()
+                .flatten()
+                .map(|local| local.password)
+                .unwrap_or_default(),
+        };
+        to_json(&smtp)
+    } else {
+        Ok(bytes.to_vec())
+    }
+}
+
+/// 导入配置档案。dry_run 为 true 时只返回将会新建/覆盖哪些文件，不做任何修改；
+/// 档案带有加密凭据但没有提供口令时，保留本机原有密码
+pub fn import_profile(
+    app_data_dir: &Path,
+    path: &Path,
+    passphrase: Option<&str>,
+    dry_run: bool,
+) -> Result<ImportReport, String> {
+    let (manifest, files) = read_archive(path)?;
+
+    let credentials = match (files.iter().find(|(name, _)| name == CREDENTIALS_ENTRY), passphrase) {
+        (Some((_, bytes)), Some(passphrase)) if !passphrase.is_empty() => {
+            let encrypted: EncryptedCredentials = parse_json(CREDENTIALS_ENTRY, bytes)?;
+            Some(decrypt_credentials(&encrypted, passphrase)?)
+        }
+        _ => None,
+    };
+
+    // 先校验全部文件，任何一个无效都不写入
+    let mut planned = Vec::new();
+    for (name, bytes) in files.iter().filter(|(name, _)| name != CREDENTIALS_ENTRY) {
+        let content = prepare_file(app_data_dir, name, bytes, credentials.as_ref())?;
+        let target = app_data_dir.join(name);
+        let action = match fs::read(&target) {
+            Ok(existing) if same_content(&existing, &content) => ImportAction::Unchanged,
+            Ok(_) => ImportAction::Overwrite,
+            Err(_) => ImportAction::Create,
+        };
+        planned.push((name.clone(), target, content, action));
+    }
+
+    if !dry_run {
+        for (name, target, content, action) in &planned {
+            if *action == ImportAction::Unchanged {
+                continue;
+            }
+            if name.contains('/') {
+                config_file::write_atomic(target, content)?;
+            } else {
+                // 配置文件保留被覆盖前的版本，导入后仍可恢复
+                config_file::save(app_data_dir, name, content)?;
+            }
+        }
+    }
+
+    Ok(ImportReport {
+        path: path.to_path_buf(),
+        created_at: manifest.created_at,
+        entries: planned
+            .into_iter()
+            .map(|(name, _, _, action)| ImportEntry {
+                label: label_of(&name),
+                path: name,
+                action,
+            })
+            .collect(),
+        has_credentials: manifest.has_credentials,
+        credentials_imported: credentials.is_some(),
+        dry_run,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn credentials() -> Credentials {
+        Credentials { smtp_password: "smtp-密码".to_string(), proxy_password: "proxy-secret".to_string() }
+    }
+
+    /// 写入只包含清单和指定条目的档案
+    fn write_raw_archive(path: &Path, names: &[&str]) {
+        let manifest = Manifest {
+            format_version: FORMAT_VERSION,
+            created_at: String::new(),
+            app_version: String::new(),
+            entries: names.iter().map(|name| name.to_string()).collect(),
+            has_credentials: false,
+        };
+        let files: ProfileFiles = names.iter().map(|name| (name.to_string(), b"{}".to_vec())).collect();
+        write_archive(path, &manifest, &files).unwrap();
+    }
+
+    #[test]
+    fn credentials_round_trip() {
+        let encrypted = encrypt_credentials(&credentials(), "口令 passphrase").unwrap();
+        assert_eq!(encrypted.rounds, PBKDF2_ROUNDS);
+        assert!(!encrypted.ciphertext.contains("proxy-secret"));
+
+        let decrypted = decrypt_credentials(&encrypted, "口令 passphrase").unwrap();
+        assert_eq!(decrypted.smtp_password, "smtp-密码");
+        assert_eq!(decrypted.proxy_password, "proxy-secret");
+        assert!(decrypt_credentials(&encrypted, "wrong").unwrap_err().contains("口令错误"));
+    }
+
+    #[test]
+    fn unexpected_rounds_are_rejected_before_deriving_key() {
+        let mut encrypted = encrypt_credentials(&credentials(), "passphrase").unwrap();
+        for rounds in [u32::MAX, 1, PBKDF2_ROUNDS + 1] {
+            encrypted.rounds = rounds;
+            let started = std::time::Instant::now();
+            let error = decrypt_credentials(&encrypted, "passphrase").unwrap_err();
+            assert!(error.contains("轮数"), "{}", error);
+            assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        }
+    }
+
+    #[test]
+    fn archive_paths_outside_app_data_dir_are_rejected() {
+        let dir = TempDir::new("profile");
+        let path = dir.path().join("profile.zip");
+        for name in ["../evil.json", "plugins/../../evil.json"] {
+            write_raw_archive(&path, &[name]);
+            let error = read_archive(&path).unwrap_err();
+            assert!(error.contains("非法路径"), "{}: {}", name, error);
+        }
+    }
+
+    #[test]
+    fn unknown_archive_entries_are_rejected() {
+        let dir = TempDir::new("profile");
+        let path = dir.path().join("profile.zip");
+        write_raw_archive(&path, &["secrets.txt"]);
+        assert!(read_archive(&path).unwrap_err().contains("未知文件"));
+
+        write_raw_archive(&path, &[settings::SMTP_FILE, "plugins/demo/plugin.json"]);
+        let (_, files) = read_archive(&path).unwrap();
+        let names: Vec<_> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, [settings::SMTP_FILE, "plugins/demo/plugin.json"]);
+    }
+
+    #[test]
+    fn export_and_import_restore_passwords_and_plugins() {
+        let source = TempDir::new("profile");
+        let target = TempDir::new("profile");
+        let smtp = SmtpConfig {
+            server: "smtp.example.com".to_string(),
+            port: 465,
+            username: "robot@example.com".to_string(),
+            password: "smtp-密码".to_string(),
+        };
+        smtp.save(source.path()).unwrap();
+        let plugin_dir = source.path().join(PLUGINS_DIR).join("demo");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        fs::write(plugin_dir.join("spider.py"), "print('demo')").unwrap();
+
+        let path = source.path().join("profile.zip");
+        let summary = export_profile(source.path(), &path, Some("passphrase")).unwrap();
+        assert!(summary.includes_credentials);
+        assert_eq!(summary.entries, [settings::SMTP_FILE, "plugins/demo/spider.py"]);
+
+        // 预览不写入文件
+        let preview = import_profile(target.path(), &path, Some("passphrase"), true).unwrap();
+        assert!(preview.entries.iter().all(|entry| entry.action == ImportAction::Create));
+        assert!(!target.path().join(settings::SMTP_FILE).exists());
+
+        let report = import_profile(target.path(), &path, Some("passphrase"), false).unwrap();
+        assert!(report.credentials_imported);
+        let imported: SmtpConfig = config_file::load_json(target.path(), settings::SMTP_FILE).unwrap().unwrap();
+        assert_eq!(imported.password, "smtp-密码");
+        assert_eq!(imported.server, "smtp.example.com");
+        assert_eq!(fs::read_to_string(target.path().join("plugins/demo/spider.py")).unwrap(), "print('demo')");
+
+        // 再次导入内容相同，全部跳过
+        let again = import_profile(target.path(), &path, None, true).unwrap();
+        assert!(again.entries.iter().all(|entry| entry.action == ImportAction::Unchanged));
+    }
+}
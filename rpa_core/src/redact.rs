@@ -0,0 +1,237 @@
+// ============================================================================
+// 敏感信息脱敏
+// ============================================================================
+//
+// 失败路径会原样带出子进程 stderr、请求参数和配置内容，其中可能有 SMTP 账号和授权码、
+// 代理密码以及从 bj.css 中提取的 RSA 密钥。日志、发送给前端的事件和返回的错误在输出前都经过 redact：
+//   - 已知的密钥值（SMTP 密码、代理密码，由 register_secret 登记）替换为 ******
+//   - password / passwd / pwd / secret / token / authorization / 授权码 / 密码 后面的值替换为 ******
+//   - PEM 格式的密钥和长度不少于 MIN_KEY_CHARS 的 base64 串替换为 [已隐藏 N 字符]
+//   - 邮箱地址的用户名部分只保留首字符（可在设置中关闭）
+// 设置保存在应用数据目录的 redaction_settings.json 中
+
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::config_file;
+use crate::error::AppResult;
+
+pub const SETTINGS_FILE: &str = "redaction_settings.json";
+const MASK: &str = "******";
+/// 视为密钥的 base64 串最小长度
+const MIN_KEY_CHARS: usize = 40;
+/// 短于此长度的值不登记为密钥，避免误伤普通文字
+const MIN_SECRET_CHARS: usize = 4;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RedactionSettings {
+    /// 隐藏邮箱地址的用户名部分（user@qq.com -> u***@qq.com）
+    #[serde(default = "default_true")]
+    pub mask_email_local_part: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        RedactionSettings { mask_email_local_part: true }
+    }
+}
+
+impl RedactionSettings {
+    pub fn load(app_data_dir: &Path) -> AppResult<Self> {
+        Ok(config_file::load_json(app_data_dir, SETTINGS_FILE)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, app_data_dir: &Path) -> Result<(), String> {
+        config_file::save_json(app_data_dir, SETTINGS_FILE, self)
+    }
+}
+
+static SETTINGS: RwLock<RedactionSettings> = RwLock::new(RedactionSettings { mask_email_local_part: true });
+static SECRETS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// 应用脱敏设置
+pub fn configure(settings: RedactionSettings) {
+    *SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = settings;
+}
+
+/// 登记一个需要隐藏的值（如 SMTP 授权码、代理密码）
+pub fn register_secret(secret: &str) {
+    let secret = secret.trim();
+    if secret.chars().count() < MIN_SECRET_CHARS {
+        return;
+    }
+    let mut secrets = SECRETS.write().unwrap_or_else(|e| e.into_inner());
+    if !secrets.iter().any(|s| s == secret) {
+        secrets.push(secret.to_string());
+        // 先替换较长的值，避免一个密钥是另一个的子串时只替换一部分
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+    }
+}
+
+fn pem_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"-----BEGIN [A-Z ]*KEY-----[\s\S]*?-----END [A-Z ]*KEY-----").unwrap()
+    })
+}
+
+/// key=value、key: value、"key": "value" 形式的口令字段
+fn secret_field_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(
+            r#"(?i)((?:password|passwd|pwd|secret|token|authorization|auth_code|授权码|密码)["']?\s*[:=：]\s*["']?)(?:bearer\s+|basic\s+)?[^\s"',;&}]+"#,
+        )
+        .unwrap()
+    })
+}
+
+fn base64_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(&format!(r"[A-Za-z0-9+/]{{{},}}={{0,2}}", MIN_KEY_CHARS)).unwrap())
+}
+
+fn email_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"([A-Za-z0-9._%+-]+)@([A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,})").unwrap())
+}
+
+/// 看起来像密钥的 base64：同时包含大写、小写字母和数字，且不是纯十六进制（SHA-256 等哈希值不隐藏）
+fn looks_like_key(text: &str) -> bool {
+    let has_upper = text.chars().any(|c| c.is_ascii_uppercase());
+    let has_lower = text.chars().any(|c| c.is_ascii_lowercase());
+    let has_digit = text.chars().any(|c| c.is_ascii_digit());
+    let hex = text.chars().all(|c| c.is_ascii_hexdigit());
+    has_upper && has_lower && has_digit && !hex
+}
+
+/// 隐藏文字中的敏感信息
+pub fn redact(text: &str) -> String {
+    let mut text = text.to_string();
+    for secret in SECRETS.read().unwrap_or_else(|e| e.into_inner()).iter() {
+        if text.contains(secret.as_str()) {
+            text = text.replace(secret.as_str(), MASK);
+        }
+    }
+
+    let text = pem_pattern().replace_all(&text, |caps: &Captures| {
+        format!("[已隐藏 {} 字符]", caps[0].chars().count())
+    });
+    let text = secret_field_pattern().replace_all(&text, |caps: &Captures| format!("{}{}", &caps[1], MASK));
+    let text = base64_pattern().replace_all(&text, |caps: &Captures| {
+        if looks_like_key(&caps[0]) {
+            format!("[已隐藏 {} 字符]", caps[0].len())
+        } else {
+            caps[0].to_string()
+        }
+    });
+
+    if SETTINGS.read().unwrap_or_else(|e| e.into_inner()).mask_email_local_part {
+        email_pattern()
+            .replace_all(&text, |caps: &Captures| {
+                let first: String = caps[1].chars().take(1).collect();
+                format!("{}***@{}", first, &caps[2])
+            })
+            .into_owned()
+    } else {
+        text.into_owned()
+    }
+}
+
+/// 隐藏 JSON 中所有字符串值里的敏感信息（用于事件 payload）
+pub fn redact_json(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::String(text) => Value::String(redact(&text)),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact_json).collect()),
+        Value::Object(map) => Value::Object(map.into_iter().map(|(key, value)| (key, redact_json(value))).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// 设置是全局状态，读写设置的测试串行执行
+    static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+    fn with_email_masking<T>(enabled: bool, f: impl FnOnce() -> T) -> T {
+        let _guard = SETTINGS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        configure(RedactionSettings { mask_email_local_part: enabled });
+        let result = f();
+        configure(RedactionSettings::default());
+        result
+    }
+
+    #[test]
+    fn registered_secrets_are_masked() {
+        // 用随机值，避免和并行执行的其他测试登记的密钥互相影响
+        let short = format!("sec{}", uuid::Uuid::new_v4().simple());
+        let long = format!("{}-suffix", short);
+        register_secret(&short);
+        register_secret(&format!("  {}  ", long));
+        register_secret("abc");
+
+        assert_eq!(redact(&format!("连接失败：{} / {}", long, short)), "连接失败：****** / ******");
+        // 太短的值不登记
+        assert_eq!(redact("abc"), "abc");
+    }
+
+    #[test]
+    fn secret_fields_are_masked() {
+        assert_eq!(redact("smtp password=hunter22 port=465"), "smtp password=****** port=465");
+        assert_eq!(redact(r#"{"pwd": "p@ss", "user": "bob"}"#), r#"{"pwd": "******", "user": "bob"}"#);
+        assert_eq!(redact("Authorization: Bearer abc.def"), "Authorization: ******");
+        assert_eq!(redact("TOKEN=xyz&page=1"), "TOKEN=******&page=1");
+        assert_eq!(redact("授权码：abcdefgh 请检查"), "授权码：****** 请检查");
+        assert_eq!(redact("密码 = 123456"), "密码 = ******");
+    }
+
+    #[test]
+    fn keys_are_hidden_but_hashes_are_kept() {
+        let pem = "-----BEGIN PUBLIC KEY-----\nMIIBIjANBgkq\n-----END PUBLIC KEY-----";
+        assert_eq!(redact(&format!("key: {}", pem)), format!("key: [已隐藏 {} 字符]", pem.chars().count()));
+
+        let key = "MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEA0z";
+        assert_eq!(redact(&format!("rsa {}", key)), format!("rsa [已隐藏 {} 字符]", key.len()));
+
+        let sha256 = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        assert_eq!(redact(sha256), sha256);
+        // 只有小写字母的长串不像密钥
+        let path = "abcdefghijklmnopqrstuvwxyzabcdefghijklmnopqrstuvwxyz";
+        assert_eq!(redact(path), path);
+    }
+
+    #[test]
+    fn email_local_part_masking_follows_settings() {
+        let text = "发送到 alice.w@qq.com 和 b@mail.cnpc.com.cn";
+        assert_eq!(with_email_masking(true, || redact(text)), "发送到 a***@qq.com 和 b***@mail.cnpc.com.cn");
+        assert_eq!(with_email_masking(false, || redact(text)), text);
+    }
+
+    #[test]
+    fn json_strings_are_redacted_recursively() {
+        let value = serde_json::json!({
+            "message": "password=hunter22",
+            "items": ["token: abc", 42, null],
+            "ok": true,
+        });
+        assert_eq!(
+            redact_json(value),
+            serde_json::json!({
+                "message": "password=******",
+                "items": ["token: ******", 42, null],
+                "ok": true,
+            })
+        );
+    }
+}
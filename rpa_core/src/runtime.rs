@@ -0,0 +1,573 @@
+// ============================================================================
+// 运行环境解析：Python 解释器、spiders.py 脚本、打包的 spider_bin
+// ============================================================================
+//
+// RuntimeResolver 放在 Tauri 的 managed state 中，解析结果缓存在 RwLock 里。
+// 查找顺序：
+//   Python:   用户配置 -> 私有环境（应用数据目录下的 venv，安装完成后）-> PATH 中的 python3 / python
+//   脚本:     用户配置 -> 资源目录 spiders.py -> 工作目录向上 -> 可执行文件目录向上
+//   可执行文件: 用户配置 -> 资源目录 bin/ -> 工作目录向上 -> 可执行文件目录向上
+// 每个尝试过的候选路径都记录下来（以及被拒绝的原因），用于界面展示和排查问题。
+// 脚本和可执行文件要通过完整性校验（integrity.rs）：哈希与构建时清单不一致的候选会被拒绝，
+// 使用前也会重新校验，防止向上查找时执行到来历不明的 spider_bin。
+// 用户配置的路径保存在 runtime_settings.json 中，修改后或调用 resolve() 时重新解析
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::RwLock;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::config_file;
+use crate::error::AppResult;
+use crate::integrity::{self, FileIntegrity, IntegrityMode};
+use crate::venv;
+
+pub const SETTINGS_FILE: &str = "runtime_settings.json";
+/// 开发环境下爬虫项目所在的目录名
+const SPIDER_PROJECT_DIR: &str = "中国石油招标投标网";
+const SCRIPT_NAME: &str = "spiders.py";
+/// 从工作目录/可执行文件目录向上查找的层数
+const SEARCH_DEPTH: usize = 5;
+
+/// 用户指定的路径，为空表示自动查找
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RuntimeSettings {
+    #[serde(default)]
+    pub python_path: String,
+    #[serde(default)]
+    pub script_path: String,
+    #[serde(default)]
+    pub binary_path: String,
+    /// 完整性校验不通过时拒绝执行（enforce）还是只警告（warn）
+    #[serde(default)]
+    pub integrity_mode: IntegrityMode,
+}
+
+impl RuntimeSettings {
+    pub fn load(app_data_dir: &Path) -> AppResult<Self> {
+        Ok(config_file::load_json(app_data_dir, SETTINGS_FILE)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, app_data_dir: &Path) -> Result<(), String> {
+        for path in [&self.script_path, &self.binary_path] {
+            if !path.trim().is_empty() && !Path::new(path.trim()).is_absolute() {
+                return Err(format!("请填写绝对路径: {}", path));
+            }
+        }
+        config_file::save_json(app_data_dir, SETTINGS_FILE, self)
+    }
+}
+
+/// 一个尝试过的候选路径
+#[derive(Serialize, Debug, Clone)]
+pub struct Candidate {
+    pub path: String,
+    /// 来源：用户配置 / PATH / 资源目录 / 工作目录 / 可执行文件目录
+    pub source: String,
+    /// 被拒绝的原因，为空表示采用了这个候选
+    pub rejected: Option<String>,
+}
+
+/// 一项运行环境的解析结果
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct Resolution {
+    pub path: Option<String>,
+    /// Python 的版本号（仅 Python）
+    pub version: Option<String>,
+    pub candidates: Vec<Candidate>,
+}
+
+impl Resolution {
+    /// 依次尝试候选路径，check 返回 Err 时记录原因并继续
+    fn search<F>(candidates: Vec<(String, &str)>, mut check: F) -> Self
+    where
+        F: FnMut(&str) -> Result<Option<String>, String>,
+    {
+        let mut resolution = Resolution::default();
+        for (path, source) in candidates {
+            if resolution.path.is_some() {
+                break;
+            }
+            let rejected = match check(&path) {
+                Ok(version) => {
+                    resolution.path = Some(path.clone());
+                    resolution.version = version;
+                    None
+                }
+                Err(reason) => Some(reason),
+            };
+            resolution.candidates.push(Candidate {
+                path,
+                source: source.to_string(),
+                rejected,
+            });
+        }
+        resolution
+    }
+
+    /// 未找到时的说明，列出尝试过的路径
+    pub fn describe_failure(&self, what: &str) -> String {
+        let tried: Vec<String> = self
+            .candidates
+            .iter()
+            .map(|c| format!("- [{}] {}: {}", c.source, c.path, c.rejected.as_deref().unwrap_or("可用")))
+            .collect();
+        format!("未找到{}。\n\n已尝试的路径:\n{}", what, tried.join("\n"))
+    }
+}
+
+/// 全部运行环境的解析结果
+#[derive(Serialize, Debug, Clone)]
+pub struct RuntimeReport {
+    pub python: Resolution,
+    pub script: Resolution,
+    pub binary: Resolution,
+    pub resolved_at: String,
+}
+
+/// 自动查找的起点
+#[derive(Debug, Clone, Default)]
+pub struct SearchRoots {
+    pub resource_dir: Option<PathBuf>,
+    pub current_dir: Option<PathBuf>,
+    pub exe_dir: Option<PathBuf>,
+    /// 应用数据目录，私有 Python 环境在其中
+    pub app_data_dir: Option<PathBuf>,
+}
+
+impl SearchRoots {
+    /// 当前进程的工作目录和可执行文件目录，resource_dir 和 app_data_dir 由调用方提供
+    pub fn from_process(resource_dir: Option<PathBuf>, app_data_dir: Option<PathBuf>) -> Self {
+        SearchRoots {
+            resource_dir,
+            app_data_dir,
+            current_dir: std::env::current_dir().ok(),
+            exe_dir: std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(Path::to_path_buf)),
+        }
+    }
+
+    /// 从 start 开始向上 SEARCH_DEPTH 层，每层拼接 relative
+    fn ancestors(start: &Option<PathBuf>, relative: &Path) -> Vec<String> {
+        start
+            .iter()
+            .flat_map(|dir| dir.ancestors().take(SEARCH_DEPTH))
+            .map(|dir| dir.join(relative).to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// 私有环境的解释器（未创建时为空）
+    fn venv_python_candidates(&self) -> Vec<(String, &'static str)> {
+        self.app_data_dir
+            .iter()
+            .map(|dir| (venv::python_path(&venv::env_dir(dir)).to_string_lossy().to_string(), "私有环境"))
+            .collect()
+    }
+
+    fn script_candidates(&self) -> Vec<(String, &'static str)> {
+        let relative = Path::new(SPIDER_PROJECT_DIR).join(SCRIPT_NAME);
+        let mut candidates: Vec<(String, &'static str)> = self
+            .resource_dir
+            .iter()
+            .map(|dir| (dir.join(SCRIPT_NAME).to_string_lossy().to_string(), "资源目录"))
+            .collect();
+        candidates.extend(Self::ancestors(&self.current_dir, &relative).into_iter().map(|p| (p, "工作目录")));
+        candidates.extend(Self::ancestors(&self.exe_dir, &relative).into_iter().map(|p| (p, "可执行文件目录")));
+        candidates
+    }
+
+    fn binary_candidates(&self) -> Vec<(String, &'static str)> {
+        let name = format!("spider_bin{}", std::env::consts::EXE_SUFFIX);
+        // onedir 模式：spider_bin/spider_bin；旧版本：单个文件 spider_bin
+        let layouts = [Path::new("spider_bin").join(&name), PathBuf::from(&name)];
+        let mut candidates = Vec::new();
+        if let Some(dir) = &self.resource_dir {
+            for layout in &layouts {
+                candidates.push((dir.join("bin").join(layout).to_string_lossy().to_string(), "资源目录"));
+            }
+        }
+        for (start, source) in [(&self.current_dir, "工作目录"), (&self.exe_dir, "可执行文件目录")] {
+            for layout in &layouts {
+                let relative = Path::new(SPIDER_PROJECT_DIR).join("dist").join(layout);
+                candidates.extend(Self::ancestors(start, &relative).into_iter().map(|p| (p, source)));
+            }
+        }
+        candidates
+    }
+}
+
+/// 检查 Python 解释器：能执行 --version 且是 Python 3
+pub(crate) fn check_python(program: &str) -> Result<Option<String>, String> {
+    let output = Command::new(program)
+        .arg("--version")
+        .output()
+        .map_err(|e| format!("无法执行: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("执行 --version 失败（{}）", output.status));
+    }
+    // Python 2 把版本号输出到 stderr
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let version = text.trim().trim_start_matches("Python").trim().to_string();
+    if !version.starts_with('3') {
+        return Err(format!("需要 Python 3，当前为 {}", text.trim()));
+    }
+    Ok(Some(version))
+}
+
+/// 私有环境必须完整安装过（有安装记录）才使用，安装到一半的环境不会被选中
+fn check_venv_python(program: &str) -> Result<Option<String>, String> {
+    check_file(program)?;
+    let env_dir = venv::env_dir_of(Path::new(program)).ok_or("不在私有环境目录中")?;
+    if venv::read_marker(&env_dir).is_none() {
+        return Err("私有环境尚未安装完成".to_string());
+    }
+    check_python(program)
+}
+
+fn check_file(path: &str) -> Result<Option<String>, String> {
+    let path = Path::new(path);
+    if !path.exists() {
+        return Err("不存在".to_string());
+    }
+    if !path.is_file() {
+        return Err("不是文件".to_string());
+    }
+    Ok(None)
+}
+
+fn check_executable(path: &str) -> Result<Option<String>, String> {
+    check_file(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path).map_err(|e| format!("无法读取文件信息: {}", e))?.permissions().mode();
+        if mode & 0o111 == 0 {
+            return Err("没有执行权限".to_string());
+        }
+    }
+    Ok(None)
+}
+
+/// 完整性校验；用户配置的路径不在清单范围内，只给出警告
+fn check_integrity(
+    path: &str,
+    user_path: &str,
+    mode: IntegrityMode,
+    verify: fn(&Path) -> Vec<FileIntegrity>,
+) -> Result<Option<String>, String> {
+    let mode = if path == user_path.trim() { IntegrityMode::Warn } else { mode };
+    integrity::enforce(&verify(Path::new(path)), mode)?;
+    Ok(None)
+}
+
+/// 用户配置的路径排在自动查找的候选之前
+fn with_user_path(user: &str, auto: Vec<(String, &'static str)>) -> Vec<(String, &'static str)> {
+    let user = user.trim();
+    let mut candidates = Vec::new();
+    if !user.is_empty() {
+        candidates.push((user.to_string(), "用户配置"));
+    }
+    candidates.extend(auto);
+    candidates
+}
+
+/// 运行环境解析服务，放在 Tauri 的 managed state 中
+pub struct RuntimeResolver {
+    roots: SearchRoots,
+    settings: RwLock<RuntimeSettings>,
+    report: RwLock<Option<RuntimeReport>>,
+}
+
+impl RuntimeResolver {
+    pub fn new(roots: SearchRoots, settings: RuntimeSettings) -> Self {
+        RuntimeResolver {
+            roots,
+            settings: RwLock::new(settings),
+            report: RwLock::new(None),
+        }
+    }
+
+    pub fn settings(&self) -> RuntimeSettings {
+        self.settings.read().unwrap().clone()
+    }
+
+    /// 更新用户配置并重新解析
+    pub fn set_settings(&self, settings: RuntimeSettings) -> RuntimeReport {
+        *self.settings.write().unwrap() = settings;
+        self.resolve()
+    }
+
+    fn path_python_candidates() -> Vec<(String, &'static str)> {
+        ["python3", "python"].iter().map(|p| (p.to_string(), "PATH")).collect()
+    }
+
+    /// 重新查找全部运行环境
+    pub fn resolve(&self) -> RuntimeReport {
+        let settings = self.settings();
+        let mut python_candidates = self.roots.venv_python_candidates();
+        python_candidates.extend(Self::path_python_candidates());
+        let python = Resolution::search(with_user_path(&settings.python_path, python_candidates), |program| {
+            if venv::env_dir_of(Path::new(program)).is_some() {
+                check_venv_python(program)
+            } else {
+                check_python(program)
+            }
+        });
+        let report = RuntimeReport {
+            python,
+            script: Resolution::search(with_user_path(&settings.script_path, self.roots.script_candidates()), |path| {
+                Self::check_script(&settings, path)
+            }),
+            binary: Resolution::search(with_user_path(&settings.binary_path, self.roots.binary_candidates()), |path| {
+                Self::check_binary(&settings, path)
+            }),
+            resolved_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        };
+        for (what, resolution) in [("Python", &report.python), ("爬虫脚本", &report.script), ("爬虫可执行文件", &report.binary)] {
+            match &resolution.path {
+                Some(path) => log_info!("[运行环境] {}: {}", what, path),
+                None => log_info!("[运行环境] 未找到{}（尝试了 {} 个路径）", what, resolution.candidates.len()),
+            }
+        }
+        *self.report.write().unwrap() = Some(report.clone());
+        report
+    }
+
+    /// 当前的解析结果；还没有解析过时先解析
+    pub fn report(&self) -> RuntimeReport {
+        if let Some(report) = self.report.read().unwrap().as_ref() {
+            return report.clone();
+        }
+        self.resolve()
+    }
+
+    fn check_script(settings: &RuntimeSettings, path: &str) -> Result<Option<String>, String> {
+        check_file(path)?;
+        check_integrity(path, &settings.script_path, settings.integrity_mode, integrity::verify_script)
+    }
+
+    fn check_binary(settings: &RuntimeSettings, path: &str) -> Result<Option<String>, String> {
+        check_executable(path)?;
+        check_integrity(path, &settings.binary_path, settings.integrity_mode, integrity::verify_binary)
+    }
+
+    /// 使用前重新检查缓存的文件：已被删除或完整性校验不通过时重新解析
+    fn report_with_valid(
+        &self,
+        pick: fn(&RuntimeReport) -> &Resolution,
+        check: fn(&RuntimeSettings, &str) -> Result<Option<String>, String>,
+    ) -> RuntimeReport {
+        let report = self.report();
+        match &pick(&report).path {
+            Some(path) => match check(&self.settings(), path) {
+                Ok(_) => report,
+                Err(e) => {
+                    log_warn!("[运行环境] {} 已不可用（{}），重新查找", path, e);
+                    self.resolve()
+                }
+            },
+            None => report,
+        }
+    }
+
+    pub fn python(&self) -> Option<String> {
+        self.report().python.path
+    }
+
+    /// 系统的 Python（不含私有环境），用作创建私有环境的基础解释器
+    pub fn system_python(&self) -> Resolution {
+        let settings = self.settings();
+        let user = if venv::env_dir_of(Path::new(settings.python_path.trim())).is_some() {
+            ""
+        } else {
+            settings.python_path.as_str()
+        };
+        Resolution::search(with_user_path(user, Self::path_python_candidates()), check_python)
+    }
+
+    pub fn script(&self) -> Result<PathBuf, String> {
+        let report = self.report_with_valid(|r| &r.script, Self::check_script);
+        report
+            .script
+            .path
+            .as_deref()
+            .map(PathBuf::from)
+            .ok_or_else(|| report.script.describe_failure("爬虫脚本 spiders.py"))
+    }
+
+    pub fn binary(&self) -> Result<PathBuf, String> {
+        let report = self.report_with_valid(|r| &r.binary, Self::check_binary);
+        report
+            .binary
+            .path
+            .as_deref()
+            .map(PathBuf::from)
+            .ok_or_else(|| report.binary.describe_failure("爬虫可执行文件 spider_bin"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn roots(base: &Path) -> SearchRoots {
+        SearchRoots {
+            resource_dir: Some(base.join("resources")),
+            current_dir: Some(base.join("work/a/b/c/d/e")),
+            exe_dir: Some(base.join("exe")),
+            app_data_dir: Some(base.join("data")),
+        }
+    }
+
+    fn sources(candidates: &[(String, &str)]) -> Vec<String> {
+        candidates.iter().map(|(_, source)| source.to_string()).collect()
+    }
+
+    #[test]
+    fn search_stops_at_first_accepted_candidate() {
+        let candidates = vec![
+            ("a".to_string(), "用户配置"),
+            ("b".to_string(), "PATH"),
+            ("c".to_string(), "PATH"),
+        ];
+        let resolution = Resolution::search(candidates, |path| match path {
+            "a" => Err("不存在".to_string()),
+            _ => Ok(Some(format!("{}-version", path))),
+        });
+        assert_eq!(resolution.path.as_deref(), Some("b"));
+        assert_eq!(resolution.version.as_deref(), Some("b-version"));
+        // c 没有尝试
+        let tried: Vec<_> = resolution.candidates.iter().map(|c| (c.path.as_str(), c.rejected.as_deref())).collect();
+        assert_eq!(tried, [("a", Some("不存在")), ("b", None)]);
+
+        let none = Resolution::search(vec![("x".to_string(), "PATH")], |_| Err("没有执行权限".to_string()));
+        assert_eq!(none.path, None);
+        let message = none.describe_failure("Python");
+        assert!(message.contains("未找到Python") && message.contains("- [PATH] x: 没有执行权限"), "{}", message);
+    }
+
+    #[test]
+    fn user_path_comes_first() {
+        let auto = vec![("auto".to_string(), "PATH")];
+        assert_eq!(
+            with_user_path("  /opt/python3  ", auto.clone()),
+            vec![("/opt/python3".to_string(), "用户配置"), ("auto".to_string(), "PATH")]
+        );
+        assert_eq!(with_user_path("   ", auto.clone()), auto);
+    }
+
+    #[test]
+    fn script_candidates_are_ordered_by_source() {
+        let base = Path::new("/base");
+        let candidates = roots(base).script_candidates();
+        let relative = Path::new(SPIDER_PROJECT_DIR).join(SCRIPT_NAME);
+
+        assert_eq!(candidates[0].0, base.join("resources").join(SCRIPT_NAME).to_string_lossy());
+        // 工作目录向上 SEARCH_DEPTH 层，然后是可执行文件目录向上
+        assert_eq!(candidates[1].0, base.join("work/a/b/c/d/e").join(&relative).to_string_lossy());
+        assert_eq!(candidates[SEARCH_DEPTH].0, base.join("work/a").join(&relative).to_string_lossy());
+        assert_eq!(candidates[SEARCH_DEPTH + 1].0, base.join("exe").join(&relative).to_string_lossy());
+        let mut expected = vec!["资源目录".to_string()];
+        expected.extend(std::iter::repeat_n("工作目录".to_string(), SEARCH_DEPTH));
+        // /base/exe 只有 3 层祖先（/base/exe、/base、/）
+        expected.extend(std::iter::repeat_n("可执行文件目录".to_string(), 3));
+        assert_eq!(sources(&candidates), expected);
+
+        assert!(SearchRoots::default().script_candidates().is_empty());
+    }
+
+    #[test]
+    fn binary_candidates_prefer_onedir_layout() {
+        let base = Path::new("/base");
+        let candidates = SearchRoots { exe_dir: None, ..roots(base) }.binary_candidates();
+        let name = format!("spider_bin{}", std::env::consts::EXE_SUFFIX);
+
+        assert_eq!(candidates[0].0, base.join("resources/bin/spider_bin").join(&name).to_string_lossy());
+        assert_eq!(candidates[1].0, base.join("resources/bin").join(&name).to_string_lossy());
+        let dist = base.join("work/a/b/c/d/e").join(SPIDER_PROJECT_DIR).join("dist");
+        assert_eq!(candidates[2].0, dist.join("spider_bin").join(&name).to_string_lossy());
+        // 每一层先试 onedir 布局，再试单个文件
+        assert_eq!(candidates[2 + SEARCH_DEPTH].0, dist.join(&name).to_string_lossy());
+        assert_eq!(candidates.len(), 2 + 2 * SEARCH_DEPTH);
+    }
+
+    #[test]
+    fn private_env_python_is_a_candidate() {
+        let base = Path::new("/base");
+        let candidates = roots(base).venv_python_candidates();
+        let expected = venv::python_path(&venv::env_dir(&base.join("data")));
+        assert_eq!(candidates, vec![(expected.to_string_lossy().to_string(), "私有环境")]);
+        assert!(SearchRoots::default().venv_python_candidates().is_empty());
+    }
+
+    #[test]
+    fn file_checks() {
+        let dir = TempDir::new("runtime");
+        let file = dir.path().join("spider_bin");
+        std::fs::write(&file, b"#!/bin/sh\n").unwrap();
+        let file = file.to_string_lossy().to_string();
+
+        assert_eq!(check_file(&file), Ok(None));
+        assert_eq!(check_file(&dir.path().join("missing").to_string_lossy()), Err("不存在".to_string()));
+        assert_eq!(check_file(&dir.path().to_string_lossy()), Err("不是文件".to_string()));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(check_executable(&file), Err("没有执行权限".to_string()));
+            std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o755)).unwrap();
+            assert_eq!(check_executable(&file), Ok(None));
+        }
+    }
+
+    #[test]
+    fn settings_require_absolute_paths() {
+        let dir = TempDir::new("runtime");
+        let relative = RuntimeSettings { script_path: "spiders.py".to_string(), ..Default::default() };
+        assert!(relative.save(dir.path()).is_err());
+
+        let absolute = dir.path().join("spiders.py").to_string_lossy().to_string();
+        let settings = RuntimeSettings { script_path: absolute.clone(), integrity_mode: IntegrityMode::Warn, ..Default::default() };
+        settings.save(dir.path()).unwrap();
+        let loaded = RuntimeSettings::load(dir.path()).unwrap();
+        assert_eq!((loaded.script_path, loaded.integrity_mode), (absolute, IntegrityMode::Warn));
+    }
+
+    #[test]
+    fn unlisted_auto_candidates_are_rejected_but_user_path_is_used() {
+        let dir = TempDir::new("runtime");
+        // 资源目录中的脚本与清单不一致，自动查找时被拒绝
+        let resources = dir.path().join("resources");
+        std::fs::create_dir_all(&resources).unwrap();
+        std::fs::write(resources.join(SCRIPT_NAME), "print('modified')").unwrap();
+        let user_script = dir.path().join("mine.py");
+        std::fs::write(&user_script, "print('mine')").unwrap();
+
+        let roots = SearchRoots { resource_dir: Some(resources.clone()), ..SearchRoots::default() };
+        let resolver = RuntimeResolver::new(roots, RuntimeSettings::default());
+        let report = resolver.resolve();
+        assert_eq!(report.script.path, None);
+        let rejected = report.script.candidates[0].rejected.as_deref().unwrap_or_default();
+        assert!(rejected.contains("完整性校验失败"), "{}", rejected);
+        assert!(resolver.script().unwrap_err().contains(&resources.join(SCRIPT_NAME).to_string_lossy().to_string()));
+
+        // 用户指定的路径不在清单范围内，只警告
+        let user_path = user_script.to_string_lossy().to_string();
+        let report = resolver.set_settings(RuntimeSettings { script_path: user_path.clone(), ..Default::default() });
+        assert_eq!(report.script.path.as_deref(), Some(user_path.as_str()));
+        assert_eq!(report.script.candidates[0].source, "用户配置");
+        assert_eq!(resolver.script().unwrap(), user_script);
+
+        // 使用前重新检查：文件被删除后重新查找
+        std::fs::remove_file(&user_script).unwrap();
+        assert!(resolver.script().is_err());
+        assert_eq!(resolver.report().script.path, None);
+    }
+}
@@ -0,0 +1,324 @@
+// ============================================================================
+// 定时任务：到点执行爬虫并把结果发送到邮箱
+// ============================================================================
+//
+// plan 校验任务设置（时间、爬虫参数）并算出执行时间，run 等到执行时间后执行爬虫、
+// 通过事件通知界面（scheduled-task-result / scheduled-task-error / email-sent / email-error），
+// 设置了邮箱时再把结果发送到邮箱。桌面应用的 start_scheduled_spider 命令和命令行工具的
+// schedule / daemon 子命令都使用这里的逻辑。
+// start 把任务登记到 AppCore 的 Schedules 中再在后台执行，可以列出、替换和取消尚未执行完的任务
+// （只保存在内存中，程序退出后不再执行）
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Local, TimeZone};
+use serde::Serialize;
+use tokio::task::AbortHandle;
+
+use crate::app_core::{AppCore, RunOptions};
+use crate::error::{AppError, AppResult};
+use crate::i18n::{self, Locale};
+use crate::settings::SpiderConfig;
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+/// 日志中结果预览的最大长度（字符）
+const RESULT_PREVIEW_CHARS: usize = 200;
+
+/// 校验通过、等待执行的定时任务
+#[derive(Debug, Clone)]
+pub struct ScheduledJob {
+    pub spider_id: String,
+    pub params: serde_json::Value,
+    /// 结果发送到的邮箱，为空时不发送
+    pub email: String,
+    /// 启用时放在邮件正文开头的推送内容
+    pub push_content: Option<String>,
+    pub next_run: DateTime<Local>,
+    pub options: RunOptions,
+}
+
+impl ScheduledJob {
+    /// 执行时间的文字形式，同时用作任务标识
+    pub fn time_label(&self) -> String {
+        self.next_run.format(TIME_FORMAT).to_string()
+    }
+}
+
+/// 校验时间字段并换算为本地时间
+fn target_time(config: &SpiderConfig, locale: Locale) -> AppResult<DateTime<Local>> {
+    let invalid_time = |key: &str| AppError::invalid_input("schedule.invalid_time", i18n::text(locale, key));
+    if config.month < 1 || config.month > 12 {
+        return Err(invalid_time("schedule.invalid_month"));
+    }
+    if config.day < 1 || config.day > 31 {
+        return Err(invalid_time("schedule.invalid_day"));
+    }
+    if config.hour > 23 {
+        return Err(invalid_time("schedule.invalid_hour"));
+    }
+    if config.minute > 59 {
+        return Err(invalid_time("schedule.invalid_minute"));
+    }
+    if config.second > 59 {
+        return Err(invalid_time("schedule.invalid_second"));
+    }
+
+    let target_date = chrono::NaiveDate::from_ymd_opt(config.year as i32, config.month, config.day)
+        .ok_or_else(|| invalid_time("schedule.invalid_date"))?;
+    let target_time = chrono::NaiveTime::from_hms_opt(config.hour, config.minute, config.second)
+        .ok_or_else(|| invalid_time("schedule.invalid_clock"))?;
+    Local
+        .from_local_datetime(&target_date.and_time(target_time))
+        .single()
+        .ok_or_else(|| invalid_time("schedule.invalid_local_time"))
+}
+
+/// 校验任务设置，执行时间必须晚于 now（一般为 AppCore::now）；爬虫参数在启动前校验，避免到点执行时才发现参数错误
+pub fn plan(core: &AppCore, config: &SpiderConfig, now: DateTime<Local>) -> AppResult<ScheduledJob> {
+    let locale = core.locale();
+    let next_run = target_time(config, locale)?;
+    core.checked_spider(&config.spider_id, &config.params)?;
+
+    if next_run <= now {
+        return Err(AppError::invalid_input(
+            "schedule.time_passed",
+            i18n::format(locale, "schedule.time_passed", &[("time", &next_run.format(TIME_FORMAT))]),
+        )
+        .hint(i18n::text(locale, "schedule.time_passed.hint")));
+    }
+
+    let push_content = Some(config.push_content.clone())
+        .filter(|content| config.push_content_enabled && !content.is_empty());
+    Ok(ScheduledJob {
+        spider_id: config.spider_id.clone(),
+        params: config.params.clone(),
+        email: config.email.clone(),
+        push_content,
+        next_run,
+        // 定时任务以计划执行时间作为任务标识，便于在数据库中按任务筛选
+        options: RunOptions {
+            job: format!("scheduled:{}", next_run.format(TIME_FORMAT)),
+            fetch_details: config.fetch_details,
+            backend: config.backend,
+        },
+    })
+}
+
+/// 邮件正文：推送内容（如果启用）、新公告列表、执行结果或错误
+fn email_body(locale: Locale, job: &ScheduledJob, new_announcements: &str, result: &Result<String, String>) -> String {
+    let mut parts = Vec::new();
+    if let Some(push_content) = &job.push_content {
+        parts.push(push_content.clone());
+    }
+    match result {
+        Ok(data) => {
+            // 新公告的详情页链接和附件
+            if !new_announcements.is_empty() {
+                parts.push(new_announcements.to_string());
+            }
+            parts.push(i18n::format(locale, "email.result_ok", &[("data", data)]));
+        }
+        Err(e) => parts.push(i18n::format(locale, "email.result_failed", &[("error", e)])),
+    }
+    parts.join("\n\n")
+}
+
+/// 结果超过 RESULT_PREVIEW_CHARS 个字符时日志中只显示开头部分（按字符截断，结果中有中文标题）
+fn result_preview(data: &str) -> Option<String> {
+    let mut chars = data.chars();
+    let preview: String = chars.by_ref().take(RESULT_PREVIEW_CHARS).collect();
+    chars.next().map(|_| preview)
+}
+
+/// 等到执行时间后执行任务并发送邮件；爬虫或邮件失败时返回错误说明
+pub async fn run(core: Arc<AppCore>, job: ScheduledJob) -> Result<(), String> {
+    // 当前时间来自 AppCore 的时钟，等待用 tokio 的计时器（测试中可以暂停和快进）
+    let wait = (job.next_run - core.now()).to_std().unwrap_or_default();
+    log_info!("[定时任务] ✅ 后台任务已成功启动！");
+    log_info!("[定时任务] 等待 {} 秒后执行 ({} 分钟)", wait.as_secs(), wait.as_secs() / 60);
+
+    tokio::time::sleep(wait).await;
+
+    log_info!("[定时任务] ⏰ 到达执行时间，开始执行爬虫任务");
+    log_info!("[定时任务] 🚀 开始执行任务 - {}", core.now().format(TIME_FORMAT));
+
+    let spider_core = core.clone();
+    let spider_job = job.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        log_info!("[定时任务] 正在执行爬虫 {}...", spider_job.spider_id);
+        spider_core
+            .run_spider(&spider_job.spider_id, &spider_job.options, spider_job.params.clone())
+            .map(|output| {
+                let new_announcements = if output.new_ids.is_empty() {
+                    String::new()
+                } else {
+                    spider_core.new_announcements_text(&output.new_ids)
+                };
+                (output.raw.to_string(), new_announcements)
+            })
+    }).await.unwrap_or_else(|e| {
+        log_error!("[定时任务] ❌ 任务执行失败: {}", e);
+        Err(AppError::task(e))
+    });
+    // 事件和邮件正文使用错误的文字描述
+    let (result, new_announcements) = match result {
+        Ok((data, new_announcements)) => (Ok(data), new_announcements),
+        Err(e) => (Err(e.to_string()), String::new()),
+    };
+
+    match &result {
+        Ok(data) => {
+            log_info!("[定时任务] ✅ 执行成功，结果长度: {} 字符", data.len());
+            match result_preview(data) {
+                Some(preview) => log_info!("[定时任务] 结果预览: {}...", preview),
+                None => log_info!("[定时任务] 结果: {}", data),
+            }
+            core.emit("scheduled-task-result", data);
+        }
+        Err(e) => {
+            log_error!("[定时任务] ❌ 执行失败: {}", e);
+            core.emit("scheduled-task-error", e);
+        }
+    }
+
+    // 如果有邮箱地址，就发送邮件（无论是否启用推送内容）
+    if job.email.is_empty() {
+        return result.map(|_| ());
+    }
+    log_info!("[定时任务] 📧 准备发送邮件到: {}", job.email);
+    log_info!("[定时任务] 推送内容: {}", if job.push_content.is_some() { "已设置" } else { "(空)" });
+
+    // 按执行时的语言设置生成邮件
+    let locale = core.locale();
+    let subject = i18n::text(locale, "email.scheduled_subject");
+    let body = email_body(locale, &job, &new_announcements, &result);
+
+    // 同步发送邮件，确保错误能被捕获
+    let email_core = core.clone();
+    let to = job.email.clone();
+    let email_result = tokio::task::spawn_blocking(move || email_core.send_email(&to, &subject, &body)).await;
+
+    let email_result = match email_result {
+        Ok(Ok(msg)) => {
+            core.emit("email-sent", msg);
+            Ok(())
+        }
+        Ok(Err(e)) => Err(i18n::format(locale, "email.send_failed", &[("error", &e)])),
+        Err(e) => Err(format!("邮件发送任务执行失败: {}", e)),
+    };
+    if let Err(error_msg) = &email_result {
+        core.emit("email-error", error_msg);
+        core.emit("scheduled-task-error", error_msg);
+    }
+    result.and(email_result)
+}
+
+/// 已登记的定时任务（给界面和 HTTP API 看的信息）
+#[derive(Serialize, Debug, Clone)]
+pub struct ScheduleInfo {
+    pub id: String,
+    pub spider_id: String,
+    pub params: serde_json::Value,
+    pub email: String,
+    /// 执行时间，格式 YYYY-MM-DD HH:MM:SS
+    pub next_run: String,
+    pub fetch_details: bool,
+    pub push_content_enabled: bool,
+}
+
+impl ScheduleInfo {
+    fn new(id: &str, job: &ScheduledJob) -> Self {
+        ScheduleInfo {
+            id: id.to_string(),
+            spider_id: job.spider_id.clone(),
+            params: job.params.clone(),
+            email: job.email.clone(),
+            next_run: job.time_label(),
+            fetch_details: job.options.fetch_details,
+            push_content_enabled: job.push_content.is_some(),
+        }
+    }
+}
+
+/// 等待执行或正在执行的定时任务，执行完后自动移除
+#[derive(Default)]
+pub struct Schedules {
+    jobs: Mutex<HashMap<String, (ScheduleInfo, AbortHandle)>>,
+}
+
+impl Schedules {
+    /// 按执行时间排序
+    pub fn list(&self) -> Vec<ScheduleInfo> {
+        let mut list: Vec<_> = self.jobs.lock().unwrap().values().map(|(info, _)| info.clone()).collect();
+        list.sort_by(|a, b| a.next_run.cmp(&b.next_run).then_with(|| a.id.cmp(&b.id)));
+        list
+    }
+
+    pub fn get(&self, id: &str) -> Option<ScheduleInfo> {
+        self.jobs.lock().unwrap().get(id).map(|(info, _)| info.clone())
+    }
+
+    /// 取消任务，任务不存在（或已执行完）时返回 false
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.jobs.lock().unwrap().remove(id) {
+            Some((_, handle)) => {
+                handle.abort();
+                log_info!("[定时任务] 已取消任务 {}", id);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 登记任务并在后台执行，返回任务信息
+fn spawn(core: &Arc<AppCore>, id: String, job: ScheduledJob) -> ScheduleInfo {
+    let info = ScheduleInfo::new(&id, &job);
+    // 持有锁直到登记完成，避免任务在登记前就执行完
+    let mut jobs = core.schedules.jobs.lock().unwrap();
+    let task_core = core.clone();
+    let task_id = id.clone();
+    let handle = tokio::spawn(async move {
+        if let Err(e) = run(task_core.clone(), job).await {
+            log_warn!("[定时任务] 任务 {} 失败: {}", task_id, e);
+        }
+        // 任务被替换后同一 ID 登记的是新任务，只移除自己
+        let mut jobs = task_core.schedules.jobs.lock().unwrap();
+        if jobs.get(&task_id).is_some_and(|(_, handle)| handle.id() == tokio::task::id()) {
+            jobs.remove(&task_id);
+        }
+    });
+    jobs.insert(id, (info.clone(), handle.abort_handle()));
+    info
+}
+
+/// 登记并启动一个新的定时任务
+pub fn start(core: &Arc<AppCore>, job: ScheduledJob) -> ScheduleInfo {
+    spawn(core, uuid::Uuid::new_v4().simple().to_string(), job)
+}
+
+/// 用新的设置替换已登记的任务（保留任务 ID），任务不存在时返回 None
+pub fn replace(core: &Arc<AppCore>, id: &str, job: ScheduledJob) -> Option<ScheduleInfo> {
+    if !core.schedules.cancel(id) {
+        return None;
+    }
+    Some(spawn(core, id.to_string(), job))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn result_preview_truncates_on_char_boundary() {
+        let short = "公告".repeat(100);
+        assert_eq!(result_preview(&short), None);
+
+        // 第 200 个字节落在汉字中间
+        let long = format!("a{}", "公告".repeat(150));
+        let preview = result_preview(&long).unwrap();
+        assert_eq!(preview.chars().count(), RESULT_PREVIEW_CHARS);
+        assert!(long.starts_with(&preview));
+    }
+}
@@ -0,0 +1,442 @@
+// ============================================================================
+// 应用设置文档（spider_config.json）
+// ============================================================================
+//
+// 设置文件带 version 字段，加载时按迁移链逐级升级到当前版本：
+//   v0: 扁平格式 { page, title, project_type, email, year, ... }
+//   v1: { params: { page, title, project_type }, email, year, ..., push_content? }
+//   v2: 在 v1 基础上增加 version / spider_id / push_content_enabled / fetch_details，所有字段必填
+//   v3: 增加执行后端 backend（auto / python / binary）和回退策略 fallback
+// 升级前的原文件备份为 spider_config.json.v<旧版本>.bak
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::backend::BackendOptions;
+use crate::config_file;
+use crate::error::{AppError, AppResult, ErrorKind};
+use crate::spider_params::{FieldError, ProjectType};
+use crate::spider_registry::DEFAULT_SPIDER_ID;
+
+pub const SETTINGS_FILE: &str = "spider_config.json";
+pub const SMTP_FILE: &str = "smtp_config.json";
+pub const CURRENT_VERSION: u32 = 3;
+/// 推送内容最大长度（字符）
+const MAX_PUSH_CONTENT_CHARS: usize = 5000;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpiderConfig {
+    #[serde(default = "default_spider_id")]
+    pub spider_id: String,
+    pub params: Value,
+    pub email: String,
+    pub year: u32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+    pub enabled: bool,
+    #[serde(default)]
+    pub push_content_enabled: bool,
+    #[serde(default)]
+    pub push_content: String,
+    /// 是否抓取新公告的详情页和附件
+    #[serde(default)]
+    pub fetch_details: bool,
+    /// 内置爬虫的执行后端和回退策略
+    #[serde(flatten)]
+    pub backend: BackendOptions,
+}
+
+pub fn default_spider_id() -> String {
+    DEFAULT_SPIDER_ID.to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SmtpConfig {
+    pub server: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+}
+
+impl Default for SmtpConfig {
+    /// 默认使用 QQ 邮箱
+    fn default() -> Self {
+        SmtpConfig {
+            server: "smtp.qq.com".to_string(),
+            port: 587,
+            username: String::new(),
+            password: String::new(),
+        }
+    }
+}
+
+impl SmtpConfig {
+    /// 读取 SMTP 配置；没有保存过时返回默认的 QQ 邮箱配置，文件损坏时返回错误
+    pub fn load(app_data_dir: &Path) -> AppResult<Self> {
+        Ok(config_file::load_json(app_data_dir, SMTP_FILE)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, app_data_dir: &Path) -> Result<(), String> {
+        config_file::save_json(app_data_dir, SMTP_FILE, self)
+    }
+}
+
+/// 带版本号的设置文档
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppSettings {
+    pub version: u32,
+    #[serde(flatten)]
+    pub spider: SpiderConfig,
+}
+
+impl AppSettings {
+    /// 校验设置中与具体爬虫无关的字段（爬虫参数由调用方按插件 schema 校验）；
+    /// 版本号不在这里检查，设置总是先经过 migrate 升级到当前版本
+    pub fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        let config = &self.spider;
+
+        if config.spider_id.trim().is_empty() {
+            errors.push(FieldError::new("spider_id", "required", "请选择爬虫".to_string()));
+        }
+        let email = config.email.trim();
+        if !email.is_empty() && !is_valid_email(email) {
+            errors.push(FieldError::new("email", "pattern", "邮箱地址格式不正确".to_string()));
+        }
+        if !(1..=12).contains(&config.month) {
+            errors.push(FieldError::new("month", "range", "月份必须在 1-12 之间".to_string()));
+        }
+        if !(1..=31).contains(&config.day) {
+            errors.push(FieldError::new("day", "range", "日期必须在 1-31 之间".to_string()));
+        } else if (1..=12).contains(&config.month)
+            && chrono::NaiveDate::from_ymd_opt(config.year as i32, config.month, config.day).is_none()
+        {
+            errors.push(FieldError::new("day", "range", "无效的日期".to_string()));
+        }
+        if config.hour > 23 {
+            errors.push(FieldError::new("hour", "range", "小时必须在 0-23 之间".to_string()));
+        }
+        if config.minute > 59 {
+            errors.push(FieldError::new("minute", "range", "分钟必须在 0-59 之间".to_string()));
+        }
+        if config.second > 59 {
+            errors.push(FieldError::new("second", "range", "秒必须在 0-59 之间".to_string()));
+        }
+        if config.push_content.chars().count() > MAX_PUSH_CONTENT_CHARS {
+            errors.push(FieldError::new(
+                "push_content",
+                "max_length",
+                format!("推送内容不能超过 {} 个字符", MAX_PUSH_CONTENT_CHARS),
+            ));
+        }
+        errors
+    }
+}
+
+fn is_valid_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !email.contains(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+/// 判断原始设置文档的版本
+fn detect_version(document: &Value) -> u32 {
+    match document.get("version").and_then(Value::as_u64) {
+        Some(version) => version as u32,
+        None if document.get("params").is_some() => 1,
+        None => 0,
+    }
+}
+
+/// v0 -> v1：把扁平的 page / title / project_type 移到 params 中，项目类型统一为中文名称
+fn migrate_v0(mut object: Map<String, Value>) -> Map<String, Value> {
+    let page = object.remove("page").unwrap_or(json!(1));
+    let title = object.remove("title").unwrap_or(json!(""));
+    let project_type = object
+        .remove("project_type")
+        .and_then(|v| v.as_str().and_then(ProjectType::parse))
+        .unwrap_or_default();
+    object.insert(
+        "params".to_string(),
+        json!({ "page": page, "title": title, "project_type": project_type.label() }),
+    );
+    object
+}
+
+/// v1 -> v2：补齐后来加上的字段，写入版本号
+fn migrate_v1(mut object: Map<String, Value>) -> Map<String, Value> {
+    let push_content = object.get("push_content").and_then(Value::as_str).unwrap_or_default().to_string();
+    object.entry("spider_id").or_insert_with(|| json!(DEFAULT_SPIDER_ID));
+    // 旧版本只要填写了推送内容就会推送
+    object.entry("push_content_enabled").or_insert_with(|| json!(!push_content.is_empty()));
+    object.entry("push_content").or_insert_with(|| json!(""));
+    object.entry("fetch_details").or_insert_with(|| json!(false));
+    for (key, default) in [("email", json!("")), ("enabled", json!(false))] {
+        object.entry(key).or_insert(default);
+    }
+    object
+}
+
+/// v2 -> v3：保持原来的行为（先 Python，环境错误时改用打包版本）
+fn migrate_v2(mut object: Map<String, Value>) -> Map<String, Value> {
+    object.entry("backend").or_insert_with(|| json!("auto"));
+    object.entry("fallback").or_insert_with(|| json!("on_environment_error"));
+    object
+}
+
+/// 迁移结果
+#[derive(Debug)]
+pub struct Migrated {
+    pub settings: AppSettings,
+    /// 原文件的版本，等于 CURRENT_VERSION 表示无需迁移
+    pub from_version: u32,
+}
+
+/// 把任意版本的设置文档升级到当前版本
+pub fn migrate(document: Value) -> Result<Migrated, String> {
+    let from_version = detect_version(&document);
+    if from_version > CURRENT_VERSION {
+        return Err(format!(
+            "设置文件版本 {} 高于当前程序支持的版本 {}，请升级程序",
+            from_version, CURRENT_VERSION
+        ));
+    }
+    let mut object = match document {
+        Value::Object(object) => object,
+        _ => return Err("设置文件必须是 JSON 对象".to_string()),
+    };
+
+    if from_version < 1 {
+        object = migrate_v0(object);
+    }
+    if from_version < 2 {
+        object = migrate_v1(object);
+    }
+    if from_version < 3 {
+        object = migrate_v2(object);
+    }
+    object.insert("version".to_string(), json!(CURRENT_VERSION));
+
+    let settings = serde_json::from_value(Value::Object(object))
+        .map_err(|e| format!("设置文件格式不正确: {}", e))?;
+    Ok(Migrated { settings, from_version })
+}
+
+/// 加载设置；文件不存在时返回 None，文件损坏时返回可恢复的错误。旧版本文件会先备份再升级写回
+pub fn load(app_data_dir: &Path) -> AppResult<Option<AppSettings>> {
+    let Some(content) = config_file::read(app_data_dir, SETTINGS_FILE)? else {
+        return Ok(None);
+    };
+    let document: Value = serde_json::from_str(&content)
+        .map_err(|e| config_file::corrupted(app_data_dir, SETTINGS_FILE, e))?;
+    let Migrated { settings, from_version } =
+        migrate(document).map_err(|e| AppError::new(ErrorKind::Config, "config.invalid", e))?;
+
+    if from_version < CURRENT_VERSION {
+        let backup = app_data_dir.join(format!("{}.v{}.bak", SETTINGS_FILE, from_version));
+        config_file::write_atomic(&backup, content.as_bytes())
+            .map_err(|e| AppError::config("备份旧版设置文件失败").detail(e))?;
+        write(app_data_dir, &settings).map_err(AppError::config)?;
+        log_info!("[设置] 设置文件已从 v{} 升级到 v{}，原文件备份为 {:?}", from_version, CURRENT_VERSION, backup);
+    }
+    Ok(Some(settings))
+}
+
+/// 写入设置文件（调用方负责校验）
+pub fn write(app_data_dir: &Path, settings: &AppSettings) -> Result<(), String> {
+    config_file::save_json(app_data_dir, SETTINGS_FILE, settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{BackendChoice, FallbackPolicy};
+    use crate::test_support::TempDir;
+
+    fn schedule() -> Map<String, Value> {
+        json!({ "email": "user@example.com", "year": 2025, "month": 1, "day": 2, "hour": 3, "minute": 4, "second": 5, "enabled": true })
+            .as_object()
+            .unwrap()
+            .clone()
+    }
+
+    fn document(fields: Value) -> Value {
+        let mut object = schedule();
+        object.extend(fields.as_object().unwrap().clone());
+        Value::Object(object)
+    }
+
+    fn v0() -> Value {
+        document(json!({ "page": 2, "title": "管道", "project_type": "工程", "push_content": "请关注" }))
+    }
+
+    fn v1() -> Value {
+        document(json!({ "params": { "page": 1, "title": "", "project_type": "0001" }, "push_content": "" }))
+    }
+
+    fn v2() -> Value {
+        document(json!({
+            "version": 2,
+            "spider_id": "sinopec",
+            "params": { "page": 3 },
+            "push_content_enabled": false,
+            "push_content": "暂不推送",
+            "fetch_details": true,
+        }))
+    }
+
+    #[test]
+    fn v0_flat_document_is_upgraded() {
+        let Migrated { settings, from_version } = migrate(v0()).unwrap();
+        assert_eq!(from_version, 0);
+        assert_eq!(settings.version, CURRENT_VERSION);
+        let spider = &settings.spider;
+        assert_eq!(spider.spider_id, DEFAULT_SPIDER_ID);
+        assert_eq!(spider.params, json!({ "page": 2, "title": "管道", "project_type": "工程" }));
+        assert_eq!((spider.email.as_str(), spider.year, spider.second, spider.enabled), ("user@example.com", 2025, 5, true));
+        // 旧版本填写了推送内容就会推送
+        assert!(spider.push_content_enabled);
+        assert_eq!(spider.push_content, "请关注");
+        assert!(!spider.fetch_details);
+        assert_eq!(spider.backend.backend, BackendChoice::Auto);
+        assert_eq!(spider.backend.fallback, Some(FallbackPolicy::OnEnvironmentError));
+        assert!(settings.validate().is_empty());
+    }
+
+    #[test]
+    fn v0_without_optional_fields_gets_defaults() {
+        let mut document = v0();
+        let object = document.as_object_mut().unwrap();
+        for key in ["page", "title", "project_type", "email", "enabled", "push_content"] {
+            object.remove(key);
+        }
+        let spider = migrate(document).unwrap().settings.spider;
+        assert_eq!(spider.params, json!({ "page": 1, "title": "", "project_type": "全部" }));
+        assert_eq!(spider.email, "");
+        assert!(!spider.enabled);
+        assert!(!spider.push_content_enabled);
+        assert_eq!(spider.push_content, "");
+    }
+
+    #[test]
+    fn v0_project_type_is_stored_as_label() {
+        // 服务与物资的编码相同，转换成编码会丢失选择
+        let mut document = v0();
+        document["project_type"] = json!("服务");
+        let spider = migrate(document).unwrap().settings.spider;
+        assert_eq!(spider.params["project_type"], "服务");
+    }
+
+    #[test]
+    fn v1_document_keeps_params_and_push_content() {
+        let Migrated { settings, from_version } = migrate(v1()).unwrap();
+        assert_eq!(from_version, 1);
+        let spider = settings.spider;
+        assert_eq!(spider.params, json!({ "page": 1, "title": "", "project_type": "0001" }));
+        assert!(!spider.push_content_enabled);
+        assert_eq!(spider.backend.fallback, Some(FallbackPolicy::OnEnvironmentError));
+    }
+
+    #[test]
+    fn v2_document_keeps_explicit_fields() {
+        let Migrated { settings, from_version } = migrate(v2()).unwrap();
+        assert_eq!(from_version, 2);
+        let spider = settings.spider;
+        assert_eq!(spider.spider_id, "sinopec");
+        assert!(!spider.push_content_enabled);
+        assert_eq!(spider.push_content, "暂不推送");
+        assert!(spider.fetch_details);
+        assert_eq!(spider.backend.backend, BackendChoice::Auto);
+
+        // v2 的字段都是必填的
+        let mut incomplete = v2();
+        incomplete.as_object_mut().unwrap().remove("year");
+        assert!(migrate(incomplete).unwrap_err().contains("格式不正确"));
+    }
+
+    #[test]
+    fn current_document_is_unchanged() {
+        let mut current = v2();
+        current["version"] = json!(CURRENT_VERSION);
+        current["backend"] = json!("binary");
+        let Migrated { settings, from_version } = migrate(current).unwrap();
+        assert_eq!(from_version, CURRENT_VERSION);
+        // 没有写回退策略时按所选后端的默认值处理
+        assert_eq!(settings.spider.backend.backend, BackendChoice::Binary);
+        assert_eq!(settings.spider.backend.fallback, None);
+    }
+
+    #[test]
+    fn future_version_and_non_objects_are_rejected() {
+        let mut future = v2();
+        future["version"] = json!(CURRENT_VERSION + 1);
+        assert!(migrate(future).unwrap_err().contains("高于当前程序支持的版本"));
+        assert!(migrate(json!([1, 2])).is_err());
+    }
+
+    #[test]
+    fn load_backs_up_legacy_file_once_and_writes_current_version() {
+        let dir = TempDir::new("settings");
+        let original = serde_json::to_string_pretty(&v1()).unwrap();
+        std::fs::write(dir.path().join(SETTINGS_FILE), &original).unwrap();
+
+        let settings = load(dir.path()).unwrap().unwrap();
+        assert_eq!(settings.version, CURRENT_VERSION);
+        let backup = dir.path().join(format!("{}.v1.bak", SETTINGS_FILE));
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), original);
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(dir.path().join(SETTINGS_FILE)).unwrap()).unwrap();
+        assert_eq!(written["version"], CURRENT_VERSION);
+
+        // 已经是当前版本，再次加载不再备份
+        std::fs::remove_file(&backup).unwrap();
+        let again = load(dir.path()).unwrap().unwrap();
+        assert_eq!(again.spider.params, settings.spider.params);
+        let backups: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".bak"))
+            .collect();
+        assert!(backups.is_empty());
+    }
+
+    #[test]
+    fn load_reports_missing_corrupted_and_future_files() {
+        let dir = TempDir::new("settings");
+        assert!(load(dir.path()).unwrap().is_none());
+
+        std::fs::write(dir.path().join(SETTINGS_FILE), "{ broken").unwrap();
+        assert_eq!(load(dir.path()).unwrap_err().code, "config.corrupted");
+
+        let mut future = v2();
+        future["version"] = json!(CURRENT_VERSION + 1);
+        std::fs::write(dir.path().join(SETTINGS_FILE), future.to_string()).unwrap();
+        assert_eq!(load(dir.path()).unwrap_err().code, "config.invalid");
+        // 不认识的版本不会被改写
+        let content: Value = serde_json::from_str(&std::fs::read_to_string(dir.path().join(SETTINGS_FILE)).unwrap()).unwrap();
+        assert_eq!(content, future);
+    }
+
+    #[test]
+    fn validate_reports_schedule_and_email_errors() {
+        let mut settings = migrate(v1()).unwrap().settings;
+        settings.spider.email = "not-an-email".to_string();
+        settings.spider.month = 2;
+        settings.spider.day = 30;
+        settings.spider.hour = 24;
+        settings.spider.push_content = "字".repeat(MAX_PUSH_CONTENT_CHARS + 1);
+        let fields: Vec<String> = settings.validate().into_iter().map(|e| e.field).collect();
+        assert_eq!(fields, vec!["email", "day", "hour", "push_content"]);
+    }
+}
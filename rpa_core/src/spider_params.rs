@@ -0,0 +1,429 @@
+// ============================================================================
+// 爬虫参数与校验
+// ============================================================================
+//
+// 在启动任何进程之前先在 Rust 端校验参数，返回字段级错误，
+// 前端可以把错误显示在对应输入框旁边
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// 页码上限（网站每页 10 条，超过该页数基本没有数据）
+pub const MAX_PAGE: i64 = 500;
+/// 搜索关键字最大长度（按字符计）
+pub const MAX_TITLE_CHARS: usize = 50;
+
+/// 项目类型，与 spiders.py 中的 project_type_map 保持一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProjectType {
+    #[default]
+    All,
+    Goods,
+    Engineering,
+    Service,
+}
+
+impl ProjectType {
+    pub const ALL: [ProjectType; 4] = [
+        ProjectType::All,
+        ProjectType::Goods,
+        ProjectType::Engineering,
+        ProjectType::Service,
+    ];
+
+    /// 网站接口使用的编码（注意：spiders.py 中"服务"与"物资"同为 0001）
+    pub fn code(&self) -> &'static str {
+        match self {
+            ProjectType::All => "",
+            ProjectType::Goods => "0001",
+            ProjectType::Engineering => "0002",
+            ProjectType::Service => "0001",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProjectType::All => "全部",
+            ProjectType::Goods => "物资",
+            ProjectType::Engineering => "工程",
+            ProjectType::Service => "服务",
+        }
+    }
+
+    /// 同时接受中文名称和编码。编码 0001 同时对应物资和服务，只能解析为物资，
+    /// 所以 params_schema、前端和保存的配置都使用中文名称，编码只为兼容旧配置
+    pub fn parse(input: &str) -> Option<ProjectType> {
+        let input = input.trim();
+        Self::ALL
+            .iter()
+            .find(|t| t.label() == input)
+            .or_else(|| Self::ALL.iter().find(|t| t.code() == input))
+            .copied()
+    }
+}
+
+impl fmt::Display for ProjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+// 序列化为编码，保持传给 spiders.py 的参数格式不变
+impl Serialize for ProjectType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for ProjectType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        ProjectType::parse(&input)
+            .ok_or_else(|| serde::de::Error::custom(format!("未知的项目类型: {}", input)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpiderParams {
+    pub page: i32,
+    pub title: String,
+    pub project_type: ProjectType,
+}
+
+/// 字段级校验错误
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, code: &str, message: String) -> Self {
+        FieldError {
+            field: field.to_string(),
+            code: code.to_string(),
+            message,
+        }
+    }
+}
+
+/// 把字段错误拼成一条可读的错误信息
+pub fn describe_errors(errors: &[FieldError]) -> String {
+    let details: Vec<String> = errors
+        .iter()
+        .map(|e| format!("- {}: {}", e.field, e.message))
+        .collect();
+    format!("参数校验失败：\n{}", details.join("\n"))
+}
+
+/// 校验中国石油招标投标网爬虫参数，成功时返回规范化后的参数
+pub fn validate_spider_params(params: &Value) -> Result<SpiderParams, Vec<FieldError>> {
+    let mut errors = Vec::new();
+
+    let object = match params.as_object() {
+        Some(object) => object,
+        None => {
+            return Err(vec![FieldError::new("params", "type", "参数必须是 JSON 对象".to_string())]);
+        }
+    };
+
+    let page = match object.get("page") {
+        None | Some(Value::Null) => {
+            errors.push(FieldError::new("page", "required", "请填写页码".to_string()));
+            None
+        }
+        Some(value) => match value.as_i64() {
+            Some(page) if page < 1 => {
+                errors.push(FieldError::new("page", "minimum", "页码必须大于等于 1".to_string()));
+                None
+            }
+            Some(page) if page > MAX_PAGE => {
+                errors.push(FieldError::new("page", "maximum", format!("页码不能超过 {}", MAX_PAGE)));
+                None
+            }
+            Some(page) => Some(page as i32),
+            None => {
+                errors.push(FieldError::new("page", "type", "页码必须是整数".to_string()));
+                None
+            }
+        },
+    };
+
+    let title = match object.get("title") {
+        None | Some(Value::Null) => Some(String::new()),
+        Some(Value::String(title)) => {
+            let title = title.trim();
+            if title.chars().count() > MAX_TITLE_CHARS {
+                errors.push(FieldError::new(
+                    "title",
+                    "max_length",
+                    format!("搜索关键字不能超过 {} 个字符", MAX_TITLE_CHARS),
+                ));
+                None
+            } else if title.chars().any(char::is_control) {
+                errors.push(FieldError::new("title", "pattern", "搜索关键字不能包含控制字符".to_string()));
+                None
+            } else {
+                Some(title.to_string())
+            }
+        }
+        Some(_) => {
+            errors.push(FieldError::new("title", "type", "搜索关键字必须是字符串".to_string()));
+            None
+        }
+    };
+
+    let project_type = match object.get("project_type") {
+        None | Some(Value::Null) => Some(ProjectType::All),
+        Some(Value::String(input)) => match ProjectType::parse(input) {
+            Some(project_type) => Some(project_type),
+            None => {
+                let allowed: Vec<&str> = ProjectType::ALL.iter().map(|t| t.label()).collect();
+                errors.push(FieldError::new(
+                    "project_type",
+                    "enum",
+                    format!("项目类型必须是 {} 之一", allowed.join(" / ")),
+                ));
+                None
+            }
+        },
+        Some(_) => {
+            errors.push(FieldError::new("project_type", "type", "项目类型必须是字符串".to_string()));
+            None
+        }
+    };
+
+    match (page, title, project_type) {
+        (Some(page), Some(title), Some(project_type)) if errors.is_empty() => Ok(SpiderParams {
+            page,
+            title,
+            project_type,
+        }),
+        _ => Err(errors),
+    }
+}
+
+/// 按插件声明的 params_schema 校验参数（支持 type / required / minimum / maximum / maxLength / enum）
+pub fn validate_against_schema(schema: &Value, params: &Value) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    let object = match params.as_object() {
+        Some(object) => object,
+        None => {
+            errors.push(FieldError::new("params", "type", "参数必须是 JSON 对象".to_string()));
+            return errors;
+        }
+    };
+
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for field in required.iter().filter_map(Value::as_str) {
+            if object.get(field).is_none_or(Value::is_null) {
+                errors.push(FieldError::new(field, "required", format!("缺少必填参数 {}", field)));
+            }
+        }
+    }
+
+    let properties = match schema.get("properties").and_then(Value::as_object) {
+        Some(properties) => properties,
+        None => return errors,
+    };
+
+    for (field, rule) in properties {
+        let value = match object.get(field) {
+            Some(value) if !value.is_null() => value,
+            _ => continue,
+        };
+        let label = rule.get("title").and_then(Value::as_str).unwrap_or(field);
+
+        let type_ok = match rule.get("type").and_then(Value::as_str) {
+            Some("integer") => value.is_i64() || value.is_u64(),
+            Some("number") => value.is_number(),
+            Some("string") => value.is_string(),
+            Some("boolean") => value.is_boolean(),
+            Some("array") => value.is_array(),
+            Some("object") => value.is_object(),
+            _ => true,
+        };
+        if !type_ok {
+            let expected = rule.get("type").and_then(Value::as_str).unwrap_or_default();
+            errors.push(FieldError::new(field, "type", format!("{} 的类型必须是 {}", label, expected)));
+            continue;
+        }
+
+        if let Some(number) = value.as_f64() {
+            if let Some(minimum) = rule.get("minimum").and_then(Value::as_f64) {
+                if number < minimum {
+                    errors.push(FieldError::new(field, "minimum", format!("{} 必须大于等于 {}", label, minimum)));
+                }
+            }
+            if let Some(maximum) = rule.get("maximum").and_then(Value::as_f64) {
+                if number > maximum {
+                    errors.push(FieldError::new(field, "maximum", format!("{} 不能超过 {}", label, maximum)));
+                }
+            }
+        }
+
+        if let (Some(text), Some(max_length)) = (value.as_str(), rule.get("maxLength").and_then(Value::as_u64)) {
+            if text.chars().count() as u64 > max_length {
+                errors.push(FieldError::new(field, "max_length", format!("{} 不能超过 {} 个字符", label, max_length)));
+            }
+        }
+
+        if let Some(allowed) = rule.get("enum").and_then(Value::as_array) {
+            if !allowed.contains(value) {
+                errors.push(FieldError::new(field, "enum", format!("{} 的取值不在允许范围内", label)));
+            }
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spider_registry::SpiderPlugin;
+    use serde_json::json;
+
+    fn params(page: Value, title: Value, project_type: Value) -> Value {
+        json!({ "page": page, "title": title, "project_type": project_type })
+    }
+
+    /// 校验失败时的 (字段, 错误码)
+    fn error_codes(params: &Value) -> Vec<(String, String)> {
+        validate_spider_params(params)
+            .unwrap_err()
+            .into_iter()
+            .map(|e| (e.field, e.code))
+            .collect()
+    }
+
+    fn page_error(page: Value) -> Vec<(String, String)> {
+        error_codes(&params(page, json!(""), json!("全部")))
+    }
+
+    #[test]
+    fn page_bounds() {
+        let minimum = vec![("page".to_string(), "minimum".to_string())];
+        assert_eq!(page_error(json!(0)), minimum);
+        assert_eq!(page_error(json!(-3)), minimum);
+        assert_eq!(page_error(json!(MAX_PAGE + 1)), vec![("page".to_string(), "maximum".to_string())]);
+        assert_eq!(page_error(json!("2")), vec![("page".to_string(), "type".to_string())]);
+        assert_eq!(page_error(json!(1.5)), vec![("page".to_string(), "type".to_string())]);
+        assert_eq!(page_error(Value::Null), vec![("page".to_string(), "required".to_string())]);
+
+        for page in [1, MAX_PAGE] {
+            let parsed = validate_spider_params(&params(json!(page), json!(""), json!("全部"))).unwrap();
+            assert_eq!(parsed.page as i64, page);
+        }
+    }
+
+    #[test]
+    fn title_length_is_counted_in_chars() {
+        // 中文每个字符 3 个字节，按字节计会提前报错
+        let longest = "管".repeat(MAX_TITLE_CHARS);
+        let parsed = validate_spider_params(&params(json!(1), json!(format!("  {}  ", longest)), json!("全部"))).unwrap();
+        assert_eq!(parsed.title, longest);
+
+        let too_long = "管".repeat(MAX_TITLE_CHARS + 1);
+        assert_eq!(
+            error_codes(&params(json!(1), json!(too_long), json!("全部"))),
+            vec![("title".to_string(), "max_length".to_string())]
+        );
+        assert_eq!(
+            error_codes(&params(json!(1), json!("管道\u{7}"), json!("全部"))),
+            vec![("title".to_string(), "pattern".to_string())]
+        );
+        assert_eq!(
+            error_codes(&params(json!(1), json!(5), json!("全部"))),
+            vec![("title".to_string(), "type".to_string())]
+        );
+    }
+
+    #[test]
+    fn project_type_labels_and_legacy_codes() {
+        for (label, expected, code) in [
+            ("全部", ProjectType::All, ""),
+            ("物资", ProjectType::Goods, "0001"),
+            ("工程", ProjectType::Engineering, "0002"),
+            ("服务", ProjectType::Service, "0001"),
+        ] {
+            let parsed = validate_spider_params(&params(json!(1), json!(""), json!(label))).unwrap();
+            assert_eq!(parsed.project_type, expected);
+            // 传给 spiders.py 的仍然是编码
+            assert_eq!(serde_json::to_value(&parsed).unwrap()["project_type"], code);
+        }
+
+        // 旧配置中的编码仍然可用，0001 只能解析为物资
+        assert_eq!(ProjectType::parse("0002"), Some(ProjectType::Engineering));
+        assert_eq!(ProjectType::parse("0001"), Some(ProjectType::Goods));
+        assert_eq!(
+            error_codes(&params(json!(1), json!(""), json!("其他"))),
+            vec![("project_type".to_string(), "enum".to_string())]
+        );
+
+        let defaults = validate_spider_params(&json!({ "page": 1 })).unwrap();
+        assert_eq!((defaults.title.as_str(), defaults.project_type), ("", ProjectType::All));
+    }
+
+    #[test]
+    fn errors_for_all_fields_are_reported_together() {
+        let codes = error_codes(&params(json!(0), json!("管".repeat(MAX_TITLE_CHARS + 1)), json!(1)));
+        assert_eq!(
+            codes,
+            vec![
+                ("page".to_string(), "minimum".to_string()),
+                ("title".to_string(), "max_length".to_string()),
+                ("project_type".to_string(), "type".to_string()),
+            ]
+        );
+        assert_eq!(error_codes(&json!([1])), vec![("params".to_string(), "type".to_string())]);
+    }
+
+    #[test]
+    fn builtin_schema_offers_every_label() {
+        let schema = SpiderPlugin::builtin_cnpcbidding().params_schema;
+        let labels: Vec<&str> = ProjectType::ALL.iter().map(ProjectType::label).collect();
+        assert_eq!(schema["properties"]["project_type"]["enum"], json!(labels));
+
+        for label in labels {
+            let value = params(json!(MAX_PAGE), json!("管".repeat(MAX_TITLE_CHARS)), json!(label));
+            assert!(validate_against_schema(&schema, &value).is_empty(), "{}", label);
+            assert!(validate_spider_params(&value).is_ok(), "{}", label);
+        }
+    }
+
+    #[test]
+    fn schema_validation_rules() {
+        let schema = json!({
+            "type": "object",
+            "required": ["page", "region"],
+            "properties": {
+                "page": { "type": "integer", "title": "页码", "minimum": 1, "maximum": 10 },
+                "keyword": { "type": "string", "maxLength": 3 },
+                "region": { "type": "string", "enum": ["华北", "西南"] },
+                "strict": { "type": "boolean" }
+            }
+        });
+        let codes = |params: Value| -> Vec<(String, String)> {
+            validate_against_schema(&schema, &params).into_iter().map(|e| (e.field, e.code)).collect()
+        };
+        let pair = |field: &str, code: &str| (field.to_string(), code.to_string());
+
+        assert!(codes(json!({ "page": 10, "keyword": "管道阀", "region": "西南", "extra": 1 })).is_empty());
+        assert_eq!(codes(json!({ "keyword": "管道" })), vec![pair("page", "required"), pair("region", "required")]);
+        assert_eq!(codes(json!({ "page": 0, "region": "华北" })), vec![pair("page", "minimum")]);
+        assert_eq!(codes(json!({ "page": 11, "region": "华北" })), vec![pair("page", "maximum")]);
+        assert_eq!(codes(json!({ "page": 1.5, "region": "华北" })), vec![pair("page", "type")]);
+        assert_eq!(codes(json!({ "page": 1, "region": "华北", "keyword": "管道阀门" })), vec![pair("keyword", "max_length")]);
+        assert_eq!(codes(json!({ "page": 1, "region": "华东" })), vec![pair("region", "enum")]);
+        assert_eq!(codes(json!({ "page": 1, "region": "华北", "strict": "yes" })), vec![pair("strict", "type")]);
+        assert_eq!(codes(json!("page=1")), vec![pair("params", "type")]);
+
+        let message = &validate_against_schema(&schema, &json!({ "page": 0, "region": "华北" }))[0].message;
+        assert!(message.contains("页码"), "{}", message);
+    }
+}
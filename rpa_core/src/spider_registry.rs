@@ -0,0 +1,446 @@
+// ============================================================================
+// 爬虫插件注册表
+// ============================================================================
+//
+// 每个插件是 plugins 目录下的一个子目录，包含一个 plugin.json：
+//
+// {
+//   "id": "sinopec",
+//   "name": "中国石化招标投标网",
+//   "params_schema": { "type": "object", "properties": { ... } },
+//   "entrypoint": { "type": "script", "path": "spider.py" },
+//   "result_mapping": { "list_path": "/data/records", "fields": { "title": "/title" } },
+//   "detail": { "url_template": "https://example.com/notice/{id}" }
+// }
+//
+// 查找顺序：内置插件 -> 资源目录 plugins/ -> 应用数据目录 plugins/，
+// 后加载的同 id 插件会覆盖先加载的（用户插件可以覆盖打包插件）
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::backend::Backend;
+use crate::process::{ProcessRunner, ProcessSpec};
+use crate::spider_params::{ProjectType, MAX_PAGE, MAX_TITLE_CHARS};
+
+/// 内置的中国石油招标投标网爬虫
+pub const DEFAULT_SPIDER_ID: &str = "cnpcbidding";
+/// 插件描述文件名
+const MANIFEST_FILE: &str = "plugin.json";
+/// 详情页地址模板中替换的值只保留 URL 非保留字符，其余按百分号编码
+const URL_VALUE: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+/// 插件入口
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Entrypoint {
+    /// 脚本：`<interpreter> <path> <params_json>`，interpreter 为 python 时使用检测到的 Python
+    Script {
+        path: String,
+        #[serde(default = "default_interpreter")]
+        interpreter: String,
+    },
+    /// 可执行文件：`<path> <params_json>`
+    Binary { path: String },
+    /// 内置的 Rust 实现
+    Builtin { name: String },
+}
+
+fn default_interpreter() -> String {
+    "python".to_string()
+}
+
+/// 结果映射：从爬虫输出的 JSON 中取出列表，并把每行映射成统一字段
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ResultMapping {
+    /// 列表所在位置（JSON Pointer），为空时输出本身即为列表
+    #[serde(default)]
+    pub list_path: String,
+    /// 统一字段名 -> 行内 JSON Pointer
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+}
+
+/// 详情页来源：用于抓取公告详情和附件
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DetailSource {
+    /// 详情页地址模板，`{字段}` 会被替换为映射后的同名字段；映射结果中有 url 字段时优先使用 url
+    pub url_template: String,
+    /// 详情接口返回 JSON 时，正文 HTML 所在位置（JSON Pointer）；为空表示返回的就是 HTML 页面
+    #[serde(default)]
+    pub content_path: String,
+    /// 视为附件的链接扩展名
+    #[serde(default = "default_attachment_extensions")]
+    pub attachment_extensions: Vec<String>,
+}
+
+fn default_attachment_extensions() -> Vec<String> {
+    ["pdf", "doc", "docx", "xls", "xlsx", "zip", "rar", "7z"]
+        .iter()
+        .map(|ext| ext.to_string())
+        .collect()
+}
+
+/// 插件来源
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginSource {
+    #[default]
+    Builtin,
+    Bundled,
+    User,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpiderPlugin {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_params_schema")]
+    pub params_schema: Value,
+    pub entrypoint: Entrypoint,
+    #[serde(default)]
+    pub result_mapping: ResultMapping,
+    /// 不支持详情抓取时为空
+    #[serde(default)]
+    pub detail: Option<DetailSource>,
+    #[serde(skip_deserializing)]
+    pub source: PluginSource,
+    /// 插件目录，脚本/可执行文件的相对路径以此为基准
+    #[serde(skip)]
+    pub dir: Option<PathBuf>,
+}
+
+fn default_params_schema() -> Value {
+    json!({ "type": "object", "properties": {} })
+}
+
+/// 爬虫执行结果：原始输出 + 按 result_mapping 映射后的行
+#[derive(Serialize, Debug, Clone)]
+pub struct SpiderRunOutput {
+    pub spider_id: String,
+    /// 执行记录 ID（对应本地数据库 runs 表）
+    pub run_id: String,
+    pub raw: Value,
+    pub items: Vec<Map<String, Value>>,
+    /// 本次新入库的公告数量
+    pub new_count: usize,
+    /// 本次新入库的公告 ID（对应本地数据库）
+    pub new_ids: Vec<i64>,
+    /// 成功抓取详情页的公告数量
+    pub detail_count: usize,
+    /// 实际执行的后端
+    pub backend: Option<Backend>,
+    /// 首选后端不可用、改用另一个后端时的原因
+    pub fallback_reason: Option<String>,
+}
+
+impl SpiderPlugin {
+    /// 内置的中国石油招标投标网插件
+    pub fn builtin_cnpcbidding() -> Self {
+        // 对外只公开中文名称，网站接口的编码由 ProjectType 在内部转换
+        let project_types: Vec<&str> = ProjectType::ALL.iter().map(ProjectType::label).collect();
+        SpiderPlugin {
+            id: DEFAULT_SPIDER_ID.to_string(),
+            name: "中国石油招标投标网".to_string(),
+            description: "公开招标中标候选人公示（cnpcbidding.com）".to_string(),
+            params_schema: json!({
+                "type": "object",
+                "required": ["page", "title", "project_type"],
+                "properties": {
+                    "page": { "type": "integer", "title": "页码", "minimum": 1, "maximum": MAX_PAGE },
+                    "title": { "type": "string", "title": "搜索关键字", "maxLength": MAX_TITLE_CHARS },
+                    "project_type": {
+                        "type": "string",
+                        "title": "项目类型",
+                        "enum": project_types
+                    }
+                }
+            }),
+            entrypoint: Entrypoint::Builtin { name: DEFAULT_SPIDER_ID.to_string() },
+            result_mapping: ResultMapping {
+                list_path: "/data/records".to_string(),
+                fields: [
+                    ("id", "/id"),
+                    ("title", "/title"),
+                    ("publish_date", "/publishDate"),
+                    ("project_type", "/projectType"),
+                ]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            },
+            // 网站详情接口需要与列表相同的加密请求，尚未核实，内置插件暂不抓取详情；
+            // 需要时可在用户插件中配置 detail
+            detail: None,
+            source: PluginSource::Builtin,
+            dir: None,
+        }
+    }
+
+    /// 解析入口相对路径（相对于插件目录）
+    pub fn resolve_path(&self, path: &str) -> PathBuf {
+        let path = Path::new(path);
+        match &self.dir {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path.to_path_buf(),
+        }
+    }
+
+    /// 执行脚本或可执行文件入口；进程启动方式、python 解释器和额外的环境变量（如代理）由调用方传入
+    pub fn run_external(
+        &self,
+        runner: &dyn ProcessRunner,
+        python: Option<&str>,
+        env: &[(String, String)],
+        params: &Value,
+    ) -> Result<String, String> {
+        let params_json = serde_json::to_string(params)
+            .map_err(|e| format!("序列化参数失败: {}", e))?;
+
+        let mut command = match &self.entrypoint {
+            Entrypoint::Script { path, interpreter } => {
+                let program = if interpreter == "python" {
+                    python.ok_or("未找到 Python 环境")?.to_string()
+                } else {
+                    interpreter.clone()
+                };
+                ProcessSpec::new(program).arg(self.resolve_path(path).to_string_lossy())
+            }
+            Entrypoint::Binary { path } => ProcessSpec::new(self.resolve_path(path)),
+            Entrypoint::Builtin { name } => {
+                return Err(format!("内置爬虫 {} 不能作为外部程序执行", name));
+            }
+        };
+        command = command.arg(params_json).envs(env.iter().cloned());
+        if let Some(dir) = &self.dir {
+            command = command.cwd(dir);
+        }
+
+        let output = runner
+            .run(&command)
+            .map_err(|e| format!("执行爬虫插件 {} 失败: {}", self.id, e))?;
+
+        if output.success {
+            String::from_utf8(output.stdout).map_err(|e| format!("解析输出失败: {}", e))
+        } else {
+            let error = String::from_utf8(output.stderr)
+                .unwrap_or_else(|_| "未知错误".to_string());
+            Err(format!("爬虫插件 {} 执行失败: {}", self.id, error))
+        }
+    }
+
+    /// 按 result_mapping.list_path 取出原始输出中的行
+    pub fn rows<'a>(&self, raw: &'a Value) -> &'a [Value] {
+        let list = if self.result_mapping.list_path.is_empty() {
+            Some(raw)
+        } else {
+            raw.pointer(&self.result_mapping.list_path)
+        };
+        list.and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// 把一行原始数据映射成统一字段
+    pub fn map_row(&self, row: &Value) -> Map<String, Value> {
+        let mapping = &self.result_mapping;
+        if mapping.fields.is_empty() {
+            return row.as_object().cloned().unwrap_or_default();
+        }
+        mapping
+            .fields
+            .iter()
+            .filter_map(|(field, pointer)| {
+                row.pointer(pointer).map(|v| (field.clone(), v.clone()))
+            })
+            .collect()
+    }
+
+    /// 按 result_mapping 把原始输出映射成统一字段的行
+    pub fn map_results(&self, raw: &Value) -> Vec<Map<String, Value>> {
+        self.rows(raw).iter().map(|row| self.map_row(row)).collect()
+    }
+
+    /// 按映射后的字段生成详情页地址，缺少模板中的字段时返回 None；
+    /// 替换的值来自爬虫输出，按百分号编码后写入，其中的 `{...}` 不会再被当作占位符
+    pub fn detail_url(&self, mapped: &Map<String, Value>) -> Option<String> {
+        if let Some(url) = mapped.get("url").and_then(Value::as_str).filter(|u| !u.trim().is_empty()) {
+            return Some(url.trim().to_string());
+        }
+        let template = self.detail.as_ref()?.url_template.as_str();
+        let mut url = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let end = start + rest[start..].find('}')?;
+            let value = match mapped.get(&rest[start + 1..end])? {
+                Value::String(s) => s.clone(),
+                Value::Null => return None,
+                other => other.to_string(),
+            };
+            url.push_str(&rest[..start]);
+            url.extend(utf8_percent_encode(&value, URL_VALUE));
+            rest = &rest[end + 1..];
+        }
+        url.push_str(rest);
+        Some(url)
+    }
+
+    /// 把原始输出包装成执行结果
+    pub fn to_output(&self, stdout: &str) -> SpiderRunOutput {
+        let raw = serde_json::from_str(stdout.trim())
+            .unwrap_or_else(|_| Value::String(stdout.to_string()));
+        let items = self.map_results(&raw);
+        SpiderRunOutput {
+            spider_id: self.id.clone(),
+            run_id: String::new(),
+            raw,
+            items,
+            new_count: 0,
+            new_ids: Vec::new(),
+            detail_count: 0,
+            backend: None,
+            fallback_reason: None,
+        }
+    }
+}
+
+/// 扫描一个 plugins 目录，返回其中所有合法插件
+fn discover_dir(dir: &Path, source: PluginSource) -> Vec<SpiderPlugin> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut plugins = Vec::new();
+    for entry in entries.flatten() {
+        let plugin_dir = entry.path();
+        let manifest = plugin_dir.join(MANIFEST_FILE);
+        if !manifest.is_file() {
+            continue;
+        }
+        let parsed = fs::read_to_string(&manifest)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<SpiderPlugin>(&content).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(mut plugin) => {
+                plugin.source = source;
+                plugin.dir = Some(plugin_dir);
+                plugins.push(plugin);
+            }
+            Err(e) => log_warn!("[插件] 跳过无效的插件描述 {:?}: {}", manifest, e),
+        }
+    }
+    plugins.sort_by(|a, b| a.id.cmp(&b.id));
+    plugins
+}
+
+/// 爬虫注册表，放在 Tauri 的 managed state 中
+pub struct SpiderRegistry {
+    plugins: RwLock<Vec<SpiderPlugin>>,
+}
+
+impl Default for SpiderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpiderRegistry {
+    pub fn new() -> Self {
+        SpiderRegistry {
+            plugins: RwLock::new(vec![SpiderPlugin::builtin_cnpcbidding()]),
+        }
+    }
+
+    /// 重新扫描插件目录；bundled_dir 为资源目录下的 plugins，user_dir 为应用数据目录下的 plugins
+    pub fn reload(&self, bundled_dir: Option<&Path>, user_dir: Option<&Path>) -> usize {
+        let mut plugins = vec![SpiderPlugin::builtin_cnpcbidding()];
+        let discovered = bundled_dir
+            .map(|dir| discover_dir(dir, PluginSource::Bundled))
+            .into_iter()
+            .chain(user_dir.map(|dir| discover_dir(dir, PluginSource::User)))
+            .flatten();
+        for plugin in discovered {
+            plugins.retain(|p| p.id != plugin.id);
+            plugins.push(plugin);
+        }
+        let count = plugins.len();
+        *self.plugins.write().unwrap() = plugins;
+        count
+    }
+
+    pub fn list(&self) -> Vec<SpiderPlugin> {
+        self.plugins.read().unwrap().clone()
+    }
+
+    pub fn get(&self, id: &str) -> Result<SpiderPlugin, String> {
+        self.plugins
+            .read()
+            .unwrap()
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or_else(|| format!("未找到爬虫插件: {}", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_template(url_template: &str) -> SpiderPlugin {
+        SpiderPlugin {
+            detail: Some(DetailSource {
+                url_template: url_template.to_string(),
+                content_path: String::new(),
+                attachment_extensions: default_attachment_extensions(),
+            }),
+            ..SpiderPlugin::builtin_cnpcbidding()
+        }
+    }
+
+    fn mapped(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn detail_url_fills_template_fields() {
+        let plugin = with_template("https://example.com/notice/{id}?type={project_type}");
+        assert_eq!(
+            plugin.detail_url(&mapped(json!({ "id": 42, "project_type": "0001" }))).as_deref(),
+            Some("https://example.com/notice/42?type=0001")
+        );
+        // 缺少字段或字段为 null 时不生成地址
+        assert_eq!(plugin.detail_url(&mapped(json!({ "id": 42 }))), None);
+        assert_eq!(plugin.detail_url(&mapped(json!({ "id": 42, "project_type": null }))), None);
+        // 映射结果中的 url 优先
+        assert_eq!(
+            plugin.detail_url(&mapped(json!({ "id": 42, "url": " https://example.com/a " }))).as_deref(),
+            Some("https://example.com/a")
+        );
+        assert_eq!(SpiderPlugin::builtin_cnpcbidding().detail_url(&mapped(json!({ "id": 42 }))), None);
+    }
+
+    #[test]
+    fn detail_url_encodes_values_and_does_not_expand_them() {
+        let plugin = with_template("https://example.com/notice/{id}/{title}");
+        let url = plugin.detail_url(&mapped(json!({ "id": "{id}", "title": "a/b?c=d&e 招标" })));
+        assert_eq!(url.as_deref(), Some("https://example.com/notice/%7Bid%7D/a%2Fb%3Fc%3Dd%26e%20%E6%8B%9B%E6%A0%87"));
+
+        let plugin = with_template("https://example.com/{title}");
+        let url = plugin.detail_url(&mapped(json!({ "title": "{title}{title}" })));
+        assert_eq!(url.as_deref(), Some("https://example.com/%7Btitle%7D%7Btitle%7D"));
+    }
+
+    #[test]
+    fn unterminated_placeholder_yields_no_url() {
+        let plugin = with_template("https://example.com/{id");
+        assert_eq!(plugin.detail_url(&mapped(json!({ "id": 1 }))), None);
+    }
+}
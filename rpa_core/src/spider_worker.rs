@@ -0,0 +1,558 @@
+// ============================================================================
+// 常驻爬虫 worker 进程
+// ============================================================================
+//
+// 与 run_automation 一样通过 stdin 发送 JSON，但进程常驻：
+//   - Rust 端启动一次 `python spiders.py --worker`，ddddocr 模型只加载一次
+//   - 每个请求一行 JSON-RPC，按 id 把响应分发给对应的调用方（多路复用）
+//   - 单个请求超时只让该调用方失败；进程崩溃，或空闲时不响应 ping（卡死）才重启
+//   - worker 一次只处理一个请求，有请求在执行时健康检查不发送 ping
+//   - 失败分为环境错误（无法启动、依赖包无法导入，JSON-RPC 错误码 ENVIRONMENT_ERROR_CODE）
+//     和执行错误，由调用方决定是否改用其他后端
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// 单次爬虫请求的超时时间（含验证码重试）
+pub const RUN_TIMEOUT: Duration = Duration::from_secs(180);
+/// 健康检查（ping）的超时时间
+pub const PING_TIMEOUT: Duration = Duration::from_secs(10);
+/// 等待 worker 启动完成（加载 ddddocr 模型）的超时时间
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+/// worker 报告环境错误（依赖包无法导入、找不到 Node.js）使用的 JSON-RPC 错误码，与 spiders.py 一致
+pub const ENVIRONMENT_ERROR_CODE: i64 = -32001;
+
+type Pending = Arc<Mutex<HashMap<u64, mpsc::Sender<Result<Value, WorkerError>>>>>;
+
+/// worker 请求失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkerError {
+    /// 运行环境问题：进程无法启动或启动后立即退出、依赖包无法导入
+    Environment(String),
+    /// 请求已经交给 worker 后失败（网站错误、超时、进程中途退出等）
+    Execution(String),
+}
+
+impl WorkerError {
+    pub fn message(&self) -> &str {
+        match self {
+            WorkerError::Environment(message) | WorkerError::Execution(message) => message,
+        }
+    }
+
+    /// 按 JSON-RPC 错误对象的错误码区分
+    fn from_rpc(error: &Value) -> Self {
+        let message = error.get("message").and_then(Value::as_str).unwrap_or("未知错误").to_string();
+        if error.get("code").and_then(Value::as_i64) == Some(ENVIRONMENT_ERROR_CODE) {
+            WorkerError::Environment(message)
+        } else {
+            WorkerError::Execution(message)
+        }
+    }
+}
+
+impl std::fmt::Display for WorkerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// 启动 worker 所需的命令
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerCommand {
+    pub program: String,
+    pub script: PathBuf,
+    pub cwd: PathBuf,
+    /// 额外的环境变量（如代理设置），变化时 worker 会重启
+    pub env: Vec<(String, String)>,
+}
+
+/// worker 状态，供前端展示
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStatus {
+    pub running: bool,
+    pub pid: Option<u32>,
+    pub restarts: u64,
+    pub in_flight: usize,
+    pub last_error: Option<String>,
+}
+
+struct WorkerProcess {
+    command: WorkerCommand,
+    child: Child,
+    stdin: ChildStdin,
+    pending: Pending,
+    alive: Arc<AtomicBool>,
+}
+
+impl WorkerProcess {
+    fn spawn(command: &WorkerCommand) -> Result<Self, String> {
+        let mut child = Command::new(&command.program)
+            .arg(&command.script)
+            .arg("--worker")
+            .current_dir(&command.cwd)
+            .envs(command.env.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("启动爬虫 worker 失败: {}", e))?;
+
+        let stdin = child.stdin.take().ok_or("无法获取 worker stdin")?;
+        let stdout = child.stdout.take().ok_or("无法获取 worker stdout")?;
+        let stderr = child.stderr.take().ok_or("无法获取 worker stderr")?;
+
+        let pending: Pending = Arc::new(Mutex::new(HashMap::new()));
+        let alive = Arc::new(AtomicBool::new(true));
+        let (ready_tx, ready_rx) = mpsc::channel::<()>();
+
+        // stdout 读取线程：按 id 分发响应
+        {
+            let pending = pending.clone();
+            let alive = alive.clone();
+            std::thread::spawn(move || {
+                let mut ready_tx = Some(ready_tx);
+                for line in BufReader::new(stdout).lines() {
+                    let line = match line {
+                        Ok(line) => line,
+                        Err(_) => break,
+                    };
+                    let message: Value = match serde_json::from_str(&line) {
+                        Ok(v) => v,
+                        Err(_) => {
+                            log_warn!("[爬虫 worker] 忽略无法解析的输出: {}", line);
+                            continue;
+                        }
+                    };
+                    let id = match message.get("id").and_then(Value::as_u64) {
+                        Some(id) => id,
+                        None => {
+                            if message.pointer("/result/ready").is_some() {
+                                if let Some(tx) = ready_tx.take() {
+                                    let _ = tx.send(());
+                                }
+                            } else if let Some(err) = message.get("error") {
+                                log_warn!("[爬虫 worker] 协议错误: {}", err);
+                            }
+                            continue;
+                        }
+                    };
+                    let sender = pending.lock().unwrap().remove(&id);
+                    if let Some(sender) = sender {
+                        let response = match message.get("error") {
+                            Some(err) => Err(WorkerError::from_rpc(err)),
+                            None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                        };
+                        let _ = sender.send(response);
+                    }
+                }
+                // 进程退出：标记为不可用，并让所有等待中的请求失败
+                alive.store(false, Ordering::SeqCst);
+                for (_, sender) in pending.lock().unwrap().drain() {
+                    let _ = sender.send(Err(WorkerError::Execution("爬虫 worker 进程已退出".to_string())));
+                }
+            });
+        }
+
+        // stderr 读取线程：转发日志
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                log_info!("[爬虫 worker] {}", line);
+            }
+        });
+
+        let mut process = WorkerProcess {
+            command: command.clone(),
+            child,
+            stdin,
+            pending,
+            alive,
+        };
+
+        if ready_rx.recv_timeout(READY_TIMEOUT).is_err() {
+            process.kill();
+            return Err("爬虫 worker 启动超时或启动后立即退出（请检查 Python 依赖是否安装）".to_string());
+        }
+
+        log_info!("[爬虫 worker] ✅ worker 已启动，pid={}", process.child.id());
+        Ok(process)
+    }
+
+    fn is_alive(&mut self) -> bool {
+        if !self.alive.load(Ordering::SeqCst) {
+            return false;
+        }
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    fn send(&mut self, id: u64, method: &str, params: &Value) -> Result<mpsc::Receiver<Result<Value, WorkerError>>, String> {
+        let (tx, rx) = mpsc::channel();
+        self.pending.lock().unwrap().insert(id, tx);
+
+        let line = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        })
+        .to_string();
+
+        let written = writeln!(self.stdin, "{}", line).and_then(|_| self.stdin.flush());
+        if let Err(e) = written {
+            self.pending.lock().unwrap().remove(&id);
+            self.alive.store(false, Ordering::SeqCst);
+            return Err(format!("写入 worker 请求失败: {}", e));
+        }
+        Ok(rx)
+    }
+
+    fn kill(&mut self) {
+        self.alive.store(false, Ordering::SeqCst);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Drop for WorkerProcess {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+/// 常驻爬虫 worker，放在 Tauri 的 managed state 中
+pub struct SpiderWorker {
+    process: Mutex<Option<WorkerProcess>>,
+    next_id: AtomicU64,
+    restarts: AtomicU64,
+    last_error: Mutex<Option<String>>,
+    ping_timeout: Duration,
+}
+
+impl Default for SpiderWorker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpiderWorker {
+    pub fn new() -> Self {
+        SpiderWorker {
+            process: Mutex::new(None),
+            next_id: AtomicU64::new(1),
+            restarts: AtomicU64::new(0),
+            last_error: Mutex::new(None),
+            ping_timeout: PING_TIMEOUT,
+        }
+    }
+
+    /// 发送一个请求并等待响应；worker 未启动或已崩溃时会先（重新）启动，启动失败属于环境错误
+    pub fn call(&self, command: &WorkerCommand, method: &str, params: Value, timeout: Duration) -> Result<Value, WorkerError> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+
+        // 只在发送请求时持有锁，等待响应时释放，以便多个调用方共用同一个进程
+        let rx = {
+            let mut guard = self.process.lock().unwrap();
+            self.ensure_running(&mut guard, command).map_err(WorkerError::Environment)?;
+            let process = guard.as_mut().unwrap();
+            process.send(id, method, &params).map_err(WorkerError::Execution)?
+        };
+
+        match rx.recv_timeout(timeout) {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(e)) => Err(e),
+            Err(_) => {
+                // 超时：只让本次调用失败，其他调用方的请求继续等待；
+                // worker 真的卡死时由健康检查在空闲时发现并重启
+                let message = format!("爬虫 worker 响应超时（{} 秒）", timeout.as_secs());
+                self.record_error(&message);
+                if let Some(process) = self.process.lock().unwrap().as_ref() {
+                    process.pending.lock().unwrap().remove(&id);
+                }
+                Err(WorkerError::Execution(message))
+            }
+        }
+    }
+
+    /// 是否有请求正在等待响应
+    fn is_busy(&self) -> bool {
+        self.process
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|process| !process.pending.lock().unwrap().is_empty())
+    }
+
+    /// 健康检查：空闲的 worker 不响应 ping 时重启；ping 排在其他请求之后超时不算失败
+    pub fn health_check(&self, command: &WorkerCommand) -> Result<(), String> {
+        match self.call(command, "ping", Value::Null, self.ping_timeout) {
+            Ok(_) => Ok(()),
+            Err(e) if self.is_busy() => {
+                log_info!("[爬虫 worker] ping 未及时响应（{}），worker 正在处理其他请求，暂不重启", e);
+                Ok(())
+            }
+            Err(e) => {
+                log_warn!("[爬虫 worker] 健康检查失败: {}，重启 worker", e);
+                self.restart(command)
+            }
+        }
+    }
+
+    /// 仅在 worker 已运行且空闲时做健康检查，避免空闲时无谓地启动进程，也避免 ping 排在长时间的爬虫请求之后
+    pub fn health_check_if_running(&self, command: &WorkerCommand) -> Result<(), String> {
+        let running = {
+            let mut guard = self.process.lock().unwrap();
+            match guard.as_mut() {
+                Some(process) => process.is_alive(),
+                None => return Ok(()),
+            }
+        };
+        if !running {
+            return self.restart(command);
+        }
+        if self.is_busy() {
+            return Ok(());
+        }
+        self.health_check(command)
+    }
+
+    pub fn restart(&self, command: &WorkerCommand) -> Result<(), String> {
+        let mut guard = self.process.lock().unwrap();
+        if guard.take().is_some() {
+            self.restarts.fetch_add(1, Ordering::SeqCst);
+        }
+        self.ensure_running(&mut guard, command)
+    }
+
+    pub fn shutdown(&self) {
+        let mut guard = self.process.lock().unwrap();
+        if let Some(process) = guard.as_mut() {
+            let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+            if let Ok(rx) = process.send(id, "shutdown", &Value::Null) {
+                let _ = rx.recv_timeout(Duration::from_secs(2));
+            }
+        }
+        *guard = None;
+    }
+
+    pub fn status(&self) -> WorkerStatus {
+        let mut guard = self.process.lock().unwrap();
+        let (running, pid, in_flight) = match guard.as_mut() {
+            Some(process) => {
+                let running = process.is_alive();
+                (running, Some(process.child.id()), process.pending.lock().unwrap().len())
+            }
+            None => (false, None, 0),
+        };
+        WorkerStatus {
+            running,
+            pid: if running { pid } else { None },
+            restarts: self.restarts.load(Ordering::SeqCst),
+            in_flight,
+            last_error: self.last_error.lock().unwrap().clone(),
+        }
+    }
+
+    fn ensure_running(&self, guard: &mut Option<WorkerProcess>, command: &WorkerCommand) -> Result<(), String> {
+        if let Some(process) = guard.as_mut() {
+            if process.is_alive() && process.command == *command {
+                return Ok(());
+            }
+            // 进程已崩溃，或解释器/脚本路径/环境变量发生变化，需要重启
+            self.restarts.fetch_add(1, Ordering::SeqCst);
+            log_info!("[爬虫 worker] 🔄 重启 worker");
+        }
+        *guard = None;
+
+        match WorkerProcess::spawn(command) {
+            Ok(process) => {
+                *guard = Some(process);
+                Ok(())
+            }
+            Err(e) => {
+                self.record_error(&e);
+                Err(e)
+            }
+        }
+    }
+
+    fn record_error(&self, message: &str) {
+        *self.last_error.lock().unwrap() = Some(message.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Instant;
+
+    /// 与 spiders.py run_worker 相同协议的假 worker：run 按 params.delay 秒后返回参数，
+    /// 带 params.error_code 时以该错误码失败
+    const STUB_WORKER: &str = r#"
+import json, sys, time
+
+def reply(request_id, result=None, error=None):
+    message = {"jsonrpc": "2.0", "id": request_id}
+    if error is not None:
+        message["error"] = error
+    else:
+        message["result"] = result
+    sys.stdout.write(json.dumps(message) + "\n")
+    sys.stdout.flush()
+
+reply(None, {"ready": True})
+for line in sys.stdin:
+    request = json.loads(line)
+    method = request.get("method")
+    if method == "ping":
+        reply(request["id"], {"pong": True})
+    elif method == "shutdown":
+        reply(request["id"], {"shutdown": True})
+        break
+    elif method == "run":
+        params = request["params"]
+        if "error_code" in params:
+            reply(request["id"], error={"code": params["error_code"], "message": "失败原因"})
+            continue
+        time.sleep(params.get("delay", 0))
+        reply(request["id"], params)
+"#;
+
+    struct StubWorker {
+        dir: PathBuf,
+        command: WorkerCommand,
+    }
+
+    impl Drop for StubWorker {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    /// 系统没有 Python 时返回 None，测试跳过
+    fn stub_worker() -> Option<StubWorker> {
+        let program = ["python3", "python"].into_iter().find(|program| {
+            Command::new(program).arg("--version").output().is_ok_and(|output| output.status.success())
+        })?;
+        let dir = std::env::temp_dir().join(format!("spider_worker_test_{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("stub_worker.py");
+        std::fs::write(&script, STUB_WORKER).unwrap();
+        let command = WorkerCommand {
+            program: program.to_string(),
+            script,
+            cwd: dir.clone(),
+            env: Vec::new(),
+        };
+        Some(StubWorker { dir, command })
+    }
+
+    fn worker() -> SpiderWorker {
+        SpiderWorker {
+            ping_timeout: Duration::from_millis(300),
+            ..SpiderWorker::new()
+        }
+    }
+
+    fn wait_until(condition: impl Fn() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !condition() {
+            assert!(Instant::now() < deadline, "等待超时");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn run_in_background(worker: &Arc<SpiderWorker>, command: &WorkerCommand, delay: f64) -> std::thread::JoinHandle<Result<Value, WorkerError>> {
+        let worker = worker.clone();
+        let command = command.clone();
+        std::thread::spawn(move || worker.call(&command, "run", json!({ "delay": delay }), Duration::from_secs(30)))
+    }
+
+    #[test]
+    fn health_check_does_not_interrupt_slow_request() {
+        let Some(stub) = stub_worker() else { return };
+        let worker = Arc::new(worker());
+        worker.restart(&stub.command).unwrap();
+        let pid = worker.status().pid;
+
+        let slow = run_in_background(&worker, &stub.command, 1.5);
+        wait_until(|| worker.status().in_flight == 1);
+
+        // 定时健康检查跳过正在处理请求的 worker
+        worker.health_check_if_running(&stub.command).unwrap();
+        // 直接 ping 会排在慢请求之后超时，也不能因此重启
+        worker.health_check(&stub.command).unwrap();
+
+        assert_eq!(slow.join().unwrap().unwrap(), json!({ "delay": 1.5 }));
+        let status = worker.status();
+        assert_eq!(status.pid, pid);
+        assert_eq!(status.restarts, 0);
+    }
+
+    #[test]
+    fn timeout_fails_only_the_waiting_caller() {
+        let Some(stub) = stub_worker() else { return };
+        let worker = Arc::new(worker());
+
+        let slow = run_in_background(&worker, &stub.command, 1.0);
+        wait_until(|| worker.status().in_flight == 1);
+        let pid = worker.status().pid;
+
+        let error = worker.call(&stub.command, "run", json!({ "delay": 0 }), Duration::from_millis(200)).unwrap_err();
+        assert!(matches!(&error, WorkerError::Execution(message) if message.contains("超时")), "{:?}", error);
+
+        // 另一个调用方的请求不受影响，进程也没有重启
+        assert_eq!(slow.join().unwrap().unwrap(), json!({ "delay": 1.0 }));
+        worker.call(&stub.command, "ping", Value::Null, Duration::from_secs(5)).unwrap();
+        let status = worker.status();
+        assert_eq!(status.pid, pid);
+        assert_eq!(status.restarts, 0);
+    }
+
+    #[test]
+    fn idle_worker_that_stops_answering_is_restarted() {
+        let Some(stub) = stub_worker() else { return };
+        let worker = worker();
+        worker.restart(&stub.command).unwrap();
+        let pid = worker.status().pid;
+
+        // 调用方放弃等待后 worker 仍在处理，从 Rust 端看是空闲的，但 ping 不会响应
+        worker.call(&stub.command, "run", json!({ "delay": 5 }), Duration::from_millis(100)).unwrap_err();
+        worker.health_check(&stub.command).unwrap();
+
+        let status = worker.status();
+        assert!(status.running);
+        assert_ne!(status.pid, pid);
+        assert_eq!(status.restarts, 1);
+        worker.call(&stub.command, "ping", Value::Null, Duration::from_secs(5)).unwrap();
+    }
+
+    #[test]
+    fn error_kind_follows_protocol_error_code() {
+        let Some(stub) = stub_worker() else { return };
+        let worker = worker();
+
+        let call = |code: i64| worker.call(&stub.command, "run", json!({ "error_code": code }), Duration::from_secs(5)).unwrap_err();
+        assert_eq!(call(ENVIRONMENT_ERROR_CODE), WorkerError::Environment("失败原因".to_string()));
+        assert_eq!(call(-32000), WorkerError::Execution("失败原因".to_string()));
+        // 执行失败不影响 worker 继续处理请求
+        assert_eq!(worker.status().restarts, 0);
+    }
+
+    #[test]
+    fn worker_that_cannot_start_is_an_environment_error() {
+        let Some(stub) = stub_worker() else { return };
+        let command = WorkerCommand { program: stub.dir.join("no-such-python").display().to_string(), ..stub.command.clone() };
+
+        let error = worker().call(&command, "ping", Value::Null, Duration::from_secs(5)).unwrap_err();
+        assert!(matches!(error, WorkerError::Environment(_)), "{:?}", error);
+    }
+}
@@ -0,0 +1,908 @@
+// ============================================================================
+// 本地公告数据库（SQLite + FTS5 全文检索）
+// ============================================================================
+//
+// 每次爬取到的公告都 upsert 到应用数据目录的 announcements.db 中，
+// 发送邮件后数据不会丢失，可以离线检索历史公示。
+//
+// FTS5 自带的 unicode61 分词器会把连续的中文当成一个词，无法检索其中的片段，
+// 因此入库和查询前都先做二元切分（bigram）："中标候选人" -> "中标 标候 候选 选人"
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::Local;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::candidate_extract::CandidateNotice;
+
+pub const DB_FILE: &str = "announcements.db";
+/// 手动执行的任务标识
+pub const MANUAL_JOB: &str = "manual";
+/// 检索每页条数上限
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS announcements (
+    id INTEGER PRIMARY KEY,
+    spider_id TEXT NOT NULL,
+    source_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL DEFAULT '',
+    project_type TEXT,
+    publish_date TEXT,
+    url TEXT,
+    raw_json TEXT NOT NULL,
+    job TEXT NOT NULL,
+    run_id TEXT NOT NULL,
+    first_seen_at TEXT NOT NULL,
+    last_seen_at TEXT NOT NULL,
+    UNIQUE (spider_id, source_id)
+);
+CREATE INDEX IF NOT EXISTS idx_announcements_publish_date ON announcements (publish_date);
+CREATE INDEX IF NOT EXISTS idx_announcements_job ON announcements (job);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS announcements_fts USING fts5 (
+    title, content, tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TABLE IF NOT EXISTS runs (
+    run_id TEXT PRIMARY KEY,
+    spider_id TEXT NOT NULL,
+    job TEXT NOT NULL,
+    params_json TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    finished_at TEXT,
+    status TEXT NOT NULL,
+    item_count INTEGER NOT NULL DEFAULT 0,
+    new_count INTEGER NOT NULL DEFAULT 0,
+    error TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_runs_started_at ON runs (started_at);
+"#;
+
+/// 增量迁移，按 PRAGMA user_version 依次执行
+const MIGRATIONS: &[&str] = &[
+    // 1: 详情页与附件
+    r#"
+    ALTER TABLE announcements ADD COLUMN detail_url TEXT;
+    ALTER TABLE announcements ADD COLUMN detail_sha256 TEXT;
+    ALTER TABLE announcements ADD COLUMN detail_path TEXT;
+    ALTER TABLE announcements ADD COLUMN detail_fetched_at TEXT;
+    ALTER TABLE announcements ADD COLUMN detail_error TEXT;
+    CREATE TABLE IF NOT EXISTS attachments (
+        id INTEGER PRIMARY KEY,
+        announcement_id INTEGER NOT NULL REFERENCES announcements (id) ON DELETE CASCADE,
+        url TEXT NOT NULL,
+        file_name TEXT NOT NULL,
+        sha256 TEXT NOT NULL,
+        size INTEGER NOT NULL,
+        path TEXT NOT NULL,
+        fetched_at TEXT NOT NULL,
+        UNIQUE (announcement_id, url)
+    );
+    "#,
+    // 2: 中标候选人结构化数据
+    r#"
+    CREATE TABLE IF NOT EXISTS candidate_notices (
+        announcement_id INTEGER PRIMARY KEY REFERENCES announcements (id) ON DELETE CASCADE,
+        notice_json TEXT NOT NULL,
+        extracted_at TEXT NOT NULL
+    );
+    "#,
+];
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{3040}'..='\u{30FF}'
+        | '\u{AC00}'..='\u{D7AF}')
+}
+
+/// 切分文本：连续的中日韩字符切成二元组，其他字母数字按词保留（转小写）
+pub fn bigram_tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut cjk_run: Vec<char> = Vec::new();
+    let mut word = String::new();
+
+    fn flush_cjk(run: &mut Vec<char>, tokens: &mut Vec<String>) {
+        match run.len() {
+            0 => {}
+            1 => tokens.push(run[0].to_string()),
+            _ => tokens.extend(run.windows(2).map(|w| w.iter().collect::<String>())),
+        }
+        run.clear();
+    }
+    fn flush_word(word: &mut String, tokens: &mut Vec<String>) {
+        if !word.is_empty() {
+            tokens.push(word.to_lowercase());
+            word.clear();
+        }
+    }
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            flush_word(&mut word, &mut tokens);
+            cjk_run.push(c);
+        } else if c.is_alphanumeric() {
+            flush_cjk(&mut cjk_run, &mut tokens);
+            word.push(c);
+        } else {
+            flush_cjk(&mut cjk_run, &mut tokens);
+            flush_word(&mut word, &mut tokens);
+        }
+    }
+    flush_cjk(&mut cjk_run, &mut tokens);
+    flush_word(&mut word, &mut tokens);
+    tokens
+}
+
+fn index_text(text: &str) -> String {
+    bigram_tokens(text).join(" ")
+}
+
+/// 把用户输入转换成 FTS5 查询：每个词都要命中（AND），单个汉字按前缀匹配
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .flat_map(|part| {
+            let tokens = bigram_tokens(part);
+            let single_cjk = tokens.len() == 1 && tokens[0].chars().count() == 1 && tokens[0].chars().all(is_cjk);
+            tokens.into_iter().map(move |t| {
+                let quoted = format!("\"{}\"", t.replace('"', "\"\""));
+                if single_cjk {
+                    format!("{}*", quoted)
+                } else {
+                    quoted
+                }
+            })
+        })
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" AND "))
+    }
+}
+
+/// 截取包含第一个查询词的片段
+fn make_snippet(text: &str, query: &str) -> String {
+    const RADIUS: usize = 40;
+    let chars: Vec<char> = text.chars().collect();
+    let lower = text.to_lowercase();
+    let position = query
+        .split_whitespace()
+        .filter_map(|term| lower.find(&term.to_lowercase()))
+        .min()
+        .map(|byte_index| lower[..byte_index].chars().count())
+        .unwrap_or(0);
+    let start = position.saturating_sub(RADIUS);
+    let end = (position + RADIUS).min(chars.len());
+    let mut snippet: String = chars[start..end].iter().collect();
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+fn now_string() -> String {
+    Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn value_to_text(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Null => None,
+        Value::String(s) if s.trim().is_empty() => None,
+        Value::String(s) => Some(s.trim().to_string()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// 一条待入库的公告（原始行 + 映射后的统一字段）
+pub struct NewAnnouncement<'a> {
+    pub row: &'a Value,
+    pub mapped: &'a Map<String, Value>,
+    /// 详情页地址，插件不支持详情抓取时为空
+    pub detail_url: Option<String>,
+}
+
+/// 已归档的附件
+#[derive(Serialize, Debug, Clone)]
+pub struct AttachmentRecord {
+    pub url: String,
+    pub file_name: String,
+    pub sha256: String,
+    pub size: u64,
+    /// 归档文件的本地路径
+    pub path: String,
+}
+
+/// 抓取到的详情页
+#[derive(Debug, Clone)]
+pub struct DetailRecord {
+    pub url: String,
+    pub sha256: String,
+    pub path: String,
+    /// 从详情页提取的纯文本，写入正文并参与全文检索
+    pub text: String,
+    pub attachments: Vec<AttachmentRecord>,
+}
+
+/// 搜索过滤条件
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct SearchFilters {
+    #[serde(default)]
+    pub spider_id: Option<String>,
+    #[serde(default)]
+    pub job: Option<String>,
+    #[serde(default)]
+    pub project_type: Option<String>,
+    /// 发布日期下限（含），格式 YYYY-MM-DD
+    #[serde(default)]
+    pub date_from: Option<String>,
+    /// 发布日期上限（含），格式 YYYY-MM-DD
+    #[serde(default)]
+    pub date_to: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AnnouncementSummary {
+    pub id: i64,
+    pub spider_id: String,
+    pub title: String,
+    pub snippet: String,
+    pub project_type: Option<String>,
+    pub publish_date: Option<String>,
+    pub url: Option<String>,
+    pub detail_url: Option<String>,
+    pub attachment_count: i64,
+    pub job: String,
+    pub first_seen_at: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SearchPage {
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+    pub items: Vec<AnnouncementSummary>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Announcement {
+    pub id: i64,
+    pub spider_id: String,
+    pub source_id: String,
+    pub title: String,
+    pub content: String,
+    pub project_type: Option<String>,
+    pub publish_date: Option<String>,
+    pub url: Option<String>,
+    pub raw: Value,
+    pub job: String,
+    pub run_id: String,
+    pub first_seen_at: String,
+    pub last_seen_at: String,
+    pub detail_url: Option<String>,
+    /// 归档的详情页文件
+    pub detail_path: Option<String>,
+    pub detail_fetched_at: Option<String>,
+    pub detail_error: Option<String>,
+    pub attachments: Vec<AttachmentRecord>,
+    /// 从详情页提取的中标候选人信息
+    pub candidate_notice: Option<CandidateNotice>,
+}
+
+/// 一次爬虫执行的记录
+#[derive(Serialize, Debug, Clone)]
+pub struct RunRecord {
+    pub run_id: String,
+    pub spider_id: String,
+    pub job: String,
+    pub params: Value,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub status: String,
+    pub item_count: i64,
+    pub new_count: i64,
+    pub error: Option<String>,
+}
+
+/// 公告数据库，放在 Tauri 的 managed state 中
+pub struct AnnouncementStore {
+    conn: Mutex<Connection>,
+}
+
+impl AnnouncementStore {
+    pub fn open(app_data_dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(app_data_dir)
+            .map_err(|e| format!("无法创建目录: {}", e))?;
+        let conn = Connection::open(app_data_dir.join(DB_FILE))
+            .map_err(|e| format!("打开公告数据库失败: {}", e))?;
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self, String> {
+        let conn = Connection::open_in_memory()
+            .map_err(|e| format!("打开公告数据库失败: {}", e))?;
+        Self::init(conn)
+    }
+
+    fn init(conn: Connection) -> Result<Self, String> {
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| format!("初始化公告数据库失败: {}", e))?;
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("初始化公告数据库失败: {}", e))?;
+        migrate(&conn).map_err(|e| format!("升级公告数据库失败: {}", e))?;
+        Ok(AnnouncementStore { conn: Mutex::new(conn) })
+    }
+
+    /// 记录一次执行开始
+    pub fn begin_run(&self, run_id: &str, spider_id: &str, job: &str, params: &Value) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO runs (run_id, spider_id, job, params_json, started_at, status) VALUES (?1, ?2, ?3, ?4, ?5, 'running')",
+            params![run_id, spider_id, job, params.to_string(), now_string()],
+        )
+        .map_err(|e| format!("记录执行历史失败: {}", e))?;
+        Ok(())
+    }
+
+    /// 记录一次执行结束
+    pub fn finish_run(&self, run_id: &str, result: Result<(usize, usize), &str>) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        let (status, item_count, new_count, error) = match result {
+            Ok((items, new)) => ("success", items as i64, new as i64, None),
+            Err(e) => ("error", 0, 0, Some(e)),
+        };
+        conn.execute(
+            "UPDATE runs SET finished_at = ?2, status = ?3, item_count = ?4, new_count = ?5, error = ?6 WHERE run_id = ?1",
+            params![run_id, now_string(), status, item_count, new_count, error],
+        )
+        .map_err(|e| format!("记录执行历史失败: {}", e))?;
+        Ok(())
+    }
+
+    /// 批量 upsert 公告，返回新增的公告 ID 列表
+    pub fn upsert_announcements(
+        &self,
+        spider_id: &str,
+        job: &str,
+        run_id: &str,
+        items: &[NewAnnouncement<'_>],
+    ) -> Result<Vec<i64>, String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(|e| format!("写入公告失败: {}", e))?;
+        let now = now_string();
+        let mut new_ids = Vec::new();
+
+        for item in items {
+            let title = match value_to_text(item.mapped.get("title")) {
+                Some(title) => title,
+                None => continue,
+            };
+            let content = value_to_text(item.mapped.get("content")).unwrap_or_default();
+            let publish_date = value_to_text(item.mapped.get("publish_date"));
+            let source_id = value_to_text(item.mapped.get("id")).unwrap_or_else(|| {
+                // 没有 ID 时用标题 + 发布日期的哈希去重
+                let digest = Sha256::digest(format!("{}|{}", title, publish_date.clone().unwrap_or_default()));
+                hex::encode(&digest[..16])
+            });
+
+            let existing: Option<i64> = tx
+                .query_row(
+                    "SELECT id FROM announcements WHERE spider_id = ?1 AND source_id = ?2",
+                    params![spider_id, source_id],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| format!("写入公告失败: {}", e))?;
+
+            let id = tx
+                .query_row(
+                    "INSERT INTO announcements
+                        (spider_id, source_id, title, content, project_type, publish_date, url, raw_json, job, run_id, first_seen_at, last_seen_at, detail_url)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11, ?12)
+                     ON CONFLICT (spider_id, source_id) DO UPDATE SET
+                        title = excluded.title,
+                        content = CASE WHEN excluded.content = '' THEN announcements.content ELSE excluded.content END,
+                        project_type = excluded.project_type,
+                        publish_date = excluded.publish_date,
+                        url = COALESCE(excluded.url, announcements.url),
+                        raw_json = excluded.raw_json,
+                        last_seen_at = excluded.last_seen_at,
+                        detail_url = COALESCE(announcements.detail_url, excluded.detail_url)
+                     RETURNING id, content",
+                    params![
+                        spider_id,
+                        source_id,
+                        title,
+                        content,
+                        value_to_text(item.mapped.get("project_type")),
+                        publish_date,
+                        value_to_text(item.mapped.get("url")),
+                        item.row.to_string(),
+                        job,
+                        run_id,
+                        now,
+                        item.detail_url,
+                    ],
+                    |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+                )
+                .map_err(|e| format!("写入公告失败: {}", e))?;
+
+            reindex(&tx, id.0, &title, &id.1)?;
+            if existing.is_none() {
+                new_ids.push(id.0);
+            }
+        }
+
+        tx.commit().map_err(|e| format!("写入公告失败: {}", e))?;
+        Ok(new_ids)
+    }
+
+    /// 全文检索
+    pub fn search(&self, query: &str, filters: &SearchFilters, page: u32, page_size: u32) -> Result<SearchPage, String> {
+        let page = page.max(1);
+        let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+        let QueryParts { from, where_clause, order, args } = build_query(query, filters);
+
+        let conn = self.conn.lock().unwrap();
+        let total: i64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM {} {}", from, where_clause),
+                params_from_iter(args.iter()),
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("检索公告失败: {}", e))?;
+
+        let mut page_args = args.clone();
+        page_args.push(SqlValue::Integer(page_size as i64));
+        // 在 i64 中计算，页码很大时 u32 乘法会溢出
+        page_args.push(SqlValue::Integer((page as i64 - 1) * page_size as i64));
+        let sql = format!(
+            "SELECT a.id, a.spider_id, a.title, a.content, a.project_type, a.publish_date, a.url, a.job, a.first_seen_at, a.detail_url,
+                (SELECT COUNT(*) FROM attachments t WHERE t.announcement_id = a.id)
+             FROM {} {} ORDER BY {} LIMIT ? OFFSET ?",
+            from, where_clause, order
+        );
+        let mut stmt = conn.prepare(&sql).map_err(|e| format!("检索公告失败: {}", e))?;
+        let items = stmt
+            .query_map(params_from_iter(page_args.iter()), |row| {
+                let title: String = row.get(2)?;
+                let content: String = row.get(3)?;
+                let snippet_source = if content.is_empty() { &title } else { &content };
+                Ok(AnnouncementSummary {
+                    id: row.get(0)?,
+                    spider_id: row.get(1)?,
+                    snippet: make_snippet(snippet_source, query),
+                    title,
+                    project_type: row.get(4)?,
+                    publish_date: row.get(5)?,
+                    url: row.get(6)?,
+                    detail_url: row.get(9)?,
+                    attachment_count: row.get(10)?,
+                    job: row.get(7)?,
+                    first_seen_at: row.get(8)?,
+                })
+            })
+            .map_err(|e| format!("检索公告失败: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("检索公告失败: {}", e))?;
+
+        Ok(SearchPage {
+            total: total as u64,
+            page,
+            page_size,
+            items,
+        })
+    }
+
+    pub fn get_announcement(&self, id: i64) -> Result<Option<Announcement>, String> {
+        let conn = self.conn.lock().unwrap();
+        let announcement = conn
+            .query_row(
+                &format!("SELECT {} FROM announcements a WHERE a.id = ?1", ANNOUNCEMENT_COLUMNS),
+                params![id],
+                read_announcement,
+            )
+            .optional()
+            .map_err(|e| format!("读取公告失败: {}", e))?;
+        match announcement {
+            Some(mut announcement) => {
+                announcement.attachments = load_attachments(&conn, id)?;
+                announcement.candidate_notice = load_candidate_notice(&conn, id)?;
+                Ok(Some(announcement))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 在给定的公告中筛选出有详情页地址的，返回 (公告 ID, 详情页地址)
+    pub fn detail_targets(&self, ids: &[i64]) -> Result<Vec<(i64, String)>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT detail_url FROM announcements WHERE id = ?1 AND detail_url IS NOT NULL")
+            .map_err(|e| format!("读取公告失败: {}", e))?;
+        let mut targets = Vec::new();
+        for id in ids {
+            let url: Option<String> = stmt
+                .query_row(params![id], |row| row.get(0))
+                .optional()
+                .map_err(|e| format!("读取公告失败: {}", e))?;
+            if let Some(url) = url {
+                targets.push((*id, url));
+            }
+        }
+        Ok(targets)
+    }
+
+    /// 保存详情页：正文替换为详情页文本并重建全文索引，附件按 URL 去重
+    pub fn save_detail(&self, id: i64, detail: &DetailRecord) -> Result<(), String> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(|e| format!("保存详情失败: {}", e))?;
+        let now = now_string();
+        let title: String = tx
+            .query_row(
+                "UPDATE announcements SET content = ?2, detail_url = ?3, detail_sha256 = ?4, detail_path = ?5,
+                    detail_fetched_at = ?6, detail_error = NULL
+                 WHERE id = ?1 RETURNING title",
+                params![id, detail.text, detail.url, detail.sha256, detail.path, now],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("保存详情失败: {}", e))?;
+        reindex(&tx, id, &title, &detail.text)?;
+        for attachment in &detail.attachments {
+            tx.execute(
+                "INSERT INTO attachments (announcement_id, url, file_name, sha256, size, path, fetched_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (announcement_id, url) DO UPDATE SET
+                    file_name = excluded.file_name, sha256 = excluded.sha256, size = excluded.size,
+                    path = excluded.path, fetched_at = excluded.fetched_at",
+                params![
+                    id,
+                    attachment.url,
+                    attachment.file_name,
+                    attachment.sha256,
+                    attachment.size as i64,
+                    attachment.path,
+                    now,
+                ],
+            )
+            .map_err(|e| format!("保存附件记录失败: {}", e))?;
+        }
+        tx.commit().map_err(|e| format!("保存详情失败: {}", e))
+    }
+
+    /// 保存提取出的中标候选人信息，None 表示清除之前的结果
+    pub fn save_candidate_notice(&self, id: i64, notice: Option<&CandidateNotice>) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        let result = match notice {
+            Some(notice) => {
+                let notice_json = serde_json::to_string(notice)
+                    .map_err(|e| format!("序列化候选人信息失败: {}", e))?;
+                conn.execute(
+                    "INSERT INTO candidate_notices (announcement_id, notice_json, extracted_at) VALUES (?1, ?2, ?3)
+                     ON CONFLICT (announcement_id) DO UPDATE SET
+                        notice_json = excluded.notice_json, extracted_at = excluded.extracted_at",
+                    params![id, notice_json, now_string()],
+                )
+            }
+            None => conn.execute("DELETE FROM candidate_notices WHERE announcement_id = ?1", params![id]),
+        };
+        result.map_err(|e| format!("保存候选人信息失败: {}", e))?;
+        Ok(())
+    }
+
+    /// 记录详情抓取失败的原因，下次可以重新抓取
+    pub fn save_detail_error(&self, id: i64, error: &str) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE announcements SET detail_error = ?2 WHERE id = ?1",
+            params![id, error],
+        )
+        .map_err(|e| format!("保存详情失败: {}", e))?;
+        Ok(())
+    }
+
+    /// 逐行遍历符合条件的公告（不分页、不整体载入内存），用于导出；返回遍历的行数
+    pub fn for_each_announcement<F>(&self, query: &str, filters: &SearchFilters, mut f: F) -> Result<usize, String>
+    where
+        F: FnMut(&Announcement) -> Result<(), String>,
+    {
+        let QueryParts { from, where_clause, order, args } = build_query(query, filters);
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM {} {} ORDER BY {}",
+                ANNOUNCEMENT_COLUMNS, from, where_clause, order
+            ))
+            .map_err(|e| format!("读取公告失败: {}", e))?;
+        let mut rows = stmt
+            .query(params_from_iter(args.iter()))
+            .map_err(|e| format!("读取公告失败: {}", e))?;
+
+        let mut count = 0;
+        while let Some(row) = rows.next().map_err(|e| format!("读取公告失败: {}", e))? {
+            let mut announcement = read_announcement(row).map_err(|e| format!("读取公告失败: {}", e))?;
+            announcement.attachments = load_attachments(&conn, announcement.id)?;
+            announcement.candidate_notice = load_candidate_notice(&conn, announcement.id)?;
+            f(&announcement)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// 最近的执行历史
+    pub fn list_runs(&self, limit: u32) -> Result<Vec<RunRecord>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT run_id, spider_id, job, params_json, started_at, finished_at, status, item_count, new_count, error
+                 FROM runs ORDER BY started_at DESC LIMIT ?1",
+            )
+            .map_err(|e| format!("读取执行历史失败: {}", e))?;
+        let runs = stmt
+            .query_map(params![limit.clamp(1, 1000)], |row| {
+                let params_json: String = row.get(3)?;
+                Ok(RunRecord {
+                    run_id: row.get(0)?,
+                    spider_id: row.get(1)?,
+                    job: row.get(2)?,
+                    params: serde_json::from_str(&params_json).unwrap_or(Value::Null),
+                    started_at: row.get(4)?,
+                    finished_at: row.get(5)?,
+                    status: row.get(6)?,
+                    item_count: row.get(7)?,
+                    new_count: row.get(8)?,
+                    error: row.get(9)?,
+                })
+            })
+            .map_err(|e| format!("读取执行历史失败: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("读取执行历史失败: {}", e))?;
+        Ok(runs)
+    }
+}
+
+const ANNOUNCEMENT_COLUMNS: &str = "a.id, a.spider_id, a.source_id, a.title, a.content, a.project_type, a.publish_date, a.url, \
+     a.raw_json, a.job, a.run_id, a.first_seen_at, a.last_seen_at, a.detail_url, a.detail_path, a.detail_fetched_at, a.detail_error";
+
+fn read_announcement(row: &rusqlite::Row<'_>) -> rusqlite::Result<Announcement> {
+    let raw_json: String = row.get(8)?;
+    Ok(Announcement {
+        id: row.get(0)?,
+        spider_id: row.get(1)?,
+        source_id: row.get(2)?,
+        title: row.get(3)?,
+        content: row.get(4)?,
+        project_type: row.get(5)?,
+        publish_date: row.get(6)?,
+        url: row.get(7)?,
+        raw: serde_json::from_str(&raw_json).unwrap_or(Value::String(raw_json)),
+        job: row.get(9)?,
+        run_id: row.get(10)?,
+        first_seen_at: row.get(11)?,
+        last_seen_at: row.get(12)?,
+        detail_url: row.get(13)?,
+        detail_path: row.get(14)?,
+        detail_fetched_at: row.get(15)?,
+        detail_error: row.get(16)?,
+        attachments: Vec::new(),
+        candidate_notice: None,
+    })
+}
+
+fn load_candidate_notice(conn: &Connection, announcement_id: i64) -> Result<Option<CandidateNotice>, String> {
+    let notice_json: Option<String> = conn
+        .prepare_cached("SELECT notice_json FROM candidate_notices WHERE announcement_id = ?1")
+        .and_then(|mut stmt| stmt.query_row(params![announcement_id], |row| row.get(0)).optional())
+        .map_err(|e| format!("读取候选人信息失败: {}", e))?;
+    match notice_json {
+        Some(json) => serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| format!("解析候选人信息失败: {}", e)),
+        None => Ok(None),
+    }
+}
+
+fn load_attachments(conn: &Connection, announcement_id: i64) -> Result<Vec<AttachmentRecord>, String> {
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT url, file_name, sha256, size, path FROM attachments WHERE announcement_id = ?1 ORDER BY id",
+        )
+        .map_err(|e| format!("读取附件记录失败: {}", e))?;
+    let attachments = stmt
+        .query_map(params![announcement_id], |row| {
+            Ok(AttachmentRecord {
+                url: row.get(0)?,
+                file_name: row.get(1)?,
+                sha256: row.get(2)?,
+                size: row.get::<_, i64>(3)? as u64,
+                path: row.get(4)?,
+            })
+        })
+        .map_err(|e| format!("读取附件记录失败: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("读取附件记录失败: {}", e))?;
+    Ok(attachments)
+}
+
+/// 依次执行尚未执行的迁移
+fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        conn.execute_batch(&format!("BEGIN; {} PRAGMA user_version = {}; COMMIT;", migration, index + 1))?;
+    }
+    Ok(())
+}
+
+/// 检索语句的 FROM / WHERE / ORDER BY 部分及参数
+struct QueryParts {
+    from: String,
+    where_clause: String,
+    order: String,
+    args: Vec<SqlValue>,
+}
+
+fn build_query(query: &str, filters: &SearchFilters) -> QueryParts {
+    let mut conditions = Vec::new();
+    let mut args: Vec<SqlValue> = Vec::new();
+    let mut from = "announcements a".to_string();
+    let mut order = "COALESCE(a.publish_date, a.first_seen_at) DESC, a.id DESC".to_string();
+
+    if let Some(fts) = fts_query(query) {
+        from.push_str(" JOIN announcements_fts f ON f.rowid = a.id");
+        conditions.push("announcements_fts MATCH ?".to_string());
+        args.push(SqlValue::Text(fts));
+        order = "bm25(announcements_fts) ASC, a.id DESC".to_string();
+    }
+    push_filters(filters, &mut conditions, &mut args);
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+    QueryParts { from, where_clause, order, args }
+}
+
+/// 更新一条公告的全文索引
+fn reindex(conn: &Connection, id: i64, title: &str, content: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM announcements_fts WHERE rowid = ?1", params![id])
+        .map_err(|e| format!("更新全文索引失败: {}", e))?;
+    conn.execute(
+        "INSERT INTO announcements_fts (rowid, title, content) VALUES (?1, ?2, ?3)",
+        params![id, index_text(title), index_text(content)],
+    )
+    .map_err(|e| format!("更新全文索引失败: {}", e))?;
+    Ok(())
+}
+
+fn push_filters(filters: &SearchFilters, conditions: &mut Vec<String>, args: &mut Vec<SqlValue>) {
+    let non_empty = |v: &Option<String>| v.as_ref().map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+
+    if let Some(spider_id) = non_empty(&filters.spider_id) {
+        conditions.push("a.spider_id = ?".to_string());
+        args.push(SqlValue::Text(spider_id));
+    }
+    if let Some(job) = non_empty(&filters.job) {
+        conditions.push("a.job = ?".to_string());
+        args.push(SqlValue::Text(job));
+    }
+    if let Some(project_type) = non_empty(&filters.project_type) {
+        conditions.push("a.project_type = ?".to_string());
+        args.push(SqlValue::Text(project_type));
+    }
+    if let Some(date_from) = non_empty(&filters.date_from) {
+        conditions.push("substr(COALESCE(a.publish_date, a.first_seen_at), 1, 10) >= ?".to_string());
+        args.push(SqlValue::Text(date_from));
+    }
+    if let Some(date_to) = non_empty(&filters.date_to) {
+        conditions.push("substr(COALESCE(a.publish_date, a.first_seen_at), 1, 10) <= ?".to_string());
+        args.push(SqlValue::Text(date_to));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn insert(store: &AnnouncementStore, id: &str, title: &str, content: &str) {
+        let row = json!({ "id": id, "title": title, "content": content });
+        let mapped = row.as_object().unwrap().clone();
+        let items = [NewAnnouncement { row: &row, mapped: &mapped, detail_url: None }];
+        store.upsert_announcements("test", MANUAL_JOB, "run-1", &items).unwrap();
+    }
+
+    fn search_titles(store: &AnnouncementStore, query: &str) -> Vec<String> {
+        let page = store.search(query, &SearchFilters::default(), 1, MAX_PAGE_SIZE).unwrap();
+        let mut titles: Vec<String> = page.items.into_iter().map(|item| item.title).collect();
+        titles.sort();
+        titles
+    }
+
+    #[test]
+    fn bigram_tokens_split_cjk_and_keep_words() {
+        assert_eq!(bigram_tokens("中标候选人"), vec!["中标", "标候", "候选", "选人"]);
+        assert_eq!(bigram_tokens("标"), vec!["标"]);
+        assert_eq!(
+            bigram_tokens("CNPC-2024 中标公示"),
+            vec!["cnpc", "2024", "中标", "标公", "公示"]
+        );
+        // 中文与字母数字相邻时各自切分
+        assert_eq!(bigram_tokens("第3标段"), vec!["第", "3", "标段"]);
+        assert!(bigram_tokens(" ，。-").is_empty());
+        assert_eq!(index_text("候选人 A"), "候选 选人 a");
+    }
+
+    #[test]
+    fn fts_query_requires_every_term() {
+        assert_eq!(fts_query("候选人").as_deref(), Some("\"候选\" AND \"选人\""));
+        assert_eq!(fts_query("管道 CNPC").as_deref(), Some("\"管道\" AND \"cnpc\""));
+        // 单个汉字按前缀匹配
+        assert_eq!(fts_query("标").as_deref(), Some("\"标\"*"));
+        // 双引号按分隔符处理，不会破坏查询语法
+        assert_eq!(fts_query("a\"b").as_deref(), Some("\"a\" AND \"b\""));
+        assert_eq!(fts_query("  ").as_deref(), None);
+        assert_eq!(fts_query("，。").as_deref(), None);
+    }
+
+    #[test]
+    fn snippet_is_centered_on_first_match() {
+        let text = format!("{}管道工程{}", "甲".repeat(60), "乙".repeat(60));
+        let snippet = make_snippet(&text, "工程");
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("管道工程"));
+        assert_eq!(snippet.chars().count(), 80 + 2);
+
+        // 没有命中时取开头，短文本不加省略号
+        assert_eq!(make_snippet("短文本", "不存在"), "短文本");
+        assert_eq!(make_snippet("Pipeline 工程", "pipeline"), "Pipeline 工程");
+    }
+
+    #[test]
+    fn search_matches_chinese_fragments() {
+        let store = AnnouncementStore::open_in_memory().unwrap();
+        insert(&store, "1", "长庆油田管道工程中标候选人公示", "第一中标候选人：某某公司");
+        insert(&store, "2", "西南油气田物资采购招标公告", "采购 PE 管材");
+        insert(&store, "3", "塔里木油田工程中标结果公告", "");
+
+        assert_eq!(search_titles(&store, "候选人"), vec!["长庆油田管道工程中标候选人公示"]);
+        assert_eq!(
+            search_titles(&store, "工程 中标"),
+            vec!["塔里木油田工程中标结果公告", "长庆油田管道工程中标候选人公示"]
+        );
+        // 正文也参与检索，英文不区分大小写
+        assert_eq!(search_titles(&store, "pe"), vec!["西南油气田物资采购招标公告"]);
+        // 单个汉字按前缀匹配
+        assert_eq!(search_titles(&store, "塔").len(), 1);
+        assert!(search_titles(&store, "炼化").is_empty());
+        assert_eq!(search_titles(&store, "").len(), 3);
+
+        let page = store.search("候选人", &SearchFilters::default(), 1, 10).unwrap();
+        assert_eq!(page.total, 1);
+        assert!(page.items[0].snippet.contains("候选人"));
+    }
+
+    #[test]
+    fn search_with_huge_page_returns_empty_page() {
+        let store = AnnouncementStore::open_in_memory().unwrap();
+        let page = store.search("", &SearchFilters::default(), u32::MAX, MAX_PAGE_SIZE).unwrap();
+        assert_eq!(page.page, u32::MAX);
+        assert!(page.items.is_empty());
+        // 数据库仍然可用
+        store.search("", &SearchFilters::default(), 1, 10).unwrap();
+    }
+}
@@ -0,0 +1,56 @@
+// 单元测试共用的辅助类型
+
+use std::path::{Path, PathBuf};
+
+/// 测试用的临时目录，离开作用域时删除
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(prefix: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("{}_test_{}", prefix, uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// 本地 HTTP 服务器：按路径返回固定的状态码和内容，未配置的路径返回 404；返回 `http://127.0.0.1:<端口>`
+pub fn serve(routes: Vec<(&'static str, u16, Vec<u8>)>) -> String {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(&stream);
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).is_err() {
+                continue;
+            }
+            // 读完请求头
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                line.clear();
+            }
+            let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+            let (status, body) = routes
+                .iter()
+                .find(|(route, _, _)| *route == path)
+                .map(|(_, status, body)| (*status, body.as_slice()))
+                .unwrap_or((404, b"not found".as_slice()));
+            let mut stream = &stream;
+            let _ = write!(stream, "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", status, body.len());
+            let _ = stream.write_all(body);
+        }
+    });
+    base
+}
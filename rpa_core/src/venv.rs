@@ -0,0 +1,553 @@
+// ============================================================================
+// 爬虫专用的私有 Python 环境
+// ============================================================================
+//
+// 在应用数据目录下创建 python-env/（venv），按 spiders.py 旁边的 requirements.lock
+// （由 requirements.txt 生成、包括间接依赖的完整锁定文件）安装依赖，不再依赖用户系统 Python 里装了什么：
+//   1. 用系统 Python 执行 python -m venv 创建环境
+//   2. pip install --no-deps -r requirements.lock，只安装锁定的版本；打包的 wheelhouse/ 中有 wheel 时
+//      离线安装（--no-index --find-links），否则联网安装（使用网络设置中的代理）
+//   3. 在新环境中逐个导入爬虫需要的包
+//   4. 写入安装记录 rpa_env.json（requirements.lock 的 SHA-256 等）
+// 没有安装记录的环境视为未完成，运行环境解析不会选中它。
+// requirements.lock 未变化时跳过安装，除非强制重建
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config_file;
+use crate::diagnostics::{self, CheckStatus};
+use crate::network::NetworkSettings;
+
+pub const ENV_DIR: &str = "python-env";
+pub const MARKER_FILE: &str = "rpa_env.json";
+/// 完整锁定的依赖清单，由 lock_requirements.sh 从 requirements.txt 生成
+pub const LOCK_FILE: &str = "requirements.lock";
+pub const WHEELHOUSE_DIR: &str = "wheelhouse";
+
+/// 安装记录，写在环境目录中
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EnvMarker {
+    /// 安装时 requirements.lock 的 SHA-256
+    pub requirements_sha256: String,
+    pub python_version: String,
+    /// 创建环境所用的系统 Python
+    pub base_python: String,
+    /// 是否从打包的 wheelhouse 离线安装
+    pub offline: bool,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SetupStage {
+    /// 检查 requirements.lock 和基础解释器
+    Prepare,
+    /// 创建 venv
+    Create,
+    /// pip 安装依赖
+    Install,
+    /// 导入检查
+    Verify,
+    Done,
+}
+
+/// 安装进度，通过 python-env-progress 事件发送给前端
+#[derive(Serialize, Debug, Clone)]
+pub struct SetupProgress {
+    pub stage: SetupStage,
+    pub message: String,
+    /// 0-100
+    pub percent: u8,
+}
+
+/// 私有环境的当前状态
+#[derive(Serialize, Debug, Clone)]
+pub struct EnvStatus {
+    pub path: String,
+    /// 环境中的解释器，环境不存在时为空
+    pub python: Option<String>,
+    /// 安装完成（有安装记录）
+    pub ready: bool,
+    pub marker: Option<EnvMarker>,
+    /// 当前使用的 requirements.lock
+    pub requirements: Option<String>,
+    /// requirements.lock 与安装时不同，需要重新安装
+    pub outdated: bool,
+    pub wheelhouse: Option<String>,
+    pub wheel_count: usize,
+}
+
+/// requirements.lock 中锁定的一个包
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    /// `; python_version < "3.10"` 这样的环境标记
+    pub marker: Option<String>,
+}
+
+/// 解析 requirements.lock：每个包一行 `name==version`，可带环境标记和 pip-compile 生成的 --hash；
+/// 注释、空行和单独的选项行跳过。安装时使用 --no-deps，没有固定版本的行会装上任意版本，视为错误
+pub fn parse_lock(content: &str) -> Result<Vec<LockedPackage>, String> {
+    let mut packages = Vec::new();
+    let mut logical = String::new();
+    let mut start_line = 0;
+    for (index, raw) in content.lines().enumerate() {
+        if logical.is_empty() {
+            start_line = index + 1;
+        }
+        // 行尾的 \ 表示下一行继续（--hash 通常这样换行）
+        if let Some(continued) = raw.trim_end().strip_suffix('\\') {
+            logical.push_str(continued);
+            logical.push(' ');
+            continue;
+        }
+        logical.push_str(raw);
+        let line = std::mem::take(&mut logical);
+
+        let line = match line.find(" #") {
+            Some(pos) => &line[..pos],
+            None if line.trim_start().starts_with('#') => "",
+            None => line.as_str(),
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('-') {
+            continue;
+        }
+        let requirement = line.split(" --").next().unwrap_or_default();
+        let (spec, marker) = match requirement.split_once(';') {
+            Some((spec, marker)) => (spec.trim(), Some(marker.trim().to_string())),
+            None => (requirement.trim(), None),
+        };
+        let pinned = spec.split_once("==").and_then(|(name, version)| {
+            let name = name.trim();
+            let version = version.trim();
+            let valid_name = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+            (valid_name && !version.is_empty() && !version.starts_with('=')).then_some((name, version))
+        });
+        match pinned {
+            Some((name, version)) => packages.push(LockedPackage {
+                name: name.to_string(),
+                version: version.to_string(),
+                marker,
+            }),
+            None => return Err(format!("{} 第 {} 行没有固定版本（需要 name==version）: {}", LOCK_FILE, start_line, spec)),
+        }
+    }
+    Ok(packages)
+}
+
+pub fn env_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(ENV_DIR)
+}
+
+/// venv 中解释器的位置（Windows 为 Scripts\python.exe）
+pub fn python_path(env_dir: &Path) -> PathBuf {
+    if cfg!(windows) {
+        env_dir.join("Scripts").join("python.exe")
+    } else {
+        env_dir.join("bin").join("python")
+    }
+}
+
+/// 解释器路径属于某个私有环境时，返回环境目录
+pub fn env_dir_of(python: &Path) -> Option<PathBuf> {
+    let dir = python.parent()?.parent()?;
+    (dir.file_name()? == ENV_DIR).then(|| dir.to_path_buf())
+}
+
+pub fn read_marker(env_dir: &Path) -> Option<EnvMarker> {
+    let content = fs::read(env_dir.join(MARKER_FILE)).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+/// 目录中的 wheel 文件数量
+fn count_wheels(dir: &Path) -> usize {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "whl"))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// 查看私有环境状态；script_dir 为 spiders.py 所在目录（requirements.lock 和 wheelhouse/ 在其中）
+pub fn status(app_data_dir: &Path, script_dir: Option<&Path>) -> EnvStatus {
+    let dir = env_dir(app_data_dir);
+    let python = python_path(&dir);
+    let marker = read_marker(&dir);
+    let requirements = script_dir.map(|d| d.join(LOCK_FILE)).filter(|p| p.is_file());
+    let wheelhouse = script_dir.map(|d| d.join(WHEELHOUSE_DIR)).filter(|p| p.is_dir());
+    let outdated = match (&marker, &requirements) {
+        (Some(marker), Some(path)) => fs::read(path)
+            .map(|content| sha256_hex(&content) != marker.requirements_sha256)
+            .unwrap_or(false),
+        _ => false,
+    };
+    EnvStatus {
+        path: dir.to_string_lossy().to_string(),
+        python: python.is_file().then(|| python.to_string_lossy().to_string()),
+        ready: marker.is_some() && python.is_file(),
+        marker,
+        requirements: requirements.as_ref().map(|p| p.to_string_lossy().to_string()),
+        outdated,
+        wheel_count: wheelhouse.as_deref().map(count_wheels).unwrap_or(0),
+        wheelhouse: wheelhouse.map(|p| p.to_string_lossy().to_string()),
+    }
+}
+
+/// 同一时间只允许一个安装任务，放在 Tauri 的 managed state 中
+#[derive(Default)]
+pub struct SetupLock(Mutex<()>);
+
+impl SetupLock {
+    pub fn try_begin(&self) -> Result<MutexGuard<'_, ()>, String> {
+        self.0.try_lock().map_err(|_| "私有 Python 环境正在安装中，请稍候".to_string())
+    }
+}
+
+pub struct SetupInput<'a> {
+    pub app_data_dir: &'a Path,
+    /// 用来创建 venv 的系统 Python
+    pub base_python: &'a str,
+    pub script_dir: &'a Path,
+    /// 联网安装时使用的代理
+    pub network: &'a NetworkSettings,
+    /// 删除现有环境后重建
+    pub force: bool,
+}
+
+fn run_step(command: &mut Command, what: &str) -> Result<(), String> {
+    let output = command.output().map_err(|e| format!("{}失败: {}", what, e))?;
+    if !output.status.success() {
+        return Err(format!("{}失败: {}", what, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// 执行 pip install，每行输出作为一条安装进度
+fn pip_install(command: &mut Command, progress: &mut dyn FnMut(SetupProgress)) -> Result<(), String> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("执行 pip 失败: {}", e))?;
+
+    // stderr 在单独的线程中读取，避免缓冲区写满后 pip 阻塞
+    let mut stderr = child.stderr.take().unwrap();
+    let stderr_reader = std::thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text);
+        text
+    });
+
+    let mut percent = 30;
+    for line in BufReader::new(child.stdout.take().unwrap()).lines().map_while(Result::ok) {
+        let line = line.trim().to_string();
+        if line.is_empty() {
+            continue;
+        }
+        // pip 不报告总进度，按输出行数推进到 85%
+        if line.starts_with("Collecting") || line.starts_with("Processing") || line.starts_with("Installing") {
+            percent = (percent + 3).min(85);
+        }
+        progress(SetupProgress { stage: SetupStage::Install, message: line, percent });
+    }
+
+    let status = child.wait().map_err(|e| format!("等待 pip 结束失败: {}", e))?;
+    let stderr = stderr_reader.join().unwrap_or_default();
+    if !status.success() {
+        return Err(format!("安装依赖失败（{}）: {}", status, stderr.trim()));
+    }
+    Ok(())
+}
+
+/// 创建或更新私有环境，返回安装后的状态
+pub fn setup(input: &SetupInput, mut progress: impl FnMut(SetupProgress)) -> Result<EnvStatus, String> {
+    let mut report = |stage, message: String, percent| {
+        log_info!("[私有环境] {}", message);
+        progress(SetupProgress { stage, message, percent });
+    };
+
+    let requirements_path = input.script_dir.join(LOCK_FILE);
+    let requirements = fs::read(&requirements_path)
+        .map_err(|e| format!("读取依赖清单 {:?} 失败: {}", requirements_path, e))?;
+    // 在创建环境之前发现未固定版本的依赖
+    parse_lock(&String::from_utf8_lossy(&requirements))?;
+    let requirements_sha256 = sha256_hex(&requirements);
+    let dir = env_dir(input.app_data_dir);
+    let python = python_path(&dir);
+
+    let current = read_marker(&dir);
+    if !input.force && python.is_file() {
+        if let Some(marker) = &current {
+            if marker.requirements_sha256 == requirements_sha256 {
+                report(SetupStage::Done, "私有环境已是最新，无需安装".to_string(), 100);
+                return Ok(status(input.app_data_dir, Some(input.script_dir)));
+            }
+        }
+    }
+
+    report(SetupStage::Prepare, format!("使用 {} 创建私有环境", input.base_python), 5);
+    let base_version = crate::runtime::check_python(input.base_python)?.unwrap_or_default();
+
+    // 先删除安装记录，安装中途失败时环境不会被当作可用
+    let _ = fs::remove_file(dir.join(MARKER_FILE));
+    if input.force || !python.is_file() {
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(|e| format!("删除旧环境失败: {}", e))?;
+        }
+        report(SetupStage::Create, format!("创建虚拟环境 {:?}", dir), 15);
+        run_step(Command::new(input.base_python).arg("-m").arg("venv").arg(&dir), "创建虚拟环境")?;
+    }
+
+    let wheelhouse = input.script_dir.join(WHEELHOUSE_DIR);
+    let offline = count_wheels(&wheelhouse) > 0;
+    let mut pip = Command::new(&python);
+    // 锁定文件已包含全部间接依赖，--no-deps 保证不会装上未锁定的版本
+    pip.args(["-m", "pip", "install", "--disable-pip-version-check", "--no-deps", "-r"])
+        .arg(&requirements_path)
+        .current_dir(input.script_dir);
+    if offline {
+        report(SetupStage::Install, format!("从 {:?} 离线安装依赖", wheelhouse), 30);
+        pip.arg("--no-index").arg("--find-links").arg(&wheelhouse);
+    } else {
+        report(SetupStage::Install, "没有打包的 wheel，联网安装依赖".to_string(), 30);
+        input.network.apply_to_command(&mut pip);
+    }
+    pip_install(&mut pip, &mut |p| report(p.stage, p.message, p.percent))?;
+
+    report(SetupStage::Verify, "检查依赖包能否导入".to_string(), 90);
+    let python_str = python.to_string_lossy().to_string();
+    let failed: Vec<String> = diagnostics::check_packages(&python_str)
+        .into_iter()
+        .filter(|check| check.status != CheckStatus::Pass)
+        .map(|check| format!("{}: {}", check.name, check.detail))
+        .collect();
+    if !failed.is_empty() {
+        return Err(format!("依赖安装后仍无法导入:\n{}", failed.join("\n")));
+    }
+
+    let marker = EnvMarker {
+        requirements_sha256,
+        python_version: crate::runtime::check_python(&python_str)?.unwrap_or(base_version),
+        base_python: input.base_python.to_string(),
+        offline,
+        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    let content = serde_json::to_vec_pretty(&marker).map_err(|e| format!("序列化安装记录失败: {}", e))?;
+    config_file::write_atomic(&dir.join(MARKER_FILE), &content)?;
+
+    report(SetupStage::Done, "私有环境安装完成".to_string(), 100);
+    Ok(status(input.app_data_dir, Some(input.script_dir)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn package(name: &str, version: &str, marker: Option<&str>) -> LockedPackage {
+        LockedPackage { name: name.to_string(), version: version.to_string(), marker: marker.map(str::to_string) }
+    }
+
+    /// PEP 503 规范化的包名
+    fn normalize(name: &str) -> String {
+        name.to_lowercase().replace(['_', '.'], "-")
+    }
+
+    fn marker(lock: &[u8]) -> EnvMarker {
+        EnvMarker {
+            requirements_sha256: sha256_hex(lock),
+            python_version: "3.11.7".to_string(),
+            base_python: "python3".to_string(),
+            offline: false,
+            created_at: "2025-01-01 00:00:00".to_string(),
+        }
+    }
+
+    fn write_marker(env_dir: &Path, marker: &EnvMarker) {
+        fs::create_dir_all(env_dir).unwrap();
+        fs::write(env_dir.join(MARKER_FILE), serde_json::to_vec(marker).unwrap()).unwrap();
+    }
+
+    fn touch_python(env_dir: &Path) {
+        let python = python_path(env_dir);
+        fs::create_dir_all(python.parent().unwrap()).unwrap();
+        fs::write(python, "").unwrap();
+    }
+
+    #[test]
+    fn lock_file_lines_are_parsed() {
+        let content = "\
+# 注释
+certifi==2025.11.12
+    # via requests
+importlib-resources==6.4.0 ; python_version < \"3.10\"
+
+--index-url https://pypi.org/simple
+requests==2.32.5 \\
+    --hash=sha256:aaaa \\
+    --hash=sha256:bbbb
+PySocks==1.7.1  # 代理支持
+";
+        assert_eq!(
+            parse_lock(content).unwrap(),
+            [
+                package("certifi", "2025.11.12", None),
+                package("importlib-resources", "6.4.0", Some("python_version < \"3.10\"")),
+                package("requests", "2.32.5", None),
+                package("PySocks", "1.7.1", None),
+            ]
+        );
+        assert_eq!(parse_lock("").unwrap(), []);
+    }
+
+    #[test]
+    fn unpinned_lock_lines_are_rejected() {
+        for (content, line) in [
+            ("requests\n", 1),
+            ("certifi==1\nrequests>=2.0\n", 2),
+            ("certifi==1\n\nnumpy==\n", 3),
+            ("pkg===1.0\n", 1),
+            ("requests[socks]==2.32.5\n", 1),
+            ("certifi==1 \\\n  --hash=sha256:aa\nrequests~=2.0 \\\n  --hash=sha256:bb\n", 3),
+        ] {
+            let error = parse_lock(content).unwrap_err();
+            assert!(error.contains(&format!("第 {} 行", line)), "{}: {}", content, error);
+        }
+    }
+
+    #[test]
+    fn bundled_lock_pins_every_direct_requirement() {
+        // 仓库中的 requirements.lock 与 requirements.txt 一致，且包括诊断检查的全部包
+        let project = Path::new(env!("CARGO_MANIFEST_DIR")).join("../中国石油招标投标网");
+        let lock = parse_lock(&fs::read_to_string(project.join(LOCK_FILE)).unwrap()).unwrap();
+        let direct = parse_lock(&fs::read_to_string(project.join("requirements.txt")).unwrap()).unwrap();
+        assert!(!direct.is_empty());
+        for requirement in &direct {
+            let locked = lock.iter().find(|p| normalize(&p.name) == normalize(&requirement.name));
+            assert_eq!(locked.map(|p| p.version.as_str()), Some(requirement.version.as_str()), "{}", requirement.name);
+        }
+        for name in ["requests", "ddddocr", "fake-useragent"] {
+            assert!(lock.iter().any(|p| normalize(&p.name) == name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn interpreter_path_maps_back_to_env_dir() {
+        let dir = env_dir(Path::new("/data"));
+        assert_eq!(env_dir_of(&python_path(&dir)), Some(dir));
+        assert_eq!(env_dir_of(Path::new("/usr/bin/python3")), None);
+        assert_eq!(env_dir_of(Path::new("python3")), None);
+    }
+
+    #[test]
+    fn marker_is_read_only_when_valid() {
+        let dir = TempDir::new("venv");
+        assert!(read_marker(dir.path()).is_none());
+        fs::write(dir.path().join(MARKER_FILE), "{ 半个").unwrap();
+        assert!(read_marker(dir.path()).is_none());
+        write_marker(dir.path(), &marker(b"lock"));
+        assert_eq!(read_marker(dir.path()).unwrap().requirements_sha256, sha256_hex(b"lock"));
+    }
+
+    #[test]
+    fn status_reports_readiness_outdated_lock_and_wheels() {
+        let data = TempDir::new("venv");
+        let scripts = TempDir::new("venv_scripts");
+        let env = env_dir(data.path());
+
+        let empty = status(data.path(), Some(scripts.path()));
+        assert!(!empty.ready && !empty.outdated);
+        assert_eq!((empty.python, empty.requirements, empty.wheelhouse), (None, None, None));
+
+        fs::write(scripts.path().join(LOCK_FILE), "requests==2.32.5\n").unwrap();
+        fs::create_dir_all(scripts.path().join(WHEELHOUSE_DIR)).unwrap();
+        for file in ["requests-2.32.5-py3-none-any.whl", "certifi-2025.11.12-py3-none-any.whl", "README.txt"] {
+            fs::write(scripts.path().join(WHEELHOUSE_DIR).join(file), "").unwrap();
+        }
+        // 有安装记录但没有解释器（安装到一半）时不可用
+        write_marker(&env, &marker(b"requests==2.32.5\n"));
+        let partial = status(data.path(), Some(scripts.path()));
+        assert!(!partial.ready);
+        assert_eq!(partial.wheel_count, 2);
+
+        touch_python(&env);
+        let ready = status(data.path(), Some(scripts.path()));
+        assert!(ready.ready && !ready.outdated);
+
+        fs::write(scripts.path().join(LOCK_FILE), "requests==2.32.4\n").unwrap();
+        assert!(status(data.path(), Some(scripts.path())).outdated);
+    }
+
+    #[test]
+    fn setup_checks_lock_before_touching_the_env() {
+        let data = TempDir::new("venv");
+        let scripts = TempDir::new("venv_scripts");
+        let network = NetworkSettings::default();
+        let input = SetupInput {
+            app_data_dir: data.path(),
+            base_python: "no-such-python",
+            script_dir: scripts.path(),
+            network: &network,
+            force: false,
+        };
+
+        let missing = setup(&input, |_| {}).unwrap_err();
+        assert!(missing.contains(LOCK_FILE), "{}", missing);
+
+        fs::write(scripts.path().join(LOCK_FILE), "requests\n").unwrap();
+        let unpinned = setup(&input, |_| {}).unwrap_err();
+        assert!(unpinned.contains("没有固定版本"), "{}", unpinned);
+        assert!(!env_dir(data.path()).exists());
+    }
+
+    #[test]
+    fn setup_skips_up_to_date_env() {
+        let data = TempDir::new("venv");
+        let scripts = TempDir::new("venv_scripts");
+        let lock = b"requests==2.32.5\n";
+        fs::write(scripts.path().join(LOCK_FILE), lock).unwrap();
+        let env = env_dir(data.path());
+        touch_python(&env);
+        write_marker(&env, &marker(lock));
+
+        let network = NetworkSettings::default();
+        let input = SetupInput {
+            app_data_dir: data.path(),
+            base_python: "no-such-python",
+            script_dir: scripts.path(),
+            network: &network,
+            force: false,
+        };
+        let mut stages = Vec::new();
+        let result = setup(&input, |p| stages.push((p.stage, p.percent))).unwrap();
+        assert!(result.ready);
+        // 没有执行基础解释器，直接完成
+        assert_eq!(stages, [(SetupStage::Done, 100)]);
+    }
+
+    #[test]
+    fn only_one_setup_at_a_time() {
+        let lock = SetupLock::default();
+        let guard = lock.try_begin().unwrap();
+        assert!(lock.try_begin().is_err());
+        drop(guard);
+        assert!(lock.try_begin().is_ok());
+    }
+}
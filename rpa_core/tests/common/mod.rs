@@ -0,0 +1,622 @@
+// ============================================================================
+// 集成测试共用的假环境
+// ============================================================================
+//
+//   FakeSite      本地的中国石油招标投标网：列表接口、详情接口和附件
+//   SmtpSink      本地 SMTP 服务器（不加密，AUTH PLAIN），记录收到的邮件
+//   FakeSpiderBin 代替打包的 spider_bin：把参数转发给 FakeSite 的列表接口并输出响应
+//   STUB_WORKER   代替 spiders.py 的常驻 worker 脚本（与 run_worker 相同的 JSON-RPC 协议）
+//   PlainSmtp     不加密地连接 SmtpSink 的 MailTransport，记录连接方式
+//   TestClock     跟随 tokio 时间前进的时钟，配合 start_paused 快进定时任务
+//   Harness       在临时目录中创建 AppCore，并把上面几个注入进去（内置爬虫通过用户插件目录指向 FakeSite）
+//
+// 每个测试二进制只用到其中一部分
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Path as UrlPath, Query};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::Engine;
+use chrono::{DateTime, Local};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde_json::{json, Value};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+use rpa_core::backend::{BackendChoice, BackendOptions};
+use rpa_core::clock::Clock;
+use rpa_core::crawl_policy::CrawlPolicy;
+use rpa_core::mailer::{MailTransport, SmtpRoute};
+use rpa_core::network::NetworkSettings;
+use rpa_core::process::{ProcessOutput, ProcessRunner, ProcessSpec};
+use rpa_core::runtime::RuntimeSettings;
+use rpa_core::settings::SmtpConfig;
+use rpa_core::spider_registry::{DetailSource, SpiderPlugin, DEFAULT_SPIDER_ID};
+use rpa_core::{AppCore, AppFeatures, AppIdentity, AppPaths, RunOptions, Services};
+
+pub const SMTP_USERNAME: &str = "robot@example.com";
+pub const SMTP_PASSWORD: &str = "sink-password";
+
+/// 假网站上的公告：(id, 标题, 发布日期, 项目类型)
+pub const ANNOUNCEMENTS: [(i64, &str, &str, &str); 2] = [
+    (101, "某油田管道防腐工程中标候选人公示", "2025-01-02", "0002"),
+    (102, "某炼化公司阀门采购中标候选人公示", "2025-01-03", "0001"),
+];
+
+/// 列表接口返回错误的搜索关键字
+pub const FAILING_TITLE: &str = "站点故障";
+
+/// 标题为该值时，FakeSpiderBin 像找不到 Node.js 一样以环境错误码失败
+pub const NODE_MISSING_TITLE: &str = "缺少Node";
+
+// ---------------------------------------------------------------------------
+// 假网站
+// ---------------------------------------------------------------------------
+
+pub struct FakeSite {
+    pub base_url: String,
+}
+
+impl FakeSite {
+    /// 在单独的线程和 tokio 运行时上启动（不受测试中暂停的时间影响）
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("绑定端口失败");
+        listener.set_nonblocking(true).unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new()
+            .route("/cms/article/page", post(article_page))
+            .route("/cms/article/detail", get(article_detail))
+            .route("/cms/file/{id}/{name}", get(attachment));
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            runtime.block_on(async move {
+                let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+                axum::serve(listener, app).await.unwrap();
+            });
+        });
+        FakeSite { base_url }
+    }
+
+    /// 指向本站详情接口的内置爬虫插件
+    pub fn plugin(&self) -> SpiderPlugin {
+        let mut plugin = SpiderPlugin::builtin_cnpcbidding();
+        plugin.detail = Some(DetailSource {
+            url_template: format!("{}/cms/article/detail?id={{id}}", self.base_url),
+            content_path: "/data/content".to_string(),
+            attachment_extensions: vec!["pdf".to_string()],
+        });
+        plugin
+    }
+}
+
+async fn article_page(Json(params): Json<Value>) -> (axum::http::StatusCode, Json<Value>) {
+    let title = params["title"].as_str().unwrap_or_default();
+    if title == FAILING_TITLE {
+        return (axum::http::StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "code": "500" })));
+    }
+    let project_type = params["project_type"].as_str().unwrap_or_default();
+    let records: Vec<Value> = ANNOUNCEMENTS
+        .iter()
+        .filter(|(_, t, _, p)| t.contains(title) && (project_type.is_empty() || *p == project_type))
+        .map(|(id, title, date, project_type)| {
+            json!({ "id": id, "title": title, "publishDate": date, "projectType": project_type })
+        })
+        .collect();
+    let total = records.len();
+    (axum::http::StatusCode::OK, Json(json!({ "code": "200", "data": { "records": records, "total": total } })))
+}
+
+async fn article_detail(Query(query): Query<HashMap<String, String>>) -> Json<Value> {
+    let id = query.get("id").cloned().unwrap_or_default();
+    let content = format!(
+        "<p>项目编号：CNPC-2025-GD-{id}</p>\
+         <p>招标代理机构：中油物资招标有限公司</p>\
+         <table>\
+         <tr><td>排名</td><td>候选人名称</td><td>投标报价（万元）</td></tr>\
+         <tr><td>1</td><td>甲工程有限公司</td><td>123.45</td></tr>\
+         <tr><td>2</td><td>乙建设有限公司</td><td>130.00</td></tr>\
+         </table>\
+         <p><a href=\"/cms/file/{id}/评标报告.pdf\">评标报告</a></p>"
+    );
+    Json(json!({ "code": "200", "data": { "content": content } }))
+}
+
+async fn attachment(UrlPath((id, _name)): UrlPath<(String, String)>) -> Vec<u8> {
+    format!("%PDF-1.4 fake attachment {}", id).into_bytes()
+}
+
+// ---------------------------------------------------------------------------
+// 假 spider_bin
+// ---------------------------------------------------------------------------
+
+/// 代替 spider_bin：参数（第一个命令行参数）原样 POST 到假网站的列表接口
+pub struct FakeSpiderBin {
+    site_url: String,
+    calls: Mutex<Vec<ProcessSpec>>,
+}
+
+impl FakeSpiderBin {
+    pub fn new(site_url: &str) -> Self {
+        FakeSpiderBin { site_url: site_url.to_string(), calls: Mutex::new(Vec::new()) }
+    }
+
+    pub fn calls(&self) -> Vec<ProcessSpec> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+impl ProcessRunner for FakeSpiderBin {
+    fn run(&self, spec: &ProcessSpec) -> io::Result<ProcessOutput> {
+        self.calls.lock().unwrap().push(spec.clone());
+        let params = spec.args.first().cloned().unwrap_or_default();
+        if serde_json::from_str::<Value>(&params).is_ok_and(|p| p["title"] == NODE_MISSING_TITLE) {
+            // 与 spiders.py 的 __main__ 相同：stderr 最后一行是带错误码的 JSON
+            let stderr = json!({ "error": "未找到 Node.js", "type": "RuntimeEnvironmentError", "code": -32001 });
+            return Ok(ProcessOutput { success: false, stdout: Vec::new(), stderr: stderr.to_string().into_bytes() });
+        }
+        let response = reqwest::blocking::Client::new()
+            .post(format!("{}/cms/article/page", self.site_url))
+            .header("Content-Type", "application/json")
+            .body(params)
+            .send()
+            .map_err(io::Error::other)?;
+        let status = response.status();
+        let body = response.bytes().map_err(io::Error::other)?.to_vec();
+        if status.is_success() {
+            Ok(ProcessOutput { success: true, stdout: body, stderr: Vec::new() })
+        } else {
+            Ok(ProcessOutput { success: false, stdout: Vec::new(), stderr: format!("HTTPError: {}", status).into_bytes() })
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 假 Python worker
+// ---------------------------------------------------------------------------
+
+/// 标题为该值时，假 worker 像缺少依赖包一样以环境错误失败
+pub const MISSING_MODULE_TITLE: &str = "缺少依赖包";
+
+/// 代替 spiders.py --worker：run 把参数 POST 到假网站的列表接口；SITE_URL 在写入时替换
+const STUB_WORKER: &str = r#"
+import json, sys, urllib.request, urllib.error
+
+SITE_URL = "{site_url}"
+EXECUTION_ERROR_CODE = -32000
+ENVIRONMENT_ERROR_CODE = -32001
+
+def reply(request_id, result=None, error=None):
+    message = {"jsonrpc": "2.0", "id": request_id}
+    if error is not None:
+        message["error"] = error
+    else:
+        message["result"] = result
+    sys.stdout.write(json.dumps(message, ensure_ascii=False) + "\n")
+    sys.stdout.flush()
+
+def run(params):
+    if params.get("title") == "{missing_module_title}":
+        import no_such_module_for_stub_worker
+    request = urllib.request.Request(
+        SITE_URL + "/cms/article/page",
+        data=json.dumps(params).encode("utf-8"),
+        headers={"Content-Type": "application/json"},
+    )
+    with urllib.request.urlopen(request, timeout=10) as response:
+        return json.loads(response.read().decode("utf-8"))
+
+reply(None, {"ready": True})
+for line in sys.stdin:
+    request = json.loads(line)
+    method = request.get("method")
+    if method == "ping":
+        reply(request["id"], {"pong": True})
+    elif method == "shutdown":
+        reply(request["id"], {"shutdown": True})
+        break
+    elif method == "run":
+        try:
+            reply(request["id"], run(request.get("params") or {}))
+        except ImportError as e:
+            reply(request["id"], error={"code": ENVIRONMENT_ERROR_CODE, "message": str(e)})
+        except urllib.error.HTTPError as e:
+            reply(request["id"], error={"code": EXECUTION_ERROR_CODE, "message": f"HTTPError: {e.code}"})
+"#;
+
+/// 系统中可用的 Python 3，没有时返回 None（相关测试跳过）
+pub fn python3() -> Option<String> {
+    ["python3", "python"]
+        .into_iter()
+        .find(|program| {
+            std::process::Command::new(program)
+                .arg("--version")
+                .output()
+                .is_ok_and(|output| output.status.success() && String::from_utf8_lossy(&output.stdout).contains("Python 3"))
+        })
+        .map(str::to_string)
+}
+
+/// 在 dir 中写入指向 site_url 的假 worker 脚本
+fn write_stub_worker(dir: &Path, site_url: &str) -> PathBuf {
+    let path = dir.join("stub_spiders.py");
+    let script = STUB_WORKER.replace("{site_url}", site_url).replace("{missing_module_title}", MISSING_MODULE_TITLE);
+    std::fs::write(&path, script).unwrap();
+    path
+}
+
+// ---------------------------------------------------------------------------
+// SMTP
+// ---------------------------------------------------------------------------
+
+/// SmtpSink 收到的一封邮件
+#[derive(Debug, Clone)]
+pub struct ReceivedMail {
+    pub mail_from: String,
+    pub rcpt_to: Vec<String>,
+    /// DATA 部分的原文
+    pub data: String,
+}
+
+impl ReceivedMail {
+    pub fn header(&self, name: &str) -> Option<String> {
+        let (headers, _) = self.data.split_once("\r\n\r\n")?;
+        let prefix = format!("{}:", name.to_lowercase());
+        let mut lines = headers.split("\r\n");
+        let mut value = lines.find(|line| line.to_lowercase().starts_with(&prefix))?[prefix.len()..].trim().to_string();
+        // 折行的头部
+        for line in lines.take_while(|line| line.starts_with([' ', '\t'])) {
+            value.push(' ');
+            value.push_str(line.trim());
+        }
+        Some(value)
+    }
+
+    /// 解码后的主题（RFC 2047）
+    pub fn subject(&self) -> String {
+        decode_encoded_words(&self.header("Subject").unwrap_or_default())
+    }
+
+    /// 按 Content-Transfer-Encoding 解码后的正文
+    pub fn body(&self) -> String {
+        let body = self.data.split_once("\r\n\r\n").map(|(_, body)| body).unwrap_or_default();
+        match self.header("Content-Transfer-Encoding").unwrap_or_default().to_lowercase().as_str() {
+            "base64" => {
+                let compact: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+                String::from_utf8(base64::engine::general_purpose::STANDARD.decode(compact).unwrap()).unwrap()
+            }
+            "quoted-printable" => decode_quoted_printable(body),
+            _ => body.to_string(),
+        }
+    }
+}
+
+fn decode_encoded_words(value: &str) -> String {
+    value
+        .split_whitespace()
+        .map(|word| {
+            let inner = word.strip_prefix("=?").and_then(|w| w.strip_suffix("?="));
+            match inner.map(|w| w.splitn(3, '?').collect::<Vec<_>>()).as_deref() {
+                Some([_, encoding, text]) if encoding.eq_ignore_ascii_case("b") => {
+                    String::from_utf8(base64::engine::general_purpose::STANDARD.decode(text).unwrap()).unwrap()
+                }
+                Some([_, encoding, text]) if encoding.eq_ignore_ascii_case("q") => {
+                    decode_quoted_printable(&text.replace('_', " "))
+                }
+                _ => format!("{} ", word),
+            }
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn decode_quoted_printable(text: &str) -> String {
+    let text = text.replace("=\r\n", "");
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'=' && i + 2 < bytes.len() {
+            if let Ok(byte) = u8::from_str_radix(&text[i + 1..i + 3], 16) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(out).unwrap()
+}
+
+/// 本地 SMTP 服务器：只支持明文连接和 AUTH PLAIN，用户名和密码不对时返回 535
+pub struct SmtpSink {
+    pub port: u16,
+    received: Arc<Mutex<Vec<ReceivedMail>>>,
+}
+
+impl SmtpSink {
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("绑定端口失败");
+        let port = listener.local_addr().unwrap().port();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sink = sink.clone();
+                std::thread::spawn(move || {
+                    let _ = serve_smtp(stream, &sink);
+                });
+            }
+        });
+        SmtpSink { port, received }
+    }
+
+    pub fn received(&self) -> Vec<ReceivedMail> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+fn serve_smtp(stream: TcpStream, sink: &Mutex<Vec<ReceivedMail>>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    let mut reply = |line: &str| writer.write_all(format!("{}\r\n", line).as_bytes());
+    reply("220 sink.test ESMTP")?;
+
+    let mut authenticated = false;
+    let mut mail_from = String::new();
+    let mut rcpt_to = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let command = line.trim_end();
+        let upper = command.to_uppercase();
+        if upper.starts_with("EHLO") {
+            reply("250-sink.test")?;
+            reply("250 AUTH PLAIN LOGIN")?;
+        } else if upper.starts_with("AUTH PLAIN") {
+            let encoded = command[10..].trim();
+            let decoded = base64::engine::general_purpose::STANDARD.decode(encoded).unwrap_or_default();
+            let parts: Vec<&[u8]> = decoded.split(|b| *b == 0).collect();
+            authenticated = parts.len() == 3 && parts[1] == SMTP_USERNAME.as_bytes() && parts[2] == SMTP_PASSWORD.as_bytes();
+            if authenticated {
+                reply("235 2.7.0 Authentication successful")?;
+            } else {
+                reply("535 5.7.8 Authentication credentials invalid")?;
+            }
+        } else if upper.starts_with("MAIL FROM:") {
+            if !authenticated {
+                reply("530 5.7.0 Authentication required")?;
+                continue;
+            }
+            mail_from = address_of(&command[10..]);
+            rcpt_to.clear();
+            reply("250 OK")?;
+        } else if upper.starts_with("RCPT TO:") {
+            rcpt_to.push(address_of(&command[8..]));
+            reply("250 OK")?;
+        } else if upper == "DATA" {
+            reply("354 End data with <CR><LF>.<CR><LF>")?;
+            let mut data = String::new();
+            loop {
+                let mut data_line = String::new();
+                if reader.read_line(&mut data_line)? == 0 {
+                    return Ok(());
+                }
+                if data_line == ".\r\n" {
+                    break;
+                }
+                data.push_str(data_line.strip_prefix('.').unwrap_or(&data_line));
+            }
+            sink.lock().unwrap().push(ReceivedMail { mail_from: mail_from.clone(), rcpt_to: rcpt_to.clone(), data });
+            reply("250 OK queued")?;
+        } else if upper == "QUIT" {
+            reply("221 Bye")?;
+            return Ok(());
+        } else if upper == "RSET" || upper == "NOOP" {
+            reply("250 OK")?;
+        } else {
+            reply("502 Command not implemented")?;
+        }
+    }
+}
+
+fn address_of(argument: &str) -> String {
+    let argument = argument.trim();
+    let end = argument.find('>').unwrap_or(argument.len());
+    argument[..end].trim_start_matches('<').to_string()
+}
+
+/// 不加密地连接 route 指定的服务器（SmtpSink），记录每次的连接方式
+#[derive(Default)]
+pub struct PlainSmtp {
+    routes: Mutex<Vec<SmtpRoute>>,
+}
+
+impl PlainSmtp {
+    pub fn routes(&self) -> Vec<SmtpRoute> {
+        self.routes.lock().unwrap().clone()
+    }
+}
+
+impl MailTransport for PlainSmtp {
+    fn send(
+        &self,
+        route: &SmtpRoute,
+        _network_settings: &NetworkSettings,
+        credentials: Credentials,
+        message: &Message,
+    ) -> Result<(), lettre::transport::smtp::Error> {
+        self.routes.lock().unwrap().push(route.clone());
+        SmtpTransport::builder_dangerous(&route.server)
+            .port(route.port)
+            .credentials(credentials)
+            .build()
+            .send(message)
+            .map(|_| ())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 时钟
+// ---------------------------------------------------------------------------
+
+/// 从 base 开始、随 tokio 时间前进的时钟；在 start_paused 的测试中随 advance 快进
+pub struct TestClock {
+    base: DateTime<Local>,
+    start: tokio::time::Instant,
+}
+
+impl TestClock {
+    pub fn new(base: DateTime<Local>) -> Self {
+        TestClock { base, start: tokio::time::Instant::now() }
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> DateTime<Local> {
+        self.base + (tokio::time::Instant::now() - self.start)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AppCore
+// ---------------------------------------------------------------------------
+
+pub const IDENTITY: AppIdentity = AppIdentity {
+    identifier: "com.example.rpa-core-test",
+    display_name: "测试应用",
+    features: AppFeatures { automation: false },
+};
+
+/// 只用打包的 spider_bin（即 FakeSpiderBin），不回退到 Python
+pub fn binary_backend() -> BackendOptions {
+    BackendOptions { backend: BackendChoice::Binary, fallback: None }
+}
+
+pub fn run_options(fetch_details: bool) -> RunOptions {
+    RunOptions { job: "manual".to_string(), fetch_details, backend: binary_backend() }
+}
+
+/// 列出全部公告的内置爬虫参数
+pub fn all_params() -> Value {
+    json!({ "page": 1, "title": "", "project_type": "" })
+}
+
+pub struct Harness {
+    pub core: Arc<AppCore>,
+    pub site: FakeSite,
+    pub smtp: SmtpSink,
+    pub spider_bin: Arc<FakeSpiderBin>,
+    pub mail: Arc<PlainSmtp>,
+    pub events: UnboundedReceiver<(String, Value)>,
+    dir: PathBuf,
+}
+
+impl Harness {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self::with_smtp_password(clock, SMTP_PASSWORD)
+    }
+
+    /// smtp_config.json 中保存指定的密码
+    pub fn with_smtp_password(clock: Arc<dyn Clock>, password: &str) -> Self {
+        Self::build(clock, password, None)
+    }
+
+    /// Python worker 使用系统的 Python 3 执行假 worker 脚本；系统没有 Python 3 时返回 None
+    pub fn with_python_worker(clock: Arc<dyn Clock>) -> Option<Self> {
+        Some(Self::build(clock, SMTP_PASSWORD, Some(python3()?)))
+    }
+
+    fn build(clock: Arc<dyn Clock>, password: &str, python: Option<String>) -> Self {
+        let dir = std::env::temp_dir().join(format!("rpa_core_test_{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let site = FakeSite::start();
+        let smtp = SmtpSink::start();
+
+        // 不限速，测试中立即发出请求
+        CrawlPolicy { min_page_delay_ms: 0, jitter_ms: 0, ..serde_json::from_value(json!({})).unwrap() }
+            .save(&dir)
+            .unwrap();
+        // spider_bin 必须是存在的可执行文件，实际执行由 FakeSpiderBin 代替
+        let binary_path = dummy_executable(&dir);
+        let mut runtime = RuntimeSettings { binary_path: binary_path.to_string_lossy().to_string(), ..Default::default() };
+        if let Some(python) = python {
+            runtime.python_path = python;
+            runtime.script_path = write_stub_worker(&dir, &site.base_url).to_string_lossy().to_string();
+        }
+        runtime.save(&dir).unwrap();
+        SmtpConfig {
+            server: "127.0.0.1".to_string(),
+            port: smtp.port,
+            username: SMTP_USERNAME.to_string(),
+            password: password.to_string(),
+        }
+        .save(&dir)
+        .unwrap();
+        // 用户插件覆盖同 id 的内置爬虫，让详情页指向假网站
+        let plugin_dir = dir.join("plugins").join(DEFAULT_SPIDER_ID);
+        std::fs::create_dir_all(&plugin_dir).unwrap();
+        std::fs::write(plugin_dir.join("plugin.json"), serde_json::to_vec_pretty(&site.plugin()).unwrap()).unwrap();
+
+        let spider_bin = Arc::new(FakeSpiderBin::new(&site.base_url));
+        let mail = Arc::new(PlainSmtp::default());
+        let services = Services { clock, processes: spider_bin.clone(), mail: mail.clone() };
+        let (sender, events) = mpsc::unbounded_channel();
+        let paths = AppPaths { app_data_dir: dir.clone(), log_dir: dir.join("logs"), resource_dir: None };
+        let core = AppCore::open_with_services(IDENTITY, paths, services, move |event, payload| {
+            let _ = sender.send((event.to_string(), payload));
+        })
+        .unwrap();
+
+        Harness { core: Arc::new(core), site, smtp, spider_bin, mail, events, dir }
+    }
+
+    pub fn app_data_dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 等待指定的事件之一，返回事件名和 payload（中间的其他事件被丢弃）
+    pub async fn next_event(&mut self, names: &[&str]) -> (String, Value) {
+        loop {
+            let (event, payload) = self.events.recv().await.expect("事件通道已关闭");
+            if names.contains(&event.as_str()) {
+                return (event, payload);
+            }
+        }
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        self.core.worker.shutdown();
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn dummy_executable(dir: &Path) -> PathBuf {
+    let path = dir.join(if cfg!(windows) { "spider_bin.exe" } else { "spider_bin" });
+    std::fs::File::create(&path).unwrap().write_all(b"#!/bin/sh\nexit 1\n").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    path
+}
+
+/// 读取归档文件
+pub fn read_file(path: &str) -> Vec<u8> {
+    let mut content = Vec::new();
+    std::fs::File::open(path).unwrap().read_to_end(&mut content).unwrap();
+    content
+}
@@ -0,0 +1,138 @@
+// 本机 HTTP API：令牌校验、检索接口的参数校验、不存在的对象和导出下载
+
+#![cfg(feature = "http-api")]
+
+mod common;
+
+use std::sync::Arc;
+
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use common::{all_params, run_options, Harness};
+use rpa_core::clock::SystemClock;
+use rpa_core::http_api::{ApiServer, ApiSettings};
+use rpa_core::spider_registry::DEFAULT_SPIDER_ID;
+
+const TOKEN: &str = "test-token-0123456789abcdef";
+
+struct Api {
+    base_url: String,
+    client: reqwest::Client,
+    _server: ApiServer,
+}
+
+impl Api {
+    async fn start(harness: &Harness) -> Self {
+        // 先占用一个空闲端口再释放，交给 API 服务监听
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let server = ApiServer::default();
+        let settings = ApiSettings { enabled: true, port, token: TOKEN.to_string() };
+        server.apply(harness.core.clone(), &settings).await.unwrap();
+        Api { base_url: format!("http://127.0.0.1:{}/api/v1", port), client: reqwest::Client::new(), _server: server }
+    }
+
+    async fn get(&self, path: &str, token: Option<&str>) -> (StatusCode, Value) {
+        let mut request = self.client.get(format!("{}{}", self.base_url, path));
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await.unwrap();
+        let status = response.status();
+        let body = response.text().await.unwrap();
+        (status, serde_json::from_str(&body).unwrap_or(Value::String(body)))
+    }
+}
+
+#[tokio::test]
+async fn requests_without_valid_token_are_rejected() {
+    let harness = Harness::new(Arc::new(SystemClock));
+    let api = Api::start(&harness).await;
+
+    assert_eq!(api.get("/announcements", None).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(api.get("/announcements", Some("wrong-token-0123456789")).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(api.get("/announcements", Some(TOKEN)).await.0, StatusCode::OK);
+    // OpenAPI 文档不需要令牌
+    assert_eq!(api.get("/openapi.json", None).await.0, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn search_rejects_out_of_range_pages() {
+    let harness = Harness::new(Arc::new(SystemClock));
+    let core = harness.core.clone();
+    tokio::task::spawn_blocking(move || core.run_spider(DEFAULT_SPIDER_ID, &run_options(false), all_params()).unwrap())
+        .await
+        .unwrap();
+    let api = Api::start(&harness).await;
+
+    for (query, field) in [("page=4294967295", "page"), ("page=0", "page"), ("page_size=1000", "page_size")] {
+        let (status, body) = api.get(&format!("/announcements?{}", query), Some(TOKEN)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}: {}", query, body);
+        assert_eq!(body["code"], "validation.failed");
+        assert_eq!(body["fields"][0]["field"], field);
+    }
+
+    // 数据库没有受到影响
+    let (status, body) = api.get("/announcements?q=管道&page=1&page_size=10", Some(TOKEN)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 1);
+    assert!(harness.core.store.search("", &Default::default(), 1, 10).is_ok());
+}
+
+#[tokio::test]
+async fn missing_objects_return_not_found() {
+    let harness = Harness::new(Arc::new(SystemClock));
+    let api = Api::start(&harness).await;
+
+    for (path, code) in [("/schedules/no-such-task", "schedule.not_found"), ("/announcements/999", "announcement.not_found")] {
+        let (status, body) = api.get(path, Some(TOKEN)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{}: {}", path, body);
+        assert_eq!(body["kind"], "not_found");
+        assert_eq!(body["code"], code);
+    }
+}
+
+/// 临时目录中导出接口留下的文件
+fn export_temp_files() -> Vec<std::path::PathBuf> {
+    std::fs::read_dir(std::env::temp_dir())
+        .unwrap()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("rpa_export_")))
+        .collect()
+}
+
+#[tokio::test]
+async fn export_streams_file_and_removes_it() {
+    let harness = Harness::new(Arc::new(SystemClock));
+    let core = harness.core.clone();
+    tokio::task::spawn_blocking(move || core.run_spider(DEFAULT_SPIDER_ID, &run_options(false), all_params()).unwrap())
+        .await
+        .unwrap();
+    let api = Api::start(&harness).await;
+    let before = export_temp_files();
+
+    let response = api
+        .client
+        .get(format!("{}/export?format=csv", api.base_url))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-export-rows"], common::ANNOUNCEMENTS.len().to_string().as_str());
+    assert!(response.headers()["content-disposition"].to_str().unwrap().ends_with(".csv\""));
+    let body = response.text().await.unwrap();
+    assert_eq!(body.lines().count(), common::ANNOUNCEMENTS.len() + 1);
+
+    // 响应体发送完后临时文件被删除
+    let mut leftover = Vec::new();
+    for _ in 0..50 {
+        leftover = export_temp_files().into_iter().filter(|path| !before.contains(path)).collect();
+        if leftover.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert!(leftover.is_empty(), "{:?}", leftover);
+}
@@ -0,0 +1,89 @@
+// 邮件：连接方式的选择、发件人地址，以及向本地 SMTP 服务器发送和认证失败
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{Harness, SMTP_USERNAME};
+use rpa_core::clock::SystemClock;
+use rpa_core::error::ErrorKind;
+use rpa_core::mailer::{self, SmtpSecurity};
+use rpa_core::network::NetworkSettings;
+use rpa_core::settings::SmtpConfig;
+
+fn smtp_config(server: &str, port: u16) -> SmtpConfig {
+    SmtpConfig { server: server.to_string(), port, username: "user".to_string(), password: "secret".to_string() }
+}
+
+#[test]
+fn route_uses_starttls_for_qq_and_port_587() {
+    let network = NetworkSettings::default();
+    assert_eq!(mailer::route(&smtp_config("smtp.qq.com", 465), &network).security, SmtpSecurity::StartTls);
+    assert_eq!(mailer::route(&smtp_config("smtp.example.com", 587), &network).security, SmtpSecurity::StartTls);
+    assert_eq!(mailer::route(&smtp_config("smtp.example.com", 465), &network).security, SmtpSecurity::Wrapper);
+}
+
+#[test]
+fn route_goes_through_proxy_unless_bypassed() {
+    let mut network = NetworkSettings {
+        proxy_enabled: true,
+        proxy_host: "127.0.0.1".to_string(),
+        proxy_port: 7890,
+        ..Default::default()
+    };
+    assert!(!mailer::route(&smtp_config("smtp.example.com", 465), &NetworkSettings::default()).via_proxy);
+    assert!(mailer::route(&smtp_config("smtp.example.com", 465), &network).via_proxy);
+    network.no_proxy = vec![".example.com".to_string()];
+    assert!(!mailer::route(&smtp_config("smtp.example.com", 465), &network).via_proxy);
+}
+
+#[test]
+fn from_address_completes_qq_numbers() {
+    assert_eq!(mailer::from_address("测试应用", "12345"), "测试应用 <12345@qq.com>");
+    assert_eq!(mailer::from_address("测试应用", "robot@example.com"), "测试应用 <robot@example.com>");
+}
+
+#[test]
+fn send_email_delivers_to_smtp_server() {
+    let harness = Harness::new(Arc::new(SystemClock));
+
+    harness.core.send_email("someone@example.com", "测试主题", "第一行正文\n第二行正文").unwrap();
+
+    let received = harness.smtp.received();
+    assert_eq!(received.len(), 1);
+    let mail = &received[0];
+    assert_eq!(mail.mail_from, SMTP_USERNAME);
+    assert_eq!(mail.rcpt_to, ["someone@example.com"]);
+    assert!(mail.header("From").unwrap().contains(SMTP_USERNAME));
+    assert_eq!(mail.subject(), "测试主题");
+    assert!(mail.body().contains("第一行正文"), "{}", mail.body());
+    assert!(mail.body().contains("第二行正文"), "{}", mail.body());
+
+    // 127.0.0.1 的非 587 端口按 SSL 连接，测试传输层记录了这一选择
+    let routes = harness.mail.routes();
+    assert_eq!(routes.len(), 1);
+    assert_eq!(routes[0].server, "127.0.0.1");
+    assert_eq!(routes[0].port, harness.smtp.port);
+    assert_eq!(routes[0].security, SmtpSecurity::Wrapper);
+    assert!(!routes[0].via_proxy);
+}
+
+#[test]
+fn rejected_credentials_map_to_auth_failed() {
+    let harness = Harness::with_smtp_password(Arc::new(SystemClock), "wrong-password");
+
+    let error = harness.core.send_email("someone@example.com", "测试主题", "正文").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Smtp);
+    assert_eq!(error.code, "smtp.auth_failed");
+    assert!(!error.hints.is_empty());
+    assert!(harness.smtp.received().is_empty());
+}
+
+#[test]
+fn invalid_recipient_is_rejected_before_connecting() {
+    let harness = Harness::new(Arc::new(SystemClock));
+
+    let error = harness.core.send_email("not-an-address", "测试主题", "正文").unwrap_err();
+    assert_eq!(error.code, "smtp.invalid_recipient");
+    assert!(harness.mail.routes().is_empty());
+}
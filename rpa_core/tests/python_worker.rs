@@ -0,0 +1,89 @@
+// 常驻 Python worker：用系统的 Python 3 执行假 worker 脚本，验证进程复用、错误分类和回退
+// 系统没有 Python 3 时这些测试直接通过
+
+mod common;
+
+use std::sync::Arc;
+
+use serde_json::json;
+
+use common::{all_params, Harness, ANNOUNCEMENTS, FAILING_TITLE, MISSING_MODULE_TITLE, NODE_MISSING_TITLE};
+use rpa_core::backend::{Backend, BackendChoice, BackendOptions, FallbackPolicy};
+use rpa_core::clock::SystemClock;
+use rpa_core::error::ErrorKind;
+use rpa_core::spider_registry::DEFAULT_SPIDER_ID;
+use rpa_core::RunOptions;
+
+fn options(backend: BackendChoice) -> RunOptions {
+    RunOptions { job: "manual".to_string(), fetch_details: false, backend: BackendOptions { backend, fallback: None } }
+}
+
+#[test]
+fn python_worker_is_started_once_and_reused() {
+    let Some(harness) = Harness::with_python_worker(Arc::new(SystemClock)) else { return };
+    let core = &harness.core;
+
+    let first = core.run_spider(DEFAULT_SPIDER_ID, &options(BackendChoice::Python), all_params()).unwrap();
+    assert_eq!(first.backend, Some(Backend::Python));
+    assert_eq!(first.new_count, ANNOUNCEMENTS.len());
+    let status = core.worker.status();
+    assert!(status.running);
+
+    let second = core.run_spider(DEFAULT_SPIDER_ID, &options(BackendChoice::Python), all_params()).unwrap();
+    assert_eq!(second.items.len(), ANNOUNCEMENTS.len());
+    assert_eq!(second.new_count, 0);
+
+    // 两次执行使用同一个 worker 进程，也没有用到 spider_bin
+    let after = core.worker.status();
+    assert_eq!(after.pid, status.pid);
+    assert_eq!(after.restarts, 0);
+    assert!(harness.spider_bin.calls().is_empty());
+}
+
+#[test]
+fn environment_error_falls_back_to_binary() {
+    let Some(harness) = Harness::with_python_worker(Arc::new(SystemClock)) else { return };
+    let core = &harness.core;
+    let params = json!({ "page": 1, "title": MISSING_MODULE_TITLE, "project_type": "" });
+
+    let output = core.run_spider(DEFAULT_SPIDER_ID, &options(BackendChoice::Auto), params.clone()).unwrap();
+    assert_eq!(output.backend, Some(Backend::Binary));
+    assert!(output.fallback_reason.unwrap().contains("no_such_module_for_stub_worker"));
+    assert_eq!(harness.spider_bin.calls().len(), 1);
+
+    // 明确选择 python 时不回退
+    let error = core.run_spider(DEFAULT_SPIDER_ID, &options(BackendChoice::Python), params).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Environment);
+    assert_eq!(error.code, "spider.no_backend");
+    assert_eq!(harness.spider_bin.calls().len(), 1);
+}
+
+#[test]
+fn binary_environment_error_falls_back_to_python() {
+    let Some(harness) = Harness::with_python_worker(Arc::new(SystemClock)) else { return };
+    let options = RunOptions {
+        job: "manual".to_string(),
+        fetch_details: false,
+        backend: BackendOptions { backend: BackendChoice::Binary, fallback: Some(FallbackPolicy::OnEnvironmentError) },
+    };
+    let params = json!({ "page": 1, "title": NODE_MISSING_TITLE, "project_type": "" });
+
+    let output = harness.core.run_spider(DEFAULT_SPIDER_ID, &options, params).unwrap();
+    assert_eq!(output.backend, Some(Backend::Python));
+    assert!(output.fallback_reason.unwrap().contains("未找到 Node.js"));
+    assert_eq!(harness.spider_bin.calls().len(), 1);
+}
+
+#[test]
+fn execution_error_is_not_retried_with_binary() {
+    let Some(harness) = Harness::with_python_worker(Arc::new(SystemClock)) else { return };
+    let core = &harness.core;
+
+    let params = json!({ "page": 1, "title": FAILING_TITLE, "project_type": "" });
+    let error = core.run_spider(DEFAULT_SPIDER_ID, &options(BackendChoice::Auto), params).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Network);
+    assert!(harness.spider_bin.calls().is_empty());
+    // 执行失败后 worker 继续可用
+    assert!(core.worker.status().running);
+    assert!(core.run_spider(DEFAULT_SPIDER_ID, &options(BackendChoice::Python), all_params()).is_ok());
+}
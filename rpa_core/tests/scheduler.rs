@@ -0,0 +1,141 @@
+// 定时任务：暂停 tokio 时间，用 TestClock 快进到执行时间，验证到点执行爬虫并发送邮件
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, TimeZone, Timelike};
+use serde_json::Value;
+
+use common::{all_params, binary_backend, Harness, TestClock, ANNOUNCEMENTS};
+use rpa_core::scheduler;
+use rpa_core::spider_registry::DEFAULT_SPIDER_ID;
+use rpa_core::SpiderConfig;
+
+fn base_time() -> DateTime<Local> {
+    Local.with_ymd_and_hms(2030, 6, 1, 8, 0, 0).unwrap()
+}
+
+fn config_at(time: DateTime<Local>, email: &str) -> SpiderConfig {
+    SpiderConfig {
+        spider_id: DEFAULT_SPIDER_ID.to_string(),
+        params: all_params(),
+        email: email.to_string(),
+        year: time.year() as u32,
+        month: time.month(),
+        day: time.day(),
+        hour: time.hour(),
+        minute: time.minute(),
+        second: time.second(),
+        enabled: true,
+        push_content_enabled: true,
+        push_content: "今日推送".to_string(),
+        fetch_details: false,
+        backend: binary_backend(),
+    }
+}
+
+/// 让出执行权，使后台任务处理已到期的计时器
+async fn settle() {
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+}
+
+#[test]
+fn plan_rejects_time_not_after_clock() {
+    let harness = Harness::new(Arc::new(TestClock::new(base_time())));
+    let core = &harness.core;
+    let now = core.now();
+
+    let past = scheduler::plan(core, &config_at(base_time() - chrono::Duration::seconds(1), ""), now).unwrap_err();
+    assert_eq!(past.code, "schedule.time_passed");
+    let job = scheduler::plan(core, &config_at(base_time() + chrono::Duration::seconds(1), ""), now).unwrap();
+    assert_eq!(job.time_label(), "2030-06-01 08:00:01");
+    assert_eq!(job.options.job, "scheduled:2030-06-01 08:00:01");
+}
+
+#[tokio::test(start_paused = true)]
+async fn scheduled_job_runs_at_next_run_and_emails_results() {
+    let mut harness = Harness::new(Arc::new(TestClock::new(base_time())));
+    let core = harness.core.clone();
+
+    let job = scheduler::plan(&core, &config_at(base_time() + chrono::Duration::hours(1), "someone@example.com"), core.now()).unwrap();
+    let info = scheduler::start(&core, job);
+    assert_eq!(info.next_run, "2030-06-01 09:00:00");
+
+    // 执行时间之前什么都不做
+    tokio::time::advance(Duration::from_secs(3599)).await;
+    settle().await;
+    assert!(harness.spider_bin.calls().is_empty());
+    assert_eq!(core.schedules.list().len(), 1);
+
+    // 到点执行爬虫、发出结果事件并发送邮件
+    let (event, payload) = harness.next_event(&["scheduled-task-result", "scheduled-task-error"]).await;
+    assert_eq!(event, "scheduled-task-result", "{}", payload);
+    assert!(core.now() >= base_time() + chrono::Duration::hours(1));
+    let raw: Value = serde_json::from_str(payload.as_str().unwrap()).unwrap();
+    assert_eq!(raw["data"]["records"].as_array().unwrap().len(), ANNOUNCEMENTS.len());
+    let (event, payload) = harness.next_event(&["email-sent", "email-error"]).await;
+    assert_eq!(event, "email-sent", "{}", payload);
+
+    assert_eq!(harness.spider_bin.calls().len(), 1);
+    let received = harness.smtp.received();
+    assert_eq!(received.len(), 1);
+    let body = received[0].body();
+    assert!(body.starts_with("今日推送"), "{}", body);
+    for (_, title, _, _) in ANNOUNCEMENTS {
+        assert!(body.contains(title), "{}", body);
+    }
+
+    let runs = core.store.list_runs(10).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].job, "scheduled:2030-06-01 09:00:00");
+
+    // 执行完后从已登记的任务中移除
+    settle().await;
+    assert!(core.schedules.get(&info.id).is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancelled_job_never_runs() {
+    let harness = Harness::new(Arc::new(TestClock::new(base_time())));
+    let core = harness.core.clone();
+
+    let job = scheduler::plan(&core, &config_at(base_time() + chrono::Duration::minutes(30), "someone@example.com"), core.now()).unwrap();
+    let info = scheduler::start(&core, job);
+    assert!(core.schedules.cancel(&info.id));
+    assert!(!core.schedules.cancel(&info.id));
+
+    tokio::time::advance(Duration::from_secs(7200)).await;
+    settle().await;
+    assert!(harness.spider_bin.calls().is_empty());
+    assert!(harness.smtp.received().is_empty());
+    assert!(core.schedules.list().is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn replaced_job_keeps_id_and_uses_new_time() {
+    let mut harness = Harness::new(Arc::new(TestClock::new(base_time())));
+    let core = harness.core.clone();
+
+    let first = scheduler::plan(&core, &config_at(base_time() + chrono::Duration::minutes(10), ""), core.now()).unwrap();
+    let info = scheduler::start(&core, first);
+    let second = scheduler::plan(&core, &config_at(base_time() + chrono::Duration::hours(2), ""), core.now()).unwrap();
+    let replaced = scheduler::replace(&core, &info.id, second).unwrap();
+    assert_eq!(replaced.id, info.id);
+    assert_eq!(replaced.next_run, "2030-06-01 10:00:00");
+
+    // 原来的执行时间已过，但任务还没有执行
+    tokio::time::advance(Duration::from_secs(3600)).await;
+    settle().await;
+    assert!(harness.spider_bin.calls().is_empty());
+    assert_eq!(core.schedules.list().len(), 1);
+
+    harness.next_event(&["scheduled-task-result"]).await;
+    assert!(core.now() >= base_time() + chrono::Duration::hours(2));
+    assert_eq!(harness.spider_bin.calls().len(), 1);
+    // 没有填写邮箱时不发送邮件
+    assert!(harness.smtp.received().is_empty());
+}
@@ -0,0 +1,124 @@
+// 通过假 spider_bin 抓取本地假网站：入库、去重、详情页和附件归档、候选人提取、执行历史
+
+mod common;
+
+use std::sync::Arc;
+
+use serde_json::json;
+
+use common::{all_params, read_file, run_options, Harness, ANNOUNCEMENTS, FAILING_TITLE, NODE_MISSING_TITLE};
+use rpa_core::clock::SystemClock;
+use rpa_core::error::ErrorKind;
+use rpa_core::spider_registry::DEFAULT_SPIDER_ID;
+
+#[test]
+fn run_spider_stores_announcements_and_archives_details() {
+    let harness = Harness::new(Arc::new(SystemClock));
+    let core = &harness.core;
+
+    let output = core.run_spider(DEFAULT_SPIDER_ID, &run_options(true), all_params()).unwrap();
+    assert_eq!(output.items.len(), ANNOUNCEMENTS.len());
+    assert_eq!(output.new_count, ANNOUNCEMENTS.len());
+    assert_eq!(output.detail_count, ANNOUNCEMENTS.len());
+
+    // 参数以 JSON 形式作为第一个参数传给 spider_bin，附带限速策略的页面请求间隔
+    let calls = harness.spider_bin.calls();
+    assert_eq!(calls.len(), 1);
+    let params: serde_json::Value = serde_json::from_str(&calls[0].args[0]).unwrap();
+    assert_eq!(
+        params,
+        json!({
+            "page": 1,
+            "title": "",
+            "project_type": "",
+            "page_delay": { "min_ms": 0, "jitter_ms": 0 },
+        })
+    );
+
+    let mut titles = Vec::new();
+    for id in &output.new_ids {
+        let announcement = core.store.get_announcement(*id).unwrap().unwrap();
+        titles.push(announcement.title.clone());
+        assert_eq!(
+            announcement.detail_url.as_deref(),
+            Some(format!("{}/cms/article/detail?id={}", harness.site.base_url, announcement.source_id).as_str())
+        );
+        assert!(announcement.detail_error.is_none(), "{:?}", announcement.detail_error);
+        assert!(announcement.detail_path.is_some());
+
+        assert_eq!(announcement.attachments.len(), 1);
+        let attachment = &announcement.attachments[0];
+        assert_eq!(attachment.file_name, "评标报告.pdf");
+        assert_eq!(read_file(&attachment.path), format!("%PDF-1.4 fake attachment {}", announcement.source_id).into_bytes());
+
+        let notice = announcement.candidate_notice.unwrap();
+        assert_eq!(notice.project_number.unwrap().value, format!("CNPC-2025-GD-{}", announcement.source_id));
+        let bidders: Vec<_> = notice.candidates.iter().map(|c| c.bidder.value.as_str()).collect();
+        assert_eq!(bidders, ["甲工程有限公司", "乙建设有限公司"]);
+        let price = notice.candidates[0].bid_price.as_ref().unwrap();
+        assert!((price.value.amount_yuan - 1_234_500.0).abs() < 0.01);
+    }
+    titles.sort();
+    let mut expected: Vec<_> = ANNOUNCEMENTS.iter().map(|(_, title, _, _)| title.to_string()).collect();
+    expected.sort();
+    assert_eq!(titles, expected);
+}
+
+#[test]
+fn repeated_run_reports_no_new_announcements() {
+    let harness = Harness::new(Arc::new(SystemClock));
+    let core = &harness.core;
+
+    let first = core.run_spider(DEFAULT_SPIDER_ID, &run_options(false), all_params()).unwrap();
+    assert_eq!(first.new_count, ANNOUNCEMENTS.len());
+    let second = core.run_spider(DEFAULT_SPIDER_ID, &run_options(false), all_params()).unwrap();
+    assert_eq!(second.items.len(), ANNOUNCEMENTS.len());
+    assert_eq!(second.new_count, 0);
+
+    let runs = core.store.list_runs(10).unwrap();
+    assert_eq!(runs.len(), 2);
+    assert!(runs.iter().all(|run| run.status == "success"));
+    assert!(runs.iter().any(|run| run.run_id == second.run_id && run.new_count == 0));
+}
+
+#[test]
+fn site_error_is_recorded_in_run_history() {
+    let harness = Harness::new(Arc::new(SystemClock));
+    let core = &harness.core;
+
+    let params = json!({ "page": 1, "title": FAILING_TITLE, "project_type": "" });
+    let error = core.run_spider(DEFAULT_SPIDER_ID, &run_options(false), params).unwrap_err();
+    // 网站返回 HTTP 错误时按网络错误处理，可以稍后重试
+    assert_eq!(error.kind, ErrorKind::Network);
+    assert_eq!(error.code, "network.request_failed");
+    assert!(error.retryable);
+
+    let runs = core.store.list_runs(10).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].status, "error");
+    assert!(runs[0].error.as_deref().unwrap_or_default().contains("500"));
+}
+
+#[test]
+fn binary_environment_error_is_reported_as_environment() {
+    let harness = Harness::new(Arc::new(SystemClock));
+
+    let params = json!({ "page": 1, "title": NODE_MISSING_TITLE, "project_type": "" });
+    let error = harness.core.run_spider(DEFAULT_SPIDER_ID, &run_options(false), params).unwrap_err();
+    // spider_bin 报告环境错误码时不按执行错误处理
+    assert_eq!(error.kind, ErrorKind::Environment);
+    assert_eq!(error.code, "spider.no_backend");
+    assert!(error.detail.as_deref().unwrap_or_default().contains("未找到 Node.js"), "{:?}", error.detail);
+    assert_eq!(harness.spider_bin.calls().len(), 1);
+}
+
+#[test]
+fn invalid_params_are_rejected_before_running() {
+    let harness = Harness::new(Arc::new(SystemClock));
+
+    let params = json!({ "page": 0, "title": "", "project_type": "" });
+    let error = harness.core.run_spider(DEFAULT_SPIDER_ID, &run_options(false), params).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Validation);
+    assert!(harness.spider_bin.calls().is_empty());
+    assert!(harness.core.store.list_runs(10).unwrap().is_empty());
+}
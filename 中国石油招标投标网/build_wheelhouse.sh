@@ -0,0 +1,33 @@
+#!/bin/bash
+
+# 下载爬虫依赖的 wheel 包到 wheelhouse/，随应用打包后可离线创建私有 Python 环境
+# 使用方法: ./build_wheelhouse.sh
+# 注意：wheel 与平台和 Python 版本相关，请在目标平台上、用目标 Python 版本执行
+
+set -e
+
+echo "🚀 开始下载依赖到 wheelhouse..."
+
+if ! command -v python3 &> /dev/null; then
+    echo "❌ 未找到 python3"
+    exit 1
+fi
+
+if [ ! -f "requirements.lock" ]; then
+    echo "❌ requirements.lock 文件不存在，请先执行 ./lock_requirements.sh"
+    exit 1
+fi
+
+# 清理旧的 wheel 包（保留 .gitkeep）
+echo "🧹 清理旧的 wheel 包..."
+mkdir -p wheelhouse
+find wheelhouse -name "*.whl" -delete
+find wheelhouse -name "*.tar.gz" -delete
+
+echo "📦 开始下载..."
+# 锁定文件已包含全部间接依赖，不再由 pip 解析
+python3 -m pip download -r requirements.lock -d wheelhouse --only-binary=:all: --no-deps
+
+count=$(find wheelhouse -name "*.whl" | wc -l | tr -d ' ')
+echo "✅ 已下载 $count 个 wheel 包到 wheelhouse/"
+echo "✨ 完成！"
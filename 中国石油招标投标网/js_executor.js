@@ -4,6 +4,37 @@
 const { JSEncrypt } = require('./jsencrypt.js');
 const { decrypt_params, decrypt_result } = require('./decryption.js');
 
+function callFunction(functionName, inputData) {
+    if (functionName === 'decrypt_params') {
+        return decrypt_params(inputData.data, inputData.localStorage);
+    } else if (functionName === 'decrypt_result') {
+        return decrypt_result(inputData.res, inputData.localStorage);
+    }
+    throw new Error(`未知函数: ${functionName}`);
+}
+
+// 常驻模式：每行一个 JSON 请求 {"fn": ..., "input": ...}，每行返回一个 JSON 响应
+// 供 spiders.py 的 worker 模式复用同一个 Node 进程，避免每页启动两次 Node
+if (process.argv[2] === '--serve') {
+    const readline = require('readline');
+    const rl = readline.createInterface({ input: process.stdin });
+    rl.on('line', (line) => {
+        if (!line.trim()) {
+            return;
+        }
+        let response;
+        try {
+            const request = JSON.parse(line);
+            response = { ok: true, result: callFunction(request.fn, request.input) };
+        } catch (error) {
+            response = { ok: false, error: error.message, type: error.constructor.name };
+        }
+        process.stdout.write(JSON.stringify(response) + '\n');
+    });
+    rl.on('close', () => process.exit(0));
+    return;
+}
+
 // 从命令行参数读取
 const args = process.argv.slice(2);
 if (args.length < 2) {
@@ -16,16 +47,9 @@ const inputData = JSON.parse(args[1]);
 
 let result;
 try {
-    if (functionName === 'decrypt_params') {
-        result = decrypt_params(inputData.data, inputData.localStorage);
-    } else if (functionName === 'decrypt_result') {
-        result = decrypt_result(inputData.res, inputData.localStorage);
-    } else {
-        throw new Error(`未知函数: ${functionName}`);
-    }
+    result = callFunction(functionName, inputData);
     console.log(JSON.stringify(result));
 } catch (error) {
     console.error(JSON.stringify({error: error.message, type: error.constructor.name}));
     process.exit(1);
 }
-
@@ -0,0 +1,37 @@
+#!/bin/bash
+
+# 把 requirements.txt 解析为完整锁定的 requirements.lock（包括间接依赖和哈希）
+# 使用方法: ./lock_requirements.sh
+# 注意：解析结果与平台和 Python 版本相关，请在目标平台上、用目标 Python 版本执行，生成后提交 requirements.lock
+
+set -e
+
+echo "🚀 开始生成 requirements.lock..."
+
+if ! command -v python3 &> /dev/null; then
+    echo "❌ 未找到 python3"
+    exit 1
+fi
+
+if [ ! -f "requirements.txt" ]; then
+    echo "❌ requirements.txt 文件不存在"
+    exit 1
+fi
+
+# 检查 pip-tools 是否安装
+if ! python3 -m piptools --help &> /dev/null; then
+    echo "❌ pip-tools 未安装，正在安装..."
+    python3 -m pip install pip-tools
+fi
+
+python3 -m piptools compile \
+    --quiet \
+    --generate-hashes \
+    --strip-extras \
+    --no-emit-index-url \
+    --output-file requirements.lock \
+    requirements.txt
+
+count=$(grep -cE '^[A-Za-z0-9]' requirements.lock | tr -d ' ')
+echo "✅ 已锁定 $count 个包到 requirements.lock"
+echo "✨ 完成！"
@@ -9,6 +9,7 @@ os.environ['PYTHONWARNINGS'] = 'ignore'
 import requests
 import re
 import time
+import random
 from fake_useragent import UserAgent
 from pprint import pp, pprint
 import ddddocr
@@ -22,9 +23,76 @@ try:
     urllib3.disable_warnings(urllib3.exceptions.NotOpenSSLWarning)
 except:
     pass
+
+
+class RuntimeEnvironmentError(Exception):
+    """运行环境问题（如找不到 Node.js），与网站返回错误区分，worker 以 ENVIRONMENT_ERROR_CODE 报告"""
+
+
+# worker 协议的错误码：环境错误时 Rust 端可以改用打包版本，执行错误直接返回
+EXECUTION_ERROR_CODE = -32000
+ENVIRONMENT_ERROR_CODE = -32001
+
+
+class PersistentJSContext(object):
+    """常驻 Node 进程的 JS 上下文（js_executor.js --serve），按行收发 JSON"""
+    def __init__(self, node_path, executor_path, script_dir):
+        self.node_path = node_path
+        self.executor_path = executor_path
+        self.script_dir = script_dir
+        self.proc = None
+
+    def _ensure_started(self):
+        if self.proc is not None and self.proc.poll() is None:
+            return
+        try:
+            self.proc = subprocess.Popen(
+                [self.node_path, self.executor_path, '--serve'],
+                cwd=self.script_dir,
+                stdin=subprocess.PIPE,
+                stdout=subprocess.PIPE,
+                stderr=subprocess.DEVNULL,
+                text=True,
+                encoding='utf-8',
+                bufsize=1,
+            )
+        except FileNotFoundError:
+            raise RuntimeEnvironmentError(f"未找到 Node.js 可执行文件: {self.node_path}")
+
+    def call(self, function_name, *args):
+        """调用 JavaScript 函数"""
+        if function_name == 'decrypt_params':
+            input_data = {'data': args[0], 'localStorage': args[1]}
+        elif function_name == 'decrypt_result':
+            input_data = {'res': args[0], 'localStorage': args[1]}
+        else:
+            raise ValueError(f"未知函数: {function_name}")
+
+        self._ensure_started()
+        self.proc.stdin.write(json.dumps({'fn': function_name, 'input': input_data}) + '\n')
+        self.proc.stdin.flush()
+        line = self.proc.stdout.readline()
+        if not line:
+            # Node 进程意外退出，下次调用时重新启动
+            self.proc = None
+            raise Exception("JavaScript 执行失败: Node 进程已退出")
+        response = json.loads(line)
+        if not response.get('ok'):
+            raise Exception(f"JavaScript 执行失败: {response.get('error', '未知错误')}")
+        return response['result']
+
+    def close(self):
+        if self.proc is not None and self.proc.poll() is None:
+            self.proc.stdin.close()
+            self.proc.wait(timeout=5)
+        self.proc = None
+
+
 class Spider(object):
-    def __init__(self):
+    def __init__(self, persistent_js=False):
         self.ctx = None
+        # worker 模式下复用同一个 Node 进程，避免每页启动两次 Node
+        self.persistent_js = persistent_js
         self.project_type_map= {
             "全部": "",
             "物资": "0001",
@@ -35,6 +103,17 @@ class Spider(object):
         # 获取脚本目录和 Node.js 执行器路径
         self.script_dir = os.path.dirname(os.path.abspath(__file__))
         self.js_executor = os.path.join(self.script_dir, 'js_executor.js')
+        # Rust 端传入的页面请求间隔 {"min_ms": ..., "jitter_ms": ...}，每次 run 重新设置
+        self.page_delay = None
+        self.page_requested = False
+
+    def wait_page_delay(self):
+        """同一次执行内两次页面请求之间按 page_delay 等待，第一次请求前的等待由 Rust 端限速器负责"""
+        if self.page_requested and self.page_delay:
+            delay_ms = int(self.page_delay.get('min_ms', 0)) + random.randint(0, int(self.page_delay.get('jitter_ms', 0)))
+            time.sleep(delay_ms / 1000)
+        self.page_requested = True
+
     def get_random_headers(self):
         """生成带有随机 User-Agent 的请求头"""
         return {
@@ -104,7 +183,7 @@ class Spider(object):
         """使用 Node.js 执行 JavaScript，返回一个类似 execjs 的上下文对象"""
         node_path = self._find_node()
         if not node_path:
-            raise Exception(
+            raise RuntimeEnvironmentError(
                 "未找到 Node.js。\n"
                 "请安装 Node.js：\n"
                 "1. 访问 https://nodejs.org/ 下载安装\n"
@@ -157,8 +236,10 @@ class Spider(object):
                 except json.JSONDecodeError as e:
                     raise Exception(f"解析 JavaScript 返回结果失败: {e}, 输出: {result.stdout if 'result' in locals() else 'N/A'}")
                 except FileNotFoundError:
-                    raise Exception(f"未找到 Node.js 可执行文件: {self.node_path}")
+                    raise RuntimeEnvironmentError(f"未找到 Node.js 可执行文件: {self.node_path}")
         
+        if self.persistent_js:
+            return PersistentJSContext(node_path, self.js_executor, self.script_dir)
         return JSContext(node_path, self.js_executor, self.script_dir)
 
 
@@ -175,11 +256,13 @@ class Spider(object):
         decrypt_params = self.ctx.call("decrypt_params", data,localStorage)
 
         request_url= "https://www.cnpcbidding.com/cms/article/page"
+        self.wait_page_delay()
         response = requests.post(request_url, headers=headers, data=decrypt_params)
         return response
 
     def get_captcha(self,headers=None):
         qr_img_url = "https://www.cnpcbidding.com/cms/validateCode/undefined"
+        self.wait_page_delay()
         response = requests.get(qr_img_url, headers=headers)
         img_data=response.json()['data']
         return img_data
@@ -207,7 +290,7 @@ class Spider(object):
 
     def loop_handle_captcha(self,headers=None):
         loop_num = 10
-        while True:
+        while loop_num > 0:
             # 获取验证码base64信息
             img_data = self.get_captcha(headers)
             # 调用ddddocr识别验证码
@@ -220,13 +303,18 @@ class Spider(object):
                 return verify_flag
             else:
                 loop_num = loop_num - 1
+        # 重试耗尽，返回 None 由 Rust 端按验证码失败处理
+        return None
 
         
 
     def run(self,request_params:dict=None):
+        self.page_delay = (request_params or {}).get('page_delay')
+        self.page_requested = False
         headers = self.get_random_headers()
         localStorage = self.get_local_storage(headers)
-        self.ctx = self.read_js_code()
+        if self.ctx is None or not self.persistent_js:
+            self.ctx = self.read_js_code()
         encrypt_data_response = self.get_encrypt_data(request_params,headers,localStorage)
         if '"code":"511"' in encrypt_data_response.text:
             verify_flag = self.loop_handle_captcha(headers)
@@ -247,9 +335,80 @@ class Spider(object):
 
 
 
+def run_worker():
+    """
+    常驻 worker 模式：从 stdin 按行读取 JSON-RPC 请求，向 stdout 按行写入响应。
+    ddddocr 模型和 Node 进程只加载一次，由 Rust 端保持进程常驻并复用。
+
+    请求: {"jsonrpc": "2.0", "id": 1, "method": "run" | "ping" | "shutdown", "params": {...}}
+    响应: {"jsonrpc": "2.0", "id": 1, "result": ...} 或 {"jsonrpc": "2.0", "id": 1, "error": {...}}
+    run 失败时错误码为 ENVIRONMENT_ERROR_CODE（依赖包无法导入、找不到 Node.js）或 EXECUTION_ERROR_CODE（其他错误）
+    """
+    # stdout 专用于协议，其余输出一律转到 stderr
+    protocol_out = sys.stdout
+    sys.stdout = sys.stderr
+
+    def reply(request_id, result=None, error=None):
+        message = {"jsonrpc": "2.0", "id": request_id}
+        if error is not None:
+            message["error"] = error
+        else:
+            message["result"] = result
+        protocol_out.write(json.dumps(message, ensure_ascii=False) + "\n")
+        protocol_out.flush()
+
+    spider = Spider(persistent_js=True)
+    reply(None, {"ready": True, "pid": os.getpid()})
+
+    for line in sys.stdin:
+        line = line.strip()
+        if not line:
+            continue
+        try:
+            request = json.loads(line)
+        except json.JSONDecodeError as e:
+            reply(None, error={"code": -32700, "message": f"JSON 解析失败: {e}"})
+            continue
+
+        request_id = request.get("id")
+        method = request.get("method")
+        if method == "ping":
+            reply(request_id, {"pong": True})
+        elif method == "shutdown":
+            reply(request_id, {"shutdown": True})
+            break
+        elif method == "run":
+            try:
+                result = spider.run(request.get("params") or {})
+                if not isinstance(result, dict):
+                    result = {"result": str(result)}
+                reply(request_id, result)
+            except (ImportError, RuntimeEnvironmentError) as e:
+                reply(request_id, error={
+                    "code": ENVIRONMENT_ERROR_CODE,
+                    "message": str(e),
+                    "data": {"type": type(e).__name__},
+                })
+            except Exception as e:
+                reply(request_id, error={
+                    "code": EXECUTION_ERROR_CODE,
+                    "message": str(e),
+                    "data": {"type": type(e).__name__},
+                })
+        else:
+            reply(request_id, error={"code": -32601, "message": f"未知方法: {method}"})
+
+    if isinstance(spider.ctx, PersistentJSContext):
+        spider.ctx.close()
+
+
 if __name__ == "__main__":
     import sys
     import json
+
+    if len(sys.argv) > 1 and sys.argv[1] == "--worker":
+        run_worker()
+        sys.exit(0)
     
     # 如果提供了命令行参数（JSON 格式），则使用它
     if len(sys.argv) > 1:
@@ -277,11 +436,21 @@ if __name__ == "__main__":
             print(json.dumps(result, ensure_ascii=False))
         else:
             print(json.dumps({"result": str(result)}, ensure_ascii=False))
+    except (ImportError, RuntimeEnvironmentError) as e:
+        # 运行环境问题，错误码与 worker 协议相同，Rust 端据此改用其他后端
+        error_result = {
+            "error": str(e),
+            "type": type(e).__name__,
+            "code": ENVIRONMENT_ERROR_CODE,
+        }
+        print(json.dumps(error_result, ensure_ascii=False), file=sys.stderr)
+        sys.exit(1)
     except Exception as e:
         # 如果执行出错，返回错误信息
         error_result = {
             "error": str(e),
-            "type": type(e).__name__
+            "type": type(e).__name__,
+            "code": EXECUTION_ERROR_CODE,
         }
         print(json.dumps(error_result, ensure_ascii=False), file=sys.stderr)
         sys.exit(1)